@@ -76,4 +76,86 @@ pub enum ErrorCode {
     DestionationOverflow, // 6035
     #[msg("Strategy cannot be closed if the collateral vault is not empty")]
     VaultNotEmpty, // 6036
+    #[msg("Invalid leverage")]
+    InvalidLeverage, // 6037
+    #[msg("Exit order can never be filled")]
+    InvalidExitOrder, // 6038
+    #[msg("Execution fee exceeds the amount prefunded by the trader")]
+    ExecutionFeeExceeded, // 6039
+    #[msg("Withdrawal request has not been filled yet")]
+    WithdrawalNotFilled, // 6040
+    #[msg("Share price moved beyond the allowed slippage")]
+    SlippageExceeded, // 6041
+    #[msg("The first deposit into a vault is below the minimum")]
+    FirstDepositTooSmall, // 6042
+    #[msg("Deposit would exceed the vault's deposit cap")]
+    DepositCapExceeded, // 6043
+    #[msg("Deposit would exceed the per-wallet deposit cap")]
+    WalletDepositCapExceeded, // 6044
+    #[msg("A depositor account is required while the vault has a per-wallet cap")]
+    MissingDepositor, // 6045
+    #[msg("Borrow would push the vault's utilization above the maximum")]
+    MaxUtilizationExceeded, // 6046
+    #[msg("Invalid utilization")]
+    InvalidUtilization, // 6047
+    #[msg("Deposits are paused for this vault")]
+    VaultDepositsPaused, // 6048
+    #[msg("Withdrawals are paused for this vault")]
+    VaultWithdrawalsPaused, // 6049
+    #[msg("Borrowing is paused for this vault")]
+    VaultBorrowsPaused, // 6050
+    #[msg("Opening positions is paused for this pool")]
+    PoolOpensPaused, // 6051
+    #[msg("Pool is reduce-only")]
+    PoolReduceOnly, // 6052
+    #[msg("Invalid status")]
+    InvalidStatus, // 6053
+    #[msg("Fee exceeds the maximum")]
+    InvalidFee, // 6054
+    #[msg("Mint has features rejected by the mint policy")]
+    MintNotAllowed, // 6055
+    #[msg("Invalid mint policy")]
+    InvalidMintPolicy, // 6056
+    #[msg("Not enough share price history for the TWAP window")]
+    InsufficientPriceHistory, // 6057
+    #[msg("Invalid TWAP window")]
+    InvalidTwapWindow, // 6058
+    #[msg("Invalid vesting period")]
+    InvalidVestingPeriod, // 6059
+    #[msg("Invalid LP cooldown")]
+    InvalidLpCooldown, // 6060
+    #[msg("Withdrawals are restricted until the LP cooldown ends")]
+    LpCooldownActive, // 6061
+    #[msg("Wallet is not allowlisted for this vault")]
+    WalletNotAllowlisted, // 6062
+    #[msg("Transfer hook invoked outside of a transfer")]
+    NotTransferring, // 6063
+    #[msg("Insufficient staked shares")]
+    InsufficientStake, // 6064
+    #[msg("Vault is decommissioned")]
+    VaultDecommissioned, // 6065
+    #[msg("Vault is not decommissioned")]
+    VaultNotDecommissioned, // 6066
+    #[msg("Vault still has shares, borrows or queued withdrawals outstanding")]
+    VaultNotWoundDown, // 6067
+    #[msg("Invalid successor vault")]
+    InvalidSuccessorVault, // 6068
+    #[msg("Fee shares account is required while the vault charges fees")]
+    MissingFeeSharesAccount, // 6069
+    #[msg("Withdrawal queue accounts are required once the vault has a queue")]
+    MissingWithdrawalQueueAccounts, // 6070
+    #[msg("Order fee is below the protocol's open fee")]
+    InsufficientOrderFee, // 6071
+    #[msg("Withdrawal request is not next in the queue")]
+    WithdrawalRequestNotNext, // 6072
+    #[msg("Shares mint is required once the vault has a share price history")]
+    MissingSharesMint, // 6073
+    #[msg("Share price history is required once the vault has one")]
+    MissingSharePriceHistory, // 6074
+    #[msg("Order price rounds to zero at the price decimals")]
+    ZeroPrice, // 6075
+    #[msg("Batch has no operations")]
+    EmptyBatch, // 6076
+    #[msg("Remaining accounts left over after the batch")]
+    UnusedRemainingAccounts, // 6077
 }
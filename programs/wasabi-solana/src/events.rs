@@ -1,5 +1,5 @@
 use {
-    crate::{close_position_cleanup::CloseAmounts, LpVault, Position},
+    crate::{close_position_cleanup::CloseAmounts, LimitOpenOrder, LpVault, Position},
     anchor_lang::prelude::*,
 };
 
@@ -126,6 +126,47 @@ pub struct ExitOrderCancelled {
     pub position_id: Pubkey,
 }
 
+#[event]
+pub struct LimitOrderPlaced {
+    pub order: Pubkey,
+    pub trader: Pubkey,
+    pub pool: Pubkey,
+    pub down_payment: u64,
+    pub fee: u64,
+    pub leverage: u64,
+    pub maker_amount: u64,
+    pub taker_amount: u64,
+    pub expiration: i64,
+}
+
+impl LimitOrderPlaced {
+    pub fn new(order: &Account<'_, LimitOpenOrder>) -> Self {
+        Self {
+            order: order.key(),
+            trader: order.trader,
+            pool: order.pool,
+            down_payment: order.down_payment,
+            fee: order.fee,
+            leverage: order.leverage,
+            maker_amount: order.maker_amount,
+            taker_amount: order.taker_amount,
+            expiration: order.expiration,
+        }
+    }
+}
+
+#[event]
+pub struct LimitOrderCancelled {
+    pub order: Pubkey,
+    pub trader: Pubkey,
+}
+
+#[event]
+pub struct LimitOrderExecuted {
+    pub order: Pubkey,
+    pub position_id: Pubkey,
+}
+
 #[event]
 pub struct PositionClosedWithOrder {
     pub side: String,
@@ -256,3 +297,200 @@ pub struct StrategyClaim {
     pub collateral: Pubkey,
     pub amount: i64,
 }
+
+#[event]
+pub struct ExitFeeCharged {
+    // The shares mint
+    pub vault: Pubkey,
+    // The wallet that withdrew during the cooldown
+    pub owner: Pubkey,
+    pub fee: u64,
+}
+
+#[event]
+pub struct WithdrawalRequested {
+    // The shares_mint`
+    pub vault: Pubkey,
+    pub owner: Pubkey,
+    pub request: Pubkey,
+    pub request_id: u64,
+    pub assets: u64,
+    pub shares: u64,
+}
+
+#[event]
+pub struct WithdrawalQueueReserved {
+    // The shares_mint`
+    pub vault: Pubkey,
+    pub assets: u64,
+    pub reserved_assets: u64,
+}
+
+#[event]
+pub struct WithdrawalFilled {
+    // The shares_mint`
+    pub vault: Pubkey,
+    pub owner: Pubkey,
+    pub request: Pubkey,
+    pub request_id: u64,
+    pub assets: u64,
+    pub shares: u64,
+}
+
+#[event]
+pub struct WithdrawalClaimed {
+    // The shares_mint`
+    pub vault: Pubkey,
+    pub owner: Pubkey,
+    pub request: Pubkey,
+    pub assets: u64,
+}
+
+#[event]
+pub struct VaultDepositCapsUpdated {
+    // The shares_mint`
+    pub vault: Pubkey,
+    pub deposit_cap: u64,
+    pub wallet_deposit_cap: u64,
+}
+
+#[event]
+pub struct VaultMaxUtilizationUpdated {
+    // The shares_mint`
+    pub vault: Pubkey,
+    pub max_utilization_bps: u16,
+}
+
+#[event]
+pub struct LpVaultStatusUpdated {
+    // The shares_mint`
+    pub vault: Pubkey,
+    pub statuses: u16,
+}
+
+#[event]
+pub struct PoolStatusUpdated {
+    pub pool: Pubkey,
+    pub statuses: u16,
+}
+
+#[event]
+pub struct VaultFeesAccrued {
+    // The shares_mint`
+    pub vault: Pubkey,
+    pub performance_fee: u64,
+    pub management_fee: u64,
+    pub shares: u64,
+}
+
+#[event]
+pub struct VaultFeesUpdated {
+    // The shares_mint`
+    pub vault: Pubkey,
+    pub fee_shares_account: Pubkey,
+    pub performance_fee_bps: u16,
+    pub management_fee_bps: u16,
+}
+
+#[event]
+pub struct VaultAllowlistUpdated {
+    // The shares_mint`
+    pub vault: Pubkey,
+    pub allowlist_enabled: bool,
+}
+
+#[event]
+pub struct WalletAllowlistUpdated {
+    // The shares_mint`
+    pub vault: Pubkey,
+    pub wallet: Pubkey,
+    pub allowed: bool,
+}
+
+#[event]
+pub struct VaultCooldownUpdated {
+    // The shares_mint`
+    pub vault: Pubkey,
+    pub lp_cooldown: i64,
+    pub exit_fee_bps: u16,
+}
+
+#[event]
+pub struct VaultVestingPeriodUpdated {
+    // The shares_mint`
+    pub vault: Pubkey,
+    pub vesting_period: i64,
+}
+
+#[event]
+pub struct MintPolicyUpdated {
+    pub rejected_features: u16,
+}
+
+#[event]
+pub struct MintAllowlistUpdated {
+    pub mint: Pubkey,
+    pub allowed: bool,
+}
+
+#[event]
+pub struct RewardPoolToppedUp {
+    pub reward_pool: Pubkey,
+    pub amount: u64,
+    pub emission_rate: u64,
+}
+
+#[event]
+pub struct SharesStaked {
+    pub reward_pool: Pubkey,
+    pub owner: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct SharesUnstaked {
+    pub reward_pool: Pubkey,
+    pub owner: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct RewardsClaimed {
+    pub reward_pool: Pubkey,
+    pub owner: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct VaultDecommissioned {
+    // The shares_mint`
+    pub vault: Pubkey,
+}
+
+#[event]
+pub struct VaultClosed {
+    // The shares_mint`
+    pub vault: Pubkey,
+    // Assets left in the vault, swept to the authority
+    pub swept: u64,
+}
+
+#[event]
+pub struct SuccessorVaultSet {
+    // The shares_mint` of the decommissioned vault
+    pub vault: Pubkey,
+    // The shares_mint` of the successor
+    pub successor: Pubkey,
+}
+
+#[event]
+pub struct SharesMigrated {
+    // The shares_mint` of the decommissioned vault
+    pub vault: Pubkey,
+    // The shares_mint` of the successor
+    pub successor: Pubkey,
+    pub owner: Pubkey,
+    pub assets: u64,
+    pub shares: u64,
+    pub successor_shares: u64,
+}
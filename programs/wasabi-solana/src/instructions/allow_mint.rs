@@ -0,0 +1,54 @@
+use {
+    crate::{
+        error::ErrorCode,
+        events::MintAllowlistUpdated,
+        state::{AllowedMint, Permission},
+    },
+    anchor_lang::prelude::*,
+    anchor_spl::token_interface::Mint,
+};
+
+// Approved mints skip the `MintPolicy` when an LP Vault or pool is initialized with them.
+
+#[derive(Accounts)]
+pub struct AllowMint<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"super_admin"],
+        bump,
+        has_one = authority,
+        constraint = super_admin.is_super_authority @ ErrorCode::InvalidPermissions
+    )]
+    pub super_admin: Account<'info, Permission>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"allowed_mint", mint.key().as_ref()],
+        bump,
+        space = 8 + std::mem::size_of::<AllowedMint>(),
+    )]
+    pub allowed_mint: Account<'info, AllowedMint>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> AllowMint<'info> {
+    pub fn allow_mint(&mut self, bumps: &AllowMintBumps) -> Result<()> {
+        self.allowed_mint.set_inner(AllowedMint {
+            mint: self.mint.key(),
+            bump: bumps.allowed_mint,
+        });
+
+        emit!(MintAllowlistUpdated {
+            mint: self.mint.key(),
+            allowed: true,
+        });
+
+        Ok(())
+    }
+}
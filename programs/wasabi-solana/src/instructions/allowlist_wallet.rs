@@ -0,0 +1,61 @@
+use {
+    crate::{
+        error::ErrorCode,
+        events::WalletAllowlistUpdated,
+        state::{AllowlistedWallet, LpVault, Permission},
+    },
+    anchor_lang::prelude::*,
+};
+
+// Approved wallets may deposit into, and receive the shares of, an allowlisted LP Vault.
+
+#[derive(Accounts)]
+pub struct AllowlistWallet<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(has_one = authority)]
+    pub permission: Account<'info, Permission>,
+
+    pub lp_vault: Account<'info, LpVault>,
+
+    /// CHECK: The wallet being approved
+    pub wallet: AccountInfo<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"allowlisted_wallet", lp_vault.key().as_ref(), wallet.key().as_ref()],
+        bump,
+        space = 8 + std::mem::size_of::<AllowlistedWallet>(),
+    )]
+    pub allowlisted_wallet: Account<'info, AllowlistedWallet>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> AllowlistWallet<'info> {
+    pub fn validate(ctx: &Context<AllowlistWallet>) -> Result<()> {
+        require!(
+            ctx.accounts.permission.can_manage_vaults(),
+            ErrorCode::InvalidPermissions
+        );
+        Ok(())
+    }
+
+    pub fn allowlist_wallet(&mut self, bumps: &AllowlistWalletBumps) -> Result<()> {
+        self.allowlisted_wallet.set_inner(AllowlistedWallet {
+            lp_vault: self.lp_vault.key(),
+            wallet: self.wallet.key(),
+            bump: bumps.allowlisted_wallet,
+        });
+
+        emit!(WalletAllowlistUpdated {
+            vault: self.lp_vault.shares_mint,
+            wallet: self.wallet.key(),
+            allowed: true,
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,347 @@
+use {
+    crate::{
+        error::ErrorCode,
+        events::{ExitOrder, ExitOrderCancelled},
+        utils::transfer_lamports_from_pda,
+        BasePool, DebtController, ExitOrderPrice, ExitOrderType, GlobalSettings, OrderSide,
+        Position, StopLossOrder, TakeProfitOrder, DEFAULT_PRICE_DECIMALS,
+    },
+    anchor_lang::{
+        prelude::*,
+        system_program::{self, Allocate, Assign, CreateAccount, Transfer},
+    },
+    anchor_spl::token_interface::Mint,
+};
+
+// Only the Position's trader can manage its orders. Every operation reads its accounts from
+// `remaining_accounts`, in the order the operations are given:
+// - `Set`:    [position, pool, currency, collateral, order (mut)]
+// - `Cancel`: [position, order (mut)]
+// No accounts may be left over once every operation has read its own.
+
+#[derive(Accounts)]
+pub struct BatchUpdateExitOrders<'info> {
+    #[account(mut)]
+    pub trader: Signer<'info>,
+
+    #[account(
+        seeds = [b"debt_controller"],
+        bump,
+    )]
+    pub debt_controller: Account<'info, DebtController>,
+
+    #[account(
+        seeds = [b"global_settings"],
+        bump,
+    )]
+    pub global_settings: Account<'info, GlobalSettings>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub enum ExitOrderAction {
+    /// Creates the order or updates it if it already exists
+    Set {
+        maker_amount: u64,
+        taker_amount: u64,
+        execution_fee: u64,
+    },
+    /// Closes the order, refunding its rent and execution fee to the trader
+    Cancel,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ExitOrderOp {
+    pub order_type: ExitOrderType,
+    pub action: ExitOrderAction,
+}
+
+impl<'info> BatchUpdateExitOrders<'info> {
+    fn next_account(
+        accounts: &mut impl Iterator<Item = &'info AccountInfo<'info>>,
+    ) -> Result<&'info AccountInfo<'info>> {
+        accounts
+            .next()
+            .ok_or_else(|| error!(anchor_lang::error::ErrorCode::AccountNotEnoughKeys))
+    }
+
+    fn load_position(
+        &self,
+        position_info: &'info AccountInfo<'info>,
+    ) -> Result<Account<'info, Position>> {
+        let position = Account::<Position>::try_from(position_info)?;
+        require_keys_eq!(
+            position.trader,
+            self.trader.key(),
+            ErrorCode::IncorrectOwner
+        );
+        Ok(position)
+    }
+
+    /// Returns the order's bump after checking it is the PDA for `position`
+    fn validate_order_address(
+        order_type: ExitOrderType,
+        position: &Pubkey,
+        order_info: &AccountInfo,
+    ) -> Result<u8> {
+        let (expected, bump) =
+            Pubkey::find_program_address(&[order_type.seed(), position.as_ref()], &crate::ID);
+        require_keys_eq!(expected, order_info.key(), ErrorCode::InvalidPubkey);
+        Ok(bump)
+    }
+
+    /// Creates the order PDA, mirroring Anchor's `init` for accounts that may hold lamports
+    fn create_order_account(
+        &self,
+        order_type: ExitOrderType,
+        position: &Pubkey,
+        order_info: &AccountInfo<'info>,
+        bump: u8,
+    ) -> Result<()> {
+        let space = 8 + match order_type {
+            ExitOrderType::TakeProfit => std::mem::size_of::<TakeProfitOrder>(),
+            ExitOrderType::StopLoss => std::mem::size_of::<StopLossOrder>(),
+        };
+        let rent = Rent::get()?.minimum_balance(space);
+        let signer_seeds: &[&[&[u8]]] = &[&[order_type.seed(), position.as_ref(), &[bump]]];
+
+        if order_info.lamports() == 0 {
+            let cpi_ctx = CpiContext::new_with_signer(
+                self.system_program.to_account_info(),
+                CreateAccount {
+                    from: self.trader.to_account_info(),
+                    to: order_info.clone(),
+                },
+                signer_seeds,
+            );
+            system_program::create_account(cpi_ctx, rent, space as u64, &crate::ID)
+        } else {
+            let shortfall = rent.saturating_sub(order_info.lamports());
+            if shortfall > 0 {
+                self.transfer_from_trader(order_info, shortfall)?;
+            }
+            system_program::allocate(
+                CpiContext::new_with_signer(
+                    self.system_program.to_account_info(),
+                    Allocate {
+                        account_to_allocate: order_info.clone(),
+                    },
+                    signer_seeds,
+                ),
+                space as u64,
+            )?;
+            system_program::assign(
+                CpiContext::new_with_signer(
+                    self.system_program.to_account_info(),
+                    Assign {
+                        account_to_assign: order_info.clone(),
+                    },
+                    signer_seeds,
+                ),
+                &crate::ID,
+            )
+        }
+    }
+
+    fn transfer_from_trader(&self, to: &AccountInfo<'info>, amount: u64) -> Result<()> {
+        let cpi_ctx = CpiContext::new(
+            self.system_program.to_account_info(),
+            Transfer {
+                from: self.trader.to_account_info(),
+                to: to.clone(),
+            },
+        );
+        system_program::transfer(cpi_ctx, amount)
+    }
+
+    /// Tops up or refunds the escrowed execution fee so the order holds exactly `execution_fee`
+    fn fund_execution_fee(
+        &self,
+        order_info: &AccountInfo<'info>,
+        escrowed: u64,
+        execution_fee: u64,
+    ) -> Result<()> {
+        if execution_fee > escrowed {
+            require_gte!(
+                self.global_settings.max_exit_order_execution_fee,
+                execution_fee,
+                ErrorCode::InvalidFee
+            );
+            self.transfer_from_trader(order_info, execution_fee - escrowed)?;
+        } else if escrowed > execution_fee {
+            transfer_lamports_from_pda(
+                order_info,
+                &self.trader.to_account_info(),
+                escrowed - execution_fee,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the execution fee currently escrowed by an initialized order
+    fn escrowed_execution_fee(
+        order_type: ExitOrderType,
+        order_info: &'info AccountInfo<'info>,
+    ) -> Result<u64> {
+        let execution_fee = match order_type {
+            ExitOrderType::TakeProfit => {
+                Account::<TakeProfitOrder>::try_from(order_info)?.execution_fee
+            }
+            ExitOrderType::StopLoss => {
+                Account::<StopLossOrder>::try_from(order_info)?.execution_fee
+            }
+        };
+        Ok(execution_fee)
+    }
+
+    fn set_order(
+        &self,
+        order_type: ExitOrderType,
+        accounts: &mut impl Iterator<Item = &'info AccountInfo<'info>>,
+        maker_amount: u64,
+        taker_amount: u64,
+        execution_fee: u64,
+    ) -> Result<()> {
+        let position = self.load_position(Self::next_account(accounts)?)?;
+        let pool = Account::<BasePool>::try_from(Self::next_account(accounts)?)?;
+        let currency = InterfaceAccount::<Mint>::try_from(Self::next_account(accounts)?)?;
+        let collateral = InterfaceAccount::<Mint>::try_from(Self::next_account(accounts)?)?;
+        let order_info = Self::next_account(accounts)?;
+
+        require_keys_eq!(
+            pool.collateral_vault,
+            position.collateral_vault,
+            ErrorCode::InvalidPool
+        );
+        require_keys_eq!(currency.key(), position.currency, ErrorCode::MintMismatch);
+        require_keys_eq!(
+            collateral.key(),
+            position.collateral,
+            ErrorCode::MintMismatch
+        );
+        require_gt!(maker_amount, 0, ErrorCode::ZeroAmount);
+
+        let interest = self.debt_controller.compute_max_interest(
+            position.principal,
+            position.last_funding_timestamp,
+            Clock::get()?.unix_timestamp,
+        )?;
+
+        // Reject orders that can never be filled or would close the position with bad debt
+        position.validate_exit_order(pool.is_long_pool, maker_amount, taker_amount, interest)?;
+
+        let price = ExitOrderPrice::from_amounts(
+            order_type,
+            maker_amount,
+            taker_amount,
+            collateral.decimals,
+            currency.decimals,
+            DEFAULT_PRICE_DECIMALS,
+        )?;
+
+        let bump = Self::validate_order_address(order_type, &position.key(), order_info)?;
+        let escrowed = if order_info.owner == &crate::ID {
+            Self::escrowed_execution_fee(order_type, order_info)?
+        } else {
+            self.create_order_account(order_type, &position.key(), order_info, bump)?;
+            0
+        };
+        self.fund_execution_fee(order_info, escrowed, execution_fee)?;
+
+        let position_key = position.key();
+        let side = OrderSide::new(pool.is_long_pool);
+        let mut data = order_info.try_borrow_mut_data()?;
+        let mut writer: &mut [u8] = &mut data;
+        match order_type {
+            ExitOrderType::TakeProfit => TakeProfitOrder {
+                position: position_key,
+                maker_amount,
+                taker_amount,
+                execution_fee,
+                price,
+                side,
+            }
+            .try_serialize(&mut writer)?,
+            ExitOrderType::StopLoss => StopLossOrder {
+                position: position_key,
+                maker_amount,
+                taker_amount,
+                execution_fee,
+                price,
+                side,
+            }
+            .try_serialize(&mut writer)?,
+        }
+
+        emit!(ExitOrder {
+            order_type: order_type as u8,
+            position_id: position.key(),
+            maker_amount,
+            taker_amount,
+        });
+
+        Ok(())
+    }
+
+    fn cancel_order(
+        &self,
+        order_type: ExitOrderType,
+        accounts: &mut impl Iterator<Item = &'info AccountInfo<'info>>,
+    ) -> Result<()> {
+        let position = self.load_position(Self::next_account(accounts)?)?;
+        let order_info = Self::next_account(accounts)?;
+
+        Self::validate_order_address(order_type, &position.key(), order_info)?;
+
+        let trader = self.trader.to_account_info();
+        match order_type {
+            ExitOrderType::TakeProfit => {
+                Account::<TakeProfitOrder>::try_from(order_info)?.close(trader)?
+            }
+            ExitOrderType::StopLoss => {
+                Account::<StopLossOrder>::try_from(order_info)?.close(trader)?
+            }
+        }
+
+        emit!(ExitOrderCancelled {
+            order_type: order_type as u8,
+            position_id: position.key(),
+        });
+
+        Ok(())
+    }
+
+    pub fn batch_update_exit_orders(
+        &self,
+        remaining_accounts: &'info [AccountInfo<'info>],
+        ops: &[ExitOrderOp],
+    ) -> Result<()> {
+        require!(!ops.is_empty(), ErrorCode::EmptyBatch);
+
+        let mut accounts = remaining_accounts.iter();
+        for op in ops {
+            match op.action {
+                ExitOrderAction::Set {
+                    maker_amount,
+                    taker_amount,
+                    execution_fee,
+                } => self.set_order(
+                    op.order_type,
+                    &mut accounts,
+                    maker_amount,
+                    taker_amount,
+                    execution_fee,
+                )?,
+                ExitOrderAction::Cancel => self.cancel_order(op.order_type, &mut accounts)?,
+            }
+        }
+        require!(
+            accounts.next().is_none(),
+            ErrorCode::UnusedRemainingAccounts
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,97 @@
+use {
+    crate::{
+        events::LimitOrderCancelled, limit_open_order_signer_seeds,
+        utils::harvest_withheld_transfer_fees, LimitOpenOrder,
+    },
+    anchor_lang::prelude::*,
+    anchor_spl::token_interface::{
+        self, CloseAccount, Mint, TokenAccount, TokenInterface, TransferChecked,
+    },
+};
+
+#[derive(Accounts)]
+pub struct CancelLimitOpenOrder<'info> {
+    #[account(mut)]
+    pub trader: Signer<'info>,
+
+    /// The trader's account that receives the escrowed down payment and fee
+    #[account(
+        mut,
+        associated_token::mint = payment_mint,
+        associated_token::authority = trader,
+        associated_token::token_program = token_program,
+    )]
+    pub trader_payment_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        close = trader,
+        has_one = trader,
+        has_one = escrow,
+    )]
+    pub limit_open_order: Box<Account<'info, LimitOpenOrder>>,
+
+    #[account(mut)]
+    pub escrow: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        address = escrow.mint,
+    )]
+    pub payment_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> CancelLimitOpenOrder<'info> {
+    fn transfer_from_escrow_to_trader(&self, amount: u64) -> Result<()> {
+        let cpi_accounts = TransferChecked {
+            from: self.escrow.to_account_info(),
+            mint: self.payment_mint.to_account_info(),
+            to: self.trader_payment_account.to_account_info(),
+            authority: self.limit_open_order.to_account_info(),
+        };
+        let cpi_ctx = CpiContext {
+            program: self.token_program.to_account_info(),
+            accounts: cpi_accounts,
+            remaining_accounts: Vec::new(),
+            signer_seeds: &[limit_open_order_signer_seeds!(self.limit_open_order)],
+        };
+        token_interface::transfer_checked(cpi_ctx, amount, self.payment_mint.decimals)
+    }
+
+    fn close_escrow(&self) -> Result<()> {
+        harvest_withheld_transfer_fees(
+            self.token_program.to_account_info(),
+            self.payment_mint.to_account_info(),
+            self.escrow.to_account_info(),
+        )?;
+
+        let cpi_accounts = CloseAccount {
+            account: self.escrow.to_account_info(),
+            destination: self.trader.to_account_info(),
+            authority: self.limit_open_order.to_account_info(),
+        };
+        let cpi_ctx = CpiContext {
+            program: self.token_program.to_account_info(),
+            accounts: cpi_accounts,
+            remaining_accounts: Vec::new(),
+            signer_seeds: &[limit_open_order_signer_seeds!(self.limit_open_order)],
+        };
+        token_interface::close_account(cpi_ctx)
+    }
+
+    pub fn cancel_limit_open_order(&self) -> Result<()> {
+        if self.escrow.amount > 0 {
+            self.transfer_from_escrow_to_trader(self.escrow.amount)?;
+        }
+        self.close_escrow()?;
+
+        emit!(LimitOrderCancelled {
+            order: self.limit_open_order.key(),
+            trader: self.trader.key(),
+        });
+
+        Ok(())
+    }
+}
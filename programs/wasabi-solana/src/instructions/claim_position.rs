@@ -2,7 +2,7 @@ use {
     super::close_position_cleanup::CloseAmounts,
     crate::{
         error::ErrorCode, events::PositionClaimed, long_pool_signer_seeds, short_pool_signer_seeds,
-        BasePool, DebtController, GlobalSettings, LpVault, Position,
+        utils::balance_increase, BasePool, DebtController, GlobalSettings, LpVault, Position,
     },
     anchor_lang::prelude::*,
     anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked},
@@ -157,13 +157,19 @@ impl<'info> ClaimPosition<'info> {
             .checked_add(interest_paid)
             .ok_or(ErrorCode::ArithmeticOverflow)?;
 
+        let balance_before = self.vault.amount;
         self.transfer_from_trader_to_vault(amount_owed)?;
+        let amount_received = balance_increase(&mut self.vault, balance_before)?;
 
+        // The principal was never deducted from `total_assets`, so only the interest is added.
+        // Any transfer fee withheld from the repayment is a loss for the LP Vault.
         self.lp_vault.total_assets = self
             .lp_vault
             .total_assets
-            .checked_add(interest_paid)
-            .ok_or(ErrorCode::ArithmeticOverflow)?;
+            .checked_add(amount_received)
+            .ok_or(ErrorCode::ArithmeticOverflow)?
+            .checked_sub(self.position.principal)
+            .ok_or(ErrorCode::ArithmeticUnderflow)?;
 
         let close_fee = self.position.fees_to_be_paid;
 
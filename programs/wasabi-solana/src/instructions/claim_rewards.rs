@@ -0,0 +1,78 @@
+use {
+    crate::{events::RewardsClaimed, reward_pool_signer_seeds, RewardPool, RewardStake},
+    anchor_lang::prelude::*,
+    anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+#[derive(Accounts)]
+pub struct ClaimRewards<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        token::mint = reward_mint,
+        token::authority = owner,
+        token::token_program = reward_token_program,
+    )]
+    pub owner_reward_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        has_one = reward_mint,
+        has_one = reward_vault,
+    )]
+    pub reward_pool: Box<Account<'info, RewardPool>>,
+
+    #[account(mut)]
+    pub reward_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub reward_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        has_one = owner,
+        has_one = reward_pool,
+        seeds = [b"reward_stake", reward_pool.key().as_ref(), owner.key().as_ref()],
+        bump = reward_stake.bump,
+    )]
+    pub reward_stake: Box<Account<'info, RewardStake>>,
+
+    pub reward_token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> ClaimRewards<'info> {
+    fn transfer_rewards_to_owner(&self, amount: u64) -> Result<()> {
+        let cpi_accounts = TransferChecked {
+            from: self.reward_vault.to_account_info(),
+            mint: self.reward_mint.to_account_info(),
+            to: self.owner_reward_account.to_account_info(),
+            authority: self.reward_pool.to_account_info(),
+        };
+        let cpi_ctx = CpiContext {
+            program: self.reward_token_program.to_account_info(),
+            accounts: cpi_accounts,
+            remaining_accounts: Vec::new(),
+            signer_seeds: &[reward_pool_signer_seeds!(self.reward_pool)],
+        };
+        token_interface::transfer_checked(cpi_ctx, amount, self.reward_mint.decimals)
+    }
+
+    pub fn claim_rewards(&mut self) -> Result<()> {
+        self.reward_pool.update(Clock::get()?.unix_timestamp)?;
+        self.reward_stake.settle(&self.reward_pool)?;
+
+        let amount = self.reward_stake.pending_rewards;
+        if amount > 0 {
+            self.reward_stake.pending_rewards = 0;
+            self.transfer_rewards_to_owner(amount)?;
+        }
+
+        emit!(RewardsClaimed {
+            reward_pool: self.reward_pool.key(),
+            owner: self.owner.key(),
+            amount,
+        });
+
+        Ok(())
+    }
+}
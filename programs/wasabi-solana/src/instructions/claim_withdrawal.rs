@@ -0,0 +1,96 @@
+use {
+    crate::{
+        error::ErrorCode, events::WithdrawalClaimed, utils::balance_increase,
+        withdrawal_queue_signer_seeds, LpVault, WithdrawalQueue, WithdrawalRequest,
+    },
+    anchor_lang::prelude::*,
+    anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+#[derive(Accounts)]
+pub struct ClaimWithdrawal<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        associated_token::mint = asset_mint,
+        associated_token::authority = owner,
+        associated_token::token_program = asset_token_program,
+    )]
+    /// The Owner's token account that receives the assets
+    pub owner_asset_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub lp_vault: Box<Account<'info, LpVault>>,
+
+    #[account(address = lp_vault.asset)]
+    pub asset_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        has_one = lp_vault,
+        has_one = queue_vault,
+        seeds = [b"withdrawal_queue", lp_vault.key().as_ref()],
+        bump = withdrawal_queue.bump,
+    )]
+    pub withdrawal_queue: Box<Account<'info, WithdrawalQueue>>,
+
+    #[account(mut)]
+    pub queue_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        close = owner,
+        has_one = owner,
+        has_one = lp_vault,
+    )]
+    pub withdrawal_request: Box<Account<'info, WithdrawalRequest>>,
+
+    pub asset_token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> ClaimWithdrawal<'info> {
+    fn transfer_from_queue_to_owner(&self, amount: u64) -> Result<()> {
+        let cpi_accounts = TransferChecked {
+            from: self.queue_vault.to_account_info(),
+            mint: self.asset_mint.to_account_info(),
+            to: self.owner_asset_account.to_account_info(),
+            authority: self.withdrawal_queue.to_account_info(),
+        };
+        let cpi_ctx = CpiContext {
+            program: self.asset_token_program.to_account_info(),
+            accounts: cpi_accounts,
+            remaining_accounts: Vec::new(),
+            signer_seeds: &[withdrawal_queue_signer_seeds!(self.withdrawal_queue)],
+        };
+        token_interface::transfer_checked(cpi_ctx, amount, self.asset_mint.decimals)
+    }
+
+    pub fn claim_withdrawal(&mut self) -> Result<()> {
+        require!(
+            self.withdrawal_queue.is_filled(&self.withdrawal_request),
+            ErrorCode::WithdrawalNotFilled
+        );
+
+        let assets = self.withdrawal_request.assets;
+        let balance_before = self.owner_asset_account.amount;
+        self.transfer_from_queue_to_owner(assets)?;
+        // The queue pays out `assets` in full, while the owner receives it net of transfer fees
+        let received = balance_increase(&mut self.owner_asset_account, balance_before)?;
+
+        self.withdrawal_queue.unclaimed_assets = self
+            .withdrawal_queue
+            .unclaimed_assets
+            .checked_sub(assets)
+            .ok_or(ErrorCode::ArithmeticUnderflow)?;
+
+        emit!(WithdrawalClaimed {
+            vault: self.lp_vault.shares_mint,
+            owner: self.owner.key(),
+            request: self.withdrawal_request.key(),
+            assets: received,
+        });
+
+        Ok(())
+    }
+}
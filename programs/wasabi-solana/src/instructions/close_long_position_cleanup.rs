@@ -12,14 +12,52 @@ pub struct CloseLongPositionCleanup<'info> {
     pub owner: Signer<'info>,
 }
 
+/// `CloseLongPositionCleanup` with the accounts to unwrap a wSOL payout to the owner as SOL
+#[derive(Accounts)]
+pub struct CloseLongPositionCleanupNative<'info> {
+    pub close_long_position_cleanup: CloseLongPositionCleanup<'info>,
+
+    /// CHECK: Temporary wSOL account the payout is unwrapped through, created and closed within
+    /// the instruction
+    #[account(
+        mut,
+        seeds = [b"native_unwrap", close_long_position_cleanup.owner.key().as_ref()],
+        bump,
+    )]
+    pub native_unwrap_account: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
 impl<'info> CloseLongPositionCleanup<'info> {
     pub fn get_hash() -> [u8; 8] {
         get_function_hash("global", "close_long_position_cleanup")
     }
 
+    pub fn get_native_hash() -> [u8; 8] {
+        get_function_hash("global", "close_long_position_cleanup_native")
+    }
+
     pub fn close_long_position_cleanup(&mut self) -> Result<()> {
         self.close_position_cleanup
             .close_position_cleanup(&CloseAction::Market)?;
         Ok(())
     }
 }
+
+impl<'info> CloseLongPositionCleanupNative<'info> {
+    /// Same as `close_long_position_cleanup`, but the payout is unwrapped to the owner as SOL
+    pub fn close_long_position_cleanup_native(&mut self) -> Result<()> {
+        let cleanup = &mut self.close_long_position_cleanup;
+        let balance_before = cleanup.close_position_cleanup.owner_payout_account.amount;
+        cleanup
+            .close_position_cleanup
+            .close_position_cleanup(&CloseAction::Market)?;
+        cleanup.close_position_cleanup.unwrap_payout(
+            &cleanup.owner,
+            &self.native_unwrap_account,
+            &self.system_program,
+            balance_before,
+        )
+    }
+}
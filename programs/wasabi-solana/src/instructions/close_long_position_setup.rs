@@ -31,7 +31,10 @@ impl<'info> CloseLongPositionSetup<'info> {
         ClosePositionSetup::validate(
             &ctx.accounts.close_position_setup,
             expiration,
-            CloseLongPositionCleanup::get_hash(),
+            &[
+                CloseLongPositionCleanup::get_hash(),
+                CloseLongPositionCleanup::get_native_hash(),
+            ],
         )?;
 
         Ok(())
@@ -0,0 +1,149 @@
+use {
+    crate::{
+        error::ErrorCode,
+        events::VaultClosed,
+        lp_vault_signer_seeds,
+        state::{LpVault, Permission, WithdrawalQueue},
+        utils::harvest_withheld_transfer_fees,
+    },
+    anchor_lang::prelude::*,
+    anchor_spl::token_interface::{
+        self, CloseAccount, Mint, TokenAccount, TokenInterface, TransferChecked,
+    },
+};
+
+// The shares mint and its metadata stay behind: the mint has no close authority.
+
+#[derive(Accounts)]
+pub struct CloseLpVault<'info> {
+    /// Receives the remaining assets and the rent of the closed accounts
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"super_admin"],
+        bump,
+        has_one = authority,
+        constraint = super_admin.is_super_authority @ ErrorCode::InvalidPermissions
+    )]
+    pub super_admin: Account<'info, Permission>,
+
+    #[account(
+        mut,
+        close = authority,
+        has_one = vault,
+        has_one = shares_mint,
+    )]
+    pub lp_vault: Box<Account<'info, LpVault>>,
+
+    #[account(mut)]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Receives the transfer fees withheld in the vault
+    #[account(
+        mut,
+        address = lp_vault.asset,
+    )]
+    pub asset_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    pub shares_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        token::mint = asset_mint,
+        token::authority = authority,
+        token::token_program = asset_token_program,
+    )]
+    pub authority_asset_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: The vault's withdrawal queue, which may not exist. Checked in `validate`.
+    #[account(
+        seeds = [b"withdrawal_queue", lp_vault.key().as_ref()],
+        bump,
+    )]
+    pub withdrawal_queue: UncheckedAccount<'info>,
+
+    pub asset_token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> CloseLpVault<'info> {
+    pub fn validate(ctx: &Context<CloseLpVault>) -> Result<()> {
+        let lp_vault = &ctx.accounts.lp_vault;
+        require!(lp_vault.decommissioned, ErrorCode::VaultNotDecommissioned);
+        require!(
+            ctx.accounts.shares_mint.supply == 0 && lp_vault.total_borrowed == 0,
+            ErrorCode::VaultNotWoundDown
+        );
+        // Open positions repay their principal into the vault, so none may still hold any
+        require!(
+            lp_vault.lent_out(ctx.accounts.vault.amount) == 0,
+            ErrorCode::VaultNotWoundDown
+        );
+
+        // Queued withdrawals are claimed against the vault, so they must all be paid out first
+        let withdrawal_queue = ctx.accounts.withdrawal_queue.to_account_info();
+        if !withdrawal_queue.data_is_empty() {
+            let queue = WithdrawalQueue::try_deserialize(&mut &withdrawal_queue.data.borrow()[..])?;
+            require!(
+                queue.pending_shares == 0 && queue.unclaimed_assets == 0,
+                ErrorCode::VaultNotWoundDown
+            );
+        }
+
+        Ok(())
+    }
+
+    fn transfer_token_from_vault_to_authority(&self, amount: u64) -> Result<()> {
+        let cpi_accounts = TransferChecked {
+            from: self.vault.to_account_info(),
+            mint: self.asset_mint.to_account_info(),
+            to: self.authority_asset_account.to_account_info(),
+            authority: self.lp_vault.to_account_info(),
+        };
+        let cpi_ctx = CpiContext {
+            program: self.asset_token_program.to_account_info(),
+            accounts: cpi_accounts,
+            remaining_accounts: Vec::new(),
+            signer_seeds: &[lp_vault_signer_seeds!(self.lp_vault)],
+        };
+        token_interface::transfer_checked(cpi_ctx, amount, self.asset_mint.decimals)
+    }
+
+    fn close_vault(&self) -> Result<()> {
+        harvest_withheld_transfer_fees(
+            self.asset_token_program.to_account_info(),
+            self.asset_mint.to_account_info(),
+            self.vault.to_account_info(),
+        )?;
+
+        let cpi_accounts = CloseAccount {
+            account: self.vault.to_account_info(),
+            destination: self.authority.to_account_info(),
+            authority: self.lp_vault.to_account_info(),
+        };
+        let cpi_ctx = CpiContext {
+            program: self.asset_token_program.to_account_info(),
+            accounts: cpi_accounts,
+            remaining_accounts: Vec::new(),
+            signer_seeds: &[lp_vault_signer_seeds!(self.lp_vault)],
+        };
+        token_interface::close_account(cpi_ctx)
+    }
+
+    /// Sweeps the rounding dust and unvested assets left once every LP has exited, then closes
+    /// the vault's token account and state
+    pub fn close_lp_vault(&mut self) -> Result<()> {
+        let swept = self.vault.amount;
+        if swept > 0 {
+            self.transfer_token_from_vault_to_authority(swept)?;
+        }
+        self.close_vault()?;
+
+        emit!(VaultClosed {
+            vault: self.lp_vault.shares_mint,
+            swept,
+        });
+
+        Ok(())
+    }
+}
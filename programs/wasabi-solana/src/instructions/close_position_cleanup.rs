@@ -2,13 +2,15 @@ use {
     crate::{
         error::ErrorCode,
         events::{PositionClosed, PositionClosedWithOrder, PositionLiquidated},
+        instructions::{checkpoint_share_price, reserve_repayment_for_queue},
         long_pool_signer_seeds, short_pool_signer_seeds,
-        utils::validate_difference,
+        utils::{balance_increase, unwrap_sol, validate_difference},
         BasePool, ClosePositionRequest, DebtController, GlobalSettings, LpVault, Position,
     },
     anchor_lang::prelude::*,
-    anchor_spl::token_interface::{
-        self, Mint, Revoke, TokenAccount, TokenInterface, TransferChecked,
+    anchor_spl::{
+        token::spl_token::native_mint,
+        token_interface::{self, Mint, Revoke, TokenAccount, TokenInterface, TransferChecked},
     },
 };
 
@@ -102,6 +104,32 @@ pub struct ClosePositionCleanup<'info> {
 
     pub currency_token_program: Interface<'info, TokenInterface>,
     pub collateral_token_program: Interface<'info, TokenInterface>,
+
+    /// CHECK: The LP Vault's withdrawal queue, required once the vault has one. The repaid
+    /// principal and interest are then reserved for it.
+    #[account(
+        mut,
+        seeds = [b"withdrawal_queue", lp_vault.key().as_ref()],
+        bump,
+    )]
+    pub withdrawal_queue: Option<UncheckedAccount<'info>>,
+
+    /// Required once the withdrawal queue exists
+    #[account(mut)]
+    pub queue_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// Required once the share price history exists
+    #[account(address = lp_vault.shares_mint)]
+    pub shares_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+
+    /// CHECK: The LP Vault's share price history, required once the vault has one. The share
+    /// price after the repayment is then recorded in it.
+    #[account(
+        mut,
+        seeds = [b"share_price_history", lp_vault.key().as_ref()],
+        bump,
+    )]
+    pub share_price_history: Option<UncheckedAccount<'info>>,
 }
 
 impl<'info> ClosePositionCleanup<'info> {
@@ -326,6 +354,43 @@ impl<'info> ClosePositionCleanup<'info> {
         }
     }
 
+    /// Unwraps what the owner's wSOL payout account received since `balance_before`, so the
+    /// payout is received as native SOL while any wSOL the owner already held stays wrapped
+    pub(crate) fn unwrap_payout(
+        &mut self,
+        owner: &Signer<'info>,
+        native_unwrap_account: &AccountInfo<'info>,
+        system_program: &Program<'info, System>,
+        balance_before: u64,
+    ) -> Result<()> {
+        require_keys_eq!(
+            self.owner_payout_account.mint,
+            native_mint::ID,
+            ErrorCode::MintMismatch
+        );
+        let payout = balance_increase(&mut self.owner_payout_account, balance_before)?;
+        let (mint, token_program) = if self.pool.is_long_pool {
+            (
+                self.currency.to_account_info(),
+                self.currency_token_program.to_account_info(),
+            )
+        } else {
+            (
+                self.collateral.to_account_info(),
+                self.collateral_token_program.to_account_info(),
+            )
+        };
+        unwrap_sol(
+            owner.to_account_info(),
+            self.owner_payout_account.to_account_info(),
+            native_unwrap_account.clone(),
+            mint,
+            token_program,
+            system_program.to_account_info(),
+            payout,
+        )
+    }
+
     #[inline]
     fn update_total_assets(
         &mut self,
@@ -362,6 +427,8 @@ impl<'info> ClosePositionCleanup<'info> {
                 .total_assets
                 .checked_add(close_amounts.interest_paid)
                 .ok_or(ErrorCode::ArithmeticOverflow)?;
+            self.lp_vault
+                .accrue_performance_fee(close_amounts.interest_paid)?;
             Ok(())
         }
     }
@@ -436,11 +503,17 @@ impl<'info> ClosePositionCleanup<'info> {
             .position
             .compute_close_fee(close_amounts.payout, self.pool.is_long_pool)?;
 
+        // Exit orders pay the executor from the order's prefunded escrow instead of the payout
+        let execution_fee = match close_action {
+            CloseAction::ExitOrder(_) => 0,
+            _ => self.close_position_request.execution_fee,
+        };
+
         // Deduct fees
         let (mut payout, close_fee) = crate::utils::deduct(
             close_amounts.payout,
             close_fee
-                .checked_add(self.close_position_request.execution_fee)
+                .checked_add(execution_fee)
                 .ok_or(ErrorCode::ArithmeticOverflow)?,
         );
 
@@ -480,11 +553,39 @@ impl<'info> ClosePositionCleanup<'info> {
         self.update_total_assets(&close_action, &close_amounts)?;
 
         // Transfer the principal and interest amount to the LP Vault.
-        self.transfer_from_pool_to_vault(
-            close_amounts
-                .principal_repaid
-                .checked_add(close_amounts.interest_paid)
-                .ok_or(ErrorCode::ArithmeticOverflow)?,
+        let repaid = close_amounts
+            .principal_repaid
+            .checked_add(close_amounts.interest_paid)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        let balance_before = self.vault.amount;
+        self.transfer_from_pool_to_vault(repaid)?;
+        let repaid_received = balance_increase(&mut self.vault, balance_before)?;
+
+        // Any transfer fee withheld from the repayment is a loss for the LP Vault
+        let transfer_fee = repaid
+            .checked_sub(repaid_received)
+            .ok_or(ErrorCode::ArithmeticUnderflow)?;
+        self.lp_vault.total_assets = self
+            .lp_vault
+            .total_assets
+            .checked_sub(transfer_fee)
+            .ok_or(ErrorCode::ArithmeticUnderflow)?;
+
+        // Repayments go to queued withdrawals first
+        reserve_repayment_for_queue(
+            self.withdrawal_queue.as_deref(),
+            self.queue_vault.as_deref_mut(),
+            &mut self.lp_vault,
+            &self.vault,
+            &self.currency,
+            &self.currency_token_program,
+            repaid_received,
+        )?;
+
+        checkpoint_share_price(
+            self.share_price_history.as_deref(),
+            &self.lp_vault,
+            self.shares_mint.as_deref_mut(),
         )?;
 
         // Pay fees
@@ -33,7 +33,10 @@ impl<'info> CloseShortPositionSetup<'info> {
         ClosePositionSetup::validate(
             &ctx.accounts.close_position_setup,
             expiration,
-            CloseShortPositionCleanup::get_hash(),
+            &[
+                CloseShortPositionCleanup::get_hash(),
+                CloseShortPositionCleanup::get_native_hash(),
+            ],
         )?;
 
         Ok(())
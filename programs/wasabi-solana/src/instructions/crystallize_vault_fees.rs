@@ -0,0 +1,99 @@
+use {
+    crate::{error::ErrorCode, events::VaultFeesAccrued, lp_vault_signer_seeds, LpVault},
+    anchor_lang::prelude::*,
+    anchor_spl::{
+        token_2022::Token2022,
+        token_interface::{self, Mint, MintTo, TokenAccount},
+    },
+};
+
+// Anyone can crystallise the LP Vault's accrued fees into shares of the fee account.
+
+#[derive(Accounts)]
+pub struct CrystallizeVaultFees<'info> {
+    #[account(
+        mut,
+        has_one = shares_mint,
+        has_one = fee_shares_account,
+    )]
+    pub lp_vault: Account<'info, LpVault>,
+
+    #[account(mut)]
+    pub shares_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut)]
+    pub fee_shares_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub shares_token_program: Program<'info, Token2022>,
+}
+
+impl<'info> CrystallizeVaultFees<'info> {
+    pub fn crystallize_vault_fees(&mut self) -> Result<()> {
+        crystallize_fees(
+            &mut self.lp_vault,
+            &mut self.shares_mint,
+            &self.fee_shares_account,
+            &self.shares_token_program,
+        )
+    }
+}
+
+/// Mints the LP Vault's accrued performance and management fees as shares to the fee account.
+/// Must run before the vault's share price is used, so depositors and withdrawers don't pay for
+/// fees accrued before they interacted with the vault.
+pub(crate) fn crystallize_fees<'info>(
+    lp_vault: &mut Account<'info, LpVault>,
+    shares_mint: &mut InterfaceAccount<'info, Mint>,
+    fee_shares_account: &InterfaceAccount<'info, TokenAccount>,
+    shares_token_program: &Program<'info, Token2022>,
+) -> Result<()> {
+    let accrual = lp_vault.crystallize_fees(shares_mint.supply, Clock::get()?.unix_timestamp)?;
+    if accrual.shares == 0 {
+        return Ok(());
+    }
+
+    let cpi_accounts = MintTo {
+        mint: shares_mint.to_account_info(),
+        to: fee_shares_account.to_account_info(),
+        authority: lp_vault.to_account_info(),
+    };
+    let cpi_ctx = CpiContext {
+        program: shares_token_program.to_account_info(),
+        accounts: cpi_accounts,
+        remaining_accounts: Vec::new(),
+        signer_seeds: &[lp_vault_signer_seeds!(lp_vault)],
+    };
+    token_interface::mint_to(cpi_ctx, accrual.shares)?;
+    shares_mint.reload()?;
+
+    emit!(VaultFeesAccrued {
+        vault: lp_vault.shares_mint,
+        performance_fee: accrual.performance_fee,
+        management_fee: accrual.management_fee,
+        shares: accrual.shares,
+    });
+
+    Ok(())
+}
+
+/// Crystallises the vault's fees before its share price is used. The fee account may only be
+/// omitted while the vault charges no fees, so callers can't exit or buy in at the pre-fee price.
+pub(crate) fn crystallize_fees_if_charged<'info>(
+    lp_vault: &mut Account<'info, LpVault>,
+    shares_mint: &mut InterfaceAccount<'info, Mint>,
+    fee_shares_account: Option<&InterfaceAccount<'info, TokenAccount>>,
+    shares_token_program: &Program<'info, Token2022>,
+) -> Result<()> {
+    match fee_shares_account {
+        Some(fee_shares_account) => crystallize_fees(
+            lp_vault,
+            shares_mint,
+            fee_shares_account,
+            shares_token_program,
+        ),
+        None => {
+            require!(!lp_vault.charges_fees(), ErrorCode::MissingFeeSharesAccount);
+            Ok(())
+        }
+    }
+}
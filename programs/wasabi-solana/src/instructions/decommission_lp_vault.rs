@@ -0,0 +1,48 @@
+use {
+    crate::{
+        error::ErrorCode,
+        events::VaultDecommissioned,
+        state::{LpVault, Permission},
+    },
+    anchor_lang::prelude::*,
+};
+
+#[derive(Accounts)]
+pub struct DecommissionLpVault<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(mut)]
+    pub lp_vault: Account<'info, LpVault>,
+
+    #[account(
+        seeds = [b"super_admin"],
+        bump,
+        has_one = authority,
+        constraint = super_admin.is_super_authority @ ErrorCode::InvalidPermissions
+    )]
+    pub super_admin: Account<'info, Permission>,
+}
+
+impl<'info> DecommissionLpVault<'info> {
+    pub fn validate(ctx: &Context<DecommissionLpVault>) -> Result<()> {
+        require!(
+            !ctx.accounts.lp_vault.decommissioned,
+            ErrorCode::VaultDecommissioned
+        );
+        Ok(())
+    }
+
+    /// Starts winding the vault down. Deposits and borrows are rejected from now on, and the
+    /// cooldown is lifted so LPs can redeem without an exit fee. This can't be undone.
+    pub fn decommission_lp_vault(&mut self) -> Result<()> {
+        self.lp_vault.decommissioned = true;
+        self.lp_vault.lp_cooldown = 0;
+        self.lp_vault.exit_fee_bps = 0;
+
+        emit!(VaultDecommissioned {
+            vault: self.lp_vault.shares_mint,
+        });
+
+        Ok(())
+    }
+}
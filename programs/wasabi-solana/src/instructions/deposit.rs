@@ -1,6 +1,12 @@
 use {
+    super::{crystallize_fees_if_charged, reserve_before_withdrawal, reserve_repayment_for_queue},
     crate::{
-        error::ErrorCode, events::Deposit, lp_vault_signer_seeds, state::GlobalSettings, LpVault,
+        error::ErrorCode,
+        events::{Deposit, ExitFeeCharged},
+        lp_vault_signer_seeds,
+        state::GlobalSettings,
+        utils::balance_increase,
+        AllowlistedWallet, Depositor, LpVault, SharePriceHistory, MIN_FIRST_DEPOSIT,
     },
     anchor_lang::prelude::*,
     anchor_spl::{
@@ -58,18 +64,214 @@ pub struct DepositOrWithdraw<'info> {
 
     pub asset_token_program: Interface<'info, TokenInterface>,
     pub shares_token_program: Program<'info, Token2022>,
+
+    /// Tracks the owner's net deposits and last deposit time, required while the vault has a
+    /// per-wallet cap or a cooldown
+    #[account(
+        mut,
+        seeds = [b"depositor", lp_vault.key().as_ref(), owner.key().as_ref()],
+        bump = depositor.bump,
+    )]
+    pub depositor: Option<Box<Account<'info, Depositor>>>,
+
+    /// Approves the owner to deposit while the vault is allowlisted
+    #[account(
+        seeds = [b"allowlisted_wallet", lp_vault.key().as_ref(), owner.key().as_ref()],
+        bump = allowlisted_wallet.bump,
+    )]
+    pub allowlisted_wallet: Option<Box<Account<'info, AllowlistedWallet>>>,
+
+    /// Receives the vault's accrued fees before the share price is used, required while the vault
+    /// charges fees
+    #[account(
+        mut,
+        address = lp_vault.fee_shares_account,
+    )]
+    pub fee_shares_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// CHECK: The LP Vault's withdrawal queue, required once the vault has one. Deposits are
+    /// then reserved for it and withdrawals are only paid from what it leaves.
+    #[account(
+        mut,
+        seeds = [b"withdrawal_queue", lp_vault.key().as_ref()],
+        bump,
+    )]
+    pub withdrawal_queue: Option<UncheckedAccount<'info>>,
+
+    /// Required once the withdrawal queue exists
+    #[account(mut)]
+    pub queue_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// CHECK: The LP Vault's share price history, required once the vault has one. The share
+    /// price after the operation is then recorded in it.
+    #[account(
+        mut,
+        seeds = [b"share_price_history", lp_vault.key().as_ref()],
+        bump,
+    )]
+    pub share_price_history: Option<UncheckedAccount<'info>>,
+}
+
+/// Checks the vault and per-wallet deposit caps, recording the deposit on the shares owner's
+/// `Depositor`. Deposits made by the shares owner restart their cooldown, while deposits made for
+/// them by other wallets only move it forward in proportion to their size. The `Depositor` is
+/// required while the vault has a per-wallet cap or a cooldown.
+pub(crate) fn check_deposit_caps(
+    lp_vault: &LpVault,
+    depositor: Option<&mut Depositor>,
+    amount: u64,
+    restarts_cooldown: bool,
+) -> Result<()> {
+    let total_assets = lp_vault
+        .total_assets
+        .checked_add(amount)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+    require_gte!(
+        lp_vault.deposit_cap,
+        total_assets,
+        ErrorCode::DepositCapExceeded
+    );
+
+    match depositor {
+        Some(depositor) => depositor.record_deposit(
+            amount,
+            lp_vault.wallet_deposit_cap,
+            Clock::get()?.unix_timestamp,
+            restarts_cooldown,
+        ),
+        None if lp_vault.wallet_deposit_cap > 0 || lp_vault.lp_cooldown > 0 => {
+            err!(ErrorCode::MissingDepositor)
+        }
+        None => Ok(()),
+    }
+}
+
+/// Keeps the exit fee on `assets` withdrawn by the shares owner in the vault, vesting it to the
+/// remaining LPs, and returns the fee. The owner's `Depositor` is required while the vault has a
+/// cooldown.
+pub(crate) fn charge_exit_fee(
+    lp_vault: &mut LpVault,
+    depositor: Option<&Depositor>,
+    assets: u64,
+) -> Result<u64> {
+    if lp_vault.lp_cooldown == 0 {
+        return Ok(0);
+    }
+    let depositor = depositor.ok_or(ErrorCode::MissingDepositor)?;
+    let now = Clock::get()?.unix_timestamp;
+    let fee = lp_vault.exit_fee(assets, depositor.last_deposit_timestamp, now)?;
+    if fee > 0 {
+        lp_vault.add_unvested_assets(fee, now)?;
+        emit!(ExitFeeCharged {
+            vault: lp_vault.shares_mint,
+            owner: depositor.owner,
+            fee,
+        });
+    }
+    Ok(fee)
+}
+
+/// Checks a deposit of `amount` is accepted, `allowlisted` being whether the wallet receiving the
+/// shares passed its `AllowlistedWallet`
+pub(crate) fn validate_deposit(
+    global_settings: &GlobalSettings,
+    lp_vault: &LpVault,
+    allowlisted: bool,
+    amount: u64,
+) -> Result<()> {
+    require_gt!(amount, 0, ErrorCode::ZeroAmount);
+
+    require!(global_settings.can_lp(), ErrorCode::UnpermittedIx);
+    require!(lp_vault.can_deposit(), ErrorCode::VaultDepositsPaused);
+    require!(
+        !lp_vault.allowlist_enabled || allowlisted,
+        ErrorCode::WalletNotAllowlisted
+    );
+
+    Ok(())
+}
+
+/// Adds `amount` received by the vault to its assets and returns the shares to mint for it,
+/// reverting if fewer than `min_shares`. The deposit is recorded on the `Depositor` of the wallet
+/// receiving the shares.
+pub(crate) fn process_deposit(
+    lp_vault: &mut LpVault,
+    shares_supply: u64,
+    depositor: Option<&mut Depositor>,
+    amount: u64,
+    min_shares: u64,
+    restarts_cooldown: bool,
+) -> Result<u64> {
+    if shares_supply == 0 {
+        require_gte!(amount, MIN_FIRST_DEPOSIT, ErrorCode::FirstDepositTooSmall);
+    }
+
+    let shares = lp_vault.preview_deposit(amount, shares_supply)?;
+
+    check_deposit_caps(lp_vault, depositor, amount, restarts_cooldown)?;
+
+    require_gte!(shares, min_shares, ErrorCode::SlippageExceeded);
+
+    lp_vault.total_assets = lp_vault
+        .total_assets
+        .checked_add(amount)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+
+    Ok(shares)
+}
+
+/// Removes `assets` withdrawn by the shares owner from the vault's assets, charging the exit fee
+/// and recording the withdrawal on the owner's `Depositor`. Returns the exit fee.
+pub(crate) fn process_withdrawal(
+    lp_vault: &mut LpVault,
+    mut depositor: Option<&mut Depositor>,
+    assets: u64,
+) -> Result<u64> {
+    lp_vault.total_assets = lp_vault
+        .total_assets
+        .checked_sub(assets)
+        .ok_or(ErrorCode::ArithmeticUnderflow)?;
+
+    let exit_fee = charge_exit_fee(lp_vault, depositor.as_deref(), assets)?;
+    if let Some(depositor) = depositor.as_mut() {
+        depositor.record_withdrawal(assets);
+    }
+
+    Ok(exit_fee)
+}
+
+/// Records the share price once the vault has a share price history. Checkpoints must not be
+/// skipped, so once the history has been initialised it and `shares_mint` are required.
+pub(crate) fn checkpoint_share_price<'info>(
+    share_price_history: Option<&AccountInfo<'info>>,
+    lp_vault: &LpVault,
+    shares_mint: Option<&mut InterfaceAccount<'info, Mint>>,
+) -> Result<()> {
+    if !lp_vault.has_share_price_history {
+        return Ok(());
+    }
+    let share_price_history = share_price_history.ok_or(ErrorCode::MissingSharePriceHistory)?;
+    let shares_mint = shares_mint.ok_or(ErrorCode::MissingSharesMint)?;
+    shares_mint.reload()?;
+
+    let mut history =
+        SharePriceHistory::try_deserialize(&mut &share_price_history.data.borrow()[..])?;
+    history.checkpoint(lp_vault, shares_mint.supply)?;
+    history.try_serialize(&mut &mut share_price_history.data.borrow_mut()[..])
 }
 
 impl<'info> DepositOrWithdraw<'info> {
     pub fn validate(ctx: &Context<DepositOrWithdraw>, amount: u64) -> Result<()> {
-        require_gt!(amount, 0, ErrorCode::ZeroAmount);
-
-        require!(
-            ctx.accounts.global_settings.can_lp(),
-            ErrorCode::UnpermittedIx
-        );
+        ctx.accounts.validate_deposit(amount)
+    }
 
-        Ok(())
+    pub(crate) fn validate_deposit(&self, amount: u64) -> Result<()> {
+        validate_deposit(
+            &self.global_settings,
+            &self.lp_vault,
+            self.allowlisted_wallet.is_some(),
+            amount,
+        )
     }
 
     pub(crate) fn transfer_token_from_owner_to_vault(&self, amount: u64) -> Result<()> {
@@ -124,30 +326,88 @@ impl<'info> DepositOrWithdraw<'info> {
         token_interface::transfer_checked(cpi_ctx, amount, self.asset_mint.decimals)
     }
 
+    /// Crystallises the vault's fees, requiring the fee account while the vault charges fees
+    pub(crate) fn crystallize_fees(&mut self) -> Result<()> {
+        crystallize_fees_if_charged(
+            &mut self.lp_vault,
+            &mut self.shares_mint,
+            self.fee_shares_account.as_deref(),
+            &self.shares_token_program,
+        )
+    }
+
+    pub(crate) fn checkpoint_share_price(&mut self) -> Result<()> {
+        checkpoint_share_price(
+            self.share_price_history.as_deref(),
+            &self.lp_vault,
+            Some(&mut self.shares_mint),
+        )
+    }
+
+    /// Removes `assets` withdrawn by the owner from the vault, returning the exit fee it keeps
+    pub(crate) fn process_withdrawal(&mut self, assets: u64) -> Result<u64> {
+        process_withdrawal(
+            &mut self.lp_vault,
+            self.depositor
+                .as_deref_mut()
+                .map(|depositor| &mut **depositor),
+            assets,
+        )
+    }
+
+    /// Reserves `amount` deposited into the vault for queued withdrawals
+    pub(crate) fn reserve_deposit_for_queue(&mut self, amount: u64) -> Result<()> {
+        reserve_repayment_for_queue(
+            self.withdrawal_queue.as_deref(),
+            self.queue_vault.as_deref_mut(),
+            &mut self.lp_vault,
+            &self.vault,
+            &self.asset_mint,
+            &self.asset_token_program,
+            amount,
+        )
+    }
+
+    /// Reserves the vault's tokens for queued withdrawals before `amount` is paid to the owner
+    pub(crate) fn reserve_before_withdrawal(&mut self, amount: u64) -> Result<()> {
+        reserve_before_withdrawal(
+            self.withdrawal_queue.as_deref(),
+            self.queue_vault.as_deref_mut(),
+            &mut self.lp_vault,
+            &mut self.vault,
+            &self.asset_mint,
+            &self.asset_token_program,
+            amount,
+        )
+    }
+
     pub fn deposit(&mut self, amount: u64) -> Result<()> {
+        self.deposit_with_min_shares(amount, 0)
+    }
+
+    /// Deposits `amount`, reverting if fewer than `min_shares` would be minted. Shares are minted
+    /// for the amount the vault received, net of any Token-2022 transfer fee.
+    pub fn deposit_with_min_shares(&mut self, amount: u64, min_shares: u64) -> Result<()> {
+        self.lp_vault.vest(Clock::get()?.unix_timestamp)?;
+        self.crystallize_fees()?;
+
+        let balance_before = self.vault.amount;
         self.transfer_token_from_owner_to_vault(amount)?;
-        let amount_u128 = amount as u128;
-
-        let shares_supply = self.shares_mint.supply as u128;
-        let shares_to_mint = if shares_supply == 0 {
-            amount
-        } else {
-            shares_supply
-                .checked_mul(amount_u128)
-                .ok_or(ErrorCode::ArithmeticOverflow)?
-                .checked_div(self.lp_vault.total_assets as u128)
-                .ok_or(ErrorCode::ArithmeticOverflow)?
-                .try_into()
-                .map_err(|_| ErrorCode::U64Overflow)?
-        };
+        let amount = balance_increase(&mut self.vault, balance_before)?;
 
+        let shares_to_mint = process_deposit(
+            &mut self.lp_vault,
+            self.shares_mint.supply,
+            self.depositor
+                .as_deref_mut()
+                .map(|depositor| &mut **depositor),
+            amount,
+            min_shares,
+            true,
+        )?;
         self.mint_shares_to_user(shares_to_mint)?;
-
-        self.lp_vault.total_assets = self
-            .lp_vault
-            .total_assets
-            .checked_add(amount)
-            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        self.reserve_deposit_for_queue(amount)?;
+        self.checkpoint_share_price()?;
 
         emit!(Deposit {
             vault: self.lp_vault.shares_mint,
@@ -0,0 +1,220 @@
+use {
+    super::{
+        checkpoint_share_price, crystallize_fees_if_charged, process_deposit,
+        reserve_repayment_for_queue, validate_deposit,
+    },
+    crate::{
+        events::Deposit, lp_vault_signer_seeds, state::GlobalSettings, utils::balance_increase,
+        AllowlistedWallet, Depositor, LpVault,
+    },
+    anchor_lang::prelude::*,
+    anchor_spl::{
+        token_2022::Token2022,
+        token_interface::{self, Mint, MintTo, TokenAccount, TokenInterface, TransferChecked},
+    },
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct DepositFor<'info> {
+    /// The key of the user that sends the assets
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        associated_token::mint = asset_mint,
+        associated_token::authority = owner,
+        associated_token::token_program = asset_token_program,
+    )]
+    /// The Owner's token account that holds the assets
+    pub owner_asset_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: The wallet that receives the shares
+    pub receiver: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        associated_token::mint = shares_mint,
+        associated_token::authority = receiver,
+        associated_token::token_program = shares_token_program,
+    )]
+    /// The Receiver's token account that stores share tokens
+    pub receiver_shares_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        has_one = vault,
+        has_one = shares_mint,
+    )]
+    pub lp_vault: Box<Account<'info, LpVault>>,
+
+    #[account(mut)]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub asset_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut)]
+    pub shares_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// CHECK: The LP Vault's withdrawal queue, required once the vault has one. The
+    /// deposit is then reserved for it.
+    #[account(
+        mut,
+        seeds = [b"withdrawal_queue", lp_vault.key().as_ref()],
+        bump,
+    )]
+    pub withdrawal_queue: Option<UncheckedAccount<'info>>,
+
+    /// Required once the withdrawal queue exists
+    #[account(mut)]
+    pub queue_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    #[account(
+        seeds = [b"global_settings"],
+        bump,
+    )]
+    pub global_settings: Account<'info, GlobalSettings>,
+
+    /// Tracks the receiver's net deposits and cooldown, created by the owner if the receiver has
+    /// none
+    #[account(
+        init_if_needed,
+        payer = owner,
+        seeds = [b"depositor", lp_vault.key().as_ref(), receiver.key().as_ref()],
+        bump,
+        space = 8 + std::mem::size_of::<Depositor>(),
+    )]
+    pub depositor: Box<Account<'info, Depositor>>,
+
+    /// Approves the receiver to hold shares while the vault is allowlisted
+    #[account(
+        seeds = [b"allowlisted_wallet", lp_vault.key().as_ref(), receiver.key().as_ref()],
+        bump = allowlisted_wallet.bump,
+    )]
+    pub allowlisted_wallet: Option<Box<Account<'info, AllowlistedWallet>>>,
+
+    /// Receives the vault's accrued fees before the share price is used, required while the vault
+    /// charges fees
+    #[account(
+        mut,
+        address = lp_vault.fee_shares_account,
+    )]
+    pub fee_shares_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// CHECK: The LP Vault's share price history, required once the vault has one. The share
+    /// price after the operation is then recorded in it.
+    #[account(
+        mut,
+        seeds = [b"share_price_history", lp_vault.key().as_ref()],
+        bump,
+    )]
+    pub share_price_history: Option<UncheckedAccount<'info>>,
+
+    pub asset_token_program: Interface<'info, TokenInterface>,
+    pub shares_token_program: Program<'info, Token2022>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> DepositFor<'info> {
+    pub fn validate(ctx: &Context<DepositFor>, amount: u64) -> Result<()> {
+        validate_deposit(
+            &ctx.accounts.global_settings,
+            &ctx.accounts.lp_vault,
+            ctx.accounts.allowlisted_wallet.is_some(),
+            amount,
+        )
+    }
+
+    fn transfer_token_from_owner_to_vault(&self, amount: u64) -> Result<()> {
+        let cpi_accounts = TransferChecked {
+            from: self.owner_asset_account.to_account_info(),
+            mint: self.asset_mint.to_account_info(),
+            to: self.vault.to_account_info(),
+            authority: self.owner.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(self.asset_token_program.to_account_info(), cpi_accounts);
+        token_interface::transfer_checked(cpi_ctx, amount, self.asset_mint.decimals)
+    }
+
+    fn mint_shares_to_receiver(&self, amount: u64) -> Result<()> {
+        let cpi_accounts = MintTo {
+            mint: self.shares_mint.to_account_info(),
+            to: self.receiver_shares_account.to_account_info(),
+            authority: self.lp_vault.to_account_info(),
+        };
+        let cpi_ctx = CpiContext {
+            program: self.shares_token_program.to_account_info(),
+            accounts: cpi_accounts,
+            remaining_accounts: Vec::new(),
+            signer_seeds: &[lp_vault_signer_seeds!(self.lp_vault)],
+        };
+        token_interface::mint_to(cpi_ctx, amount)
+    }
+
+    /// Deposits `amount` from the owner and mints the shares to the receiver, reverting if fewer
+    /// than `min_shares` would be minted
+    pub fn deposit_for(
+        &mut self,
+        amount: u64,
+        min_shares: u64,
+        bumps: &DepositForBumps,
+    ) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        if self.depositor.owner == Pubkey::default() {
+            self.depositor.set_inner(Depositor {
+                owner: self.receiver.key(),
+                lp_vault: self.lp_vault.key(),
+                deposited: 0,
+                bump: bumps.depositor,
+                last_deposit_timestamp: now,
+            });
+        }
+
+        self.lp_vault.vest(now)?;
+        crystallize_fees_if_charged(
+            &mut self.lp_vault,
+            &mut self.shares_mint,
+            self.fee_shares_account.as_deref(),
+            &self.shares_token_program,
+        )?;
+
+        let balance_before = self.vault.amount;
+        self.transfer_token_from_owner_to_vault(amount)?;
+        let amount = balance_increase(&mut self.vault, balance_before)?;
+
+        let shares_to_mint = process_deposit(
+            &mut self.lp_vault,
+            self.shares_mint.supply,
+            Some(&mut self.depositor),
+            amount,
+            min_shares,
+            self.receiver.key() == self.owner.key(),
+        )?;
+        self.mint_shares_to_receiver(shares_to_mint)?;
+        reserve_repayment_for_queue(
+            self.withdrawal_queue.as_deref(),
+            self.queue_vault.as_deref_mut(),
+            &mut self.lp_vault,
+            &self.vault,
+            &self.asset_mint,
+            &self.asset_token_program,
+            amount,
+        )?;
+        checkpoint_share_price(
+            self.share_price_history.as_deref(),
+            &self.lp_vault,
+            Some(&mut self.shares_mint),
+        )?;
+
+        emit!(Deposit {
+            vault: self.lp_vault.shares_mint,
+            sender: self.owner.key(),
+            owner: self.receiver.key(),
+            assets: amount,
+            shares: shares_to_mint,
+        });
+
+        Ok(())
+    }
+}
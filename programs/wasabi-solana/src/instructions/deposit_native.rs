@@ -0,0 +1,81 @@
+use {
+    super::WithdrawTrait,
+    crate::{
+        error::ErrorCode,
+        instructions::deposit::*,
+        utils::{balance_increase, unwrap_sol, wrap_sol},
+    },
+    anchor_lang::prelude::*,
+    anchor_spl::token::spl_token::native_mint,
+};
+
+// Deposits into and withdrawals from a wSOL LP Vault using the owner's SOL. Deposits wrap exactly
+// the deposited amount into the owner's wSOL account, and withdrawals unwrap exactly the withdrawn
+// amount, so any wSOL the owner already held stays wrapped. The owner pays the lamports to wrap,
+// so it must be writable.
+
+#[derive(Accounts)]
+pub struct DepositOrWithdrawNative<'info> {
+    pub deposit_or_withdraw: DepositOrWithdraw<'info>,
+
+    /// CHECK: Temporary wSOL account the withdrawn amount is unwrapped through, created and
+    /// closed within the instruction
+    #[account(
+        mut,
+        seeds = [b"native_unwrap", deposit_or_withdraw.owner.key().as_ref()],
+        bump,
+    )]
+    pub native_unwrap_account: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> DepositOrWithdrawNative<'info> {
+    pub fn validate(ctx: &Context<DepositOrWithdrawNative>) -> Result<()> {
+        require_keys_eq!(
+            ctx.accounts.deposit_or_withdraw.asset_mint.key(),
+            native_mint::ID,
+            ErrorCode::MintMismatch
+        );
+        Ok(())
+    }
+
+    pub fn validate_deposit(ctx: &Context<DepositOrWithdrawNative>, amount: u64) -> Result<()> {
+        Self::validate(ctx)?;
+        ctx.accounts.deposit_or_withdraw.validate_deposit(amount)
+    }
+
+    pub fn deposit_native(&mut self, amount: u64, min_shares: u64) -> Result<()> {
+        let dw = &self.deposit_or_withdraw;
+        wrap_sol(
+            dw.owner.to_account_info(),
+            dw.owner_asset_account.to_account_info(),
+            dw.asset_token_program.to_account_info(),
+            self.system_program.to_account_info(),
+            amount,
+        )?;
+        self.deposit_or_withdraw
+            .deposit_with_min_shares(amount, min_shares)
+    }
+
+    pub fn withdraw_native(&mut self, amount: u64, max_shares: u64) -> Result<()> {
+        let balance_before = self.deposit_or_withdraw.owner_asset_account.amount;
+        self.deposit_or_withdraw
+            .withdraw_with_max_shares(amount, max_shares)?;
+        let withdrawn = balance_increase(
+            &mut self.deposit_or_withdraw.owner_asset_account,
+            balance_before,
+        )?;
+
+        let dw = &self.deposit_or_withdraw;
+        unwrap_sol(
+            dw.owner.to_account_info(),
+            dw.owner_asset_account.to_account_info(),
+            self.native_unwrap_account.to_account_info(),
+            dw.asset_mint.to_account_info(),
+            dw.asset_token_program.to_account_info(),
+            self.system_program.to_account_info(),
+            withdrawn,
+        )
+    }
+}
@@ -3,6 +3,7 @@ use {
         error::ErrorCode,
         events::NativeYieldClaimed,
         state::{GlobalSettings, Permission},
+        utils::balance_increase,
         LpVault,
     },
     anchor_lang::prelude::*,
@@ -74,12 +75,12 @@ impl<'info> Donate<'info> {
     }
 
     pub fn donate(&mut self, amount: u64) -> Result<()> {
+        let balance_before = self.vault.amount;
         self.transfer_token_from_owner_to_vault(amount)?;
-        self.lp_vault.total_assets = self
-            .lp_vault
-            .total_assets
-            .checked_add(amount)
-            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        let amount = balance_increase(&mut self.vault, balance_before)?;
+
+        self.lp_vault
+            .add_unvested_assets(amount, Clock::get()?.unix_timestamp)?;
 
         emit!(NativeYieldClaimed {
             source: self.owner.key(),
@@ -0,0 +1,303 @@
+use {
+    crate::{
+        debt_controller::LEVERAGE_DENOMINATOR,
+        error::ErrorCode,
+        events::{LimitOrderExecuted, PositionOpened},
+        limit_open_order_signer_seeds, long_pool_signer_seeds, short_pool_signer_seeds,
+        utils::{balance_increase, get_function_hash, harvest_withheld_transfer_fees},
+        BasePool, DebtController, LimitOpenOrder, LpVault, OpenPositionRequest, Position,
+    },
+    anchor_lang::prelude::*,
+    anchor_spl::token_interface::{
+        self, CloseAccount, Mint, Revoke, TokenAccount, TokenInterface, TransferChecked,
+    },
+};
+
+#[derive(Accounts)]
+pub struct ExecuteLimitOpenOrderCleanup<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// CHECK: Validated against the `limit_open_order`, receives the order and escrow rent
+    #[account(mut)]
+    pub trader: AccountInfo<'info>,
+
+    /// The trader's account that receives whatever is left in the escrow
+    #[account(
+        mut,
+        associated_token::mint = escrow_mint,
+        associated_token::authority = trader,
+        associated_token::token_program = escrow_token_program,
+    )]
+    pub trader_payment_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        close = trader,
+        has_one = trader,
+        has_one = pool,
+        has_one = lp_vault,
+        has_one = escrow,
+    )]
+    pub limit_open_order: Box<Account<'info, LimitOpenOrder>>,
+
+    #[account(mut)]
+    pub escrow: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The escrow's mint, used to refund the escrow and harvest its withheld transfer fees
+    #[account(
+        mut,
+        address = escrow.mint,
+    )]
+    pub escrow_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        has_one = lp_vault,
+    )]
+    pub position: Box<Account<'info, Position>>,
+
+    /// The pool that owns the Position
+    #[account(
+        has_one = collateral_vault,
+        has_one = currency_vault,
+        has_one = currency,
+    )]
+    pub pool: Box<Account<'info, BasePool>>,
+
+    /// The collateral account that is the destination of the swap
+    pub collateral_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    // The token account that is the source of the swap
+    #[account(mut)]
+    pub currency_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub currency: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The LP Vault that the position borrowed from
+    #[account(
+        has_one = vault,
+    )]
+    pub lp_vault: Box<Account<'info, LpVault>>,
+
+    /// The LP Vault's token account.
+    #[account(mut)]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"open_pos", trader.key().as_ref()],
+        bump,
+    )]
+    pub open_position_request: Box<Account<'info, OpenPositionRequest>>,
+
+    #[account(
+        seeds = [b"debt_controller"],
+        bump,
+    )]
+    pub debt_controller: Box<Account<'info, DebtController>>,
+
+    pub currency_token_program: Interface<'info, TokenInterface>,
+    /// The token program of the escrowed down payment
+    pub escrow_token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> ExecuteLimitOpenOrderCleanup<'info> {
+    pub fn get_hash() -> [u8; 8] {
+        get_function_hash("global", "execute_limit_open_order_cleanup")
+    }
+
+    fn get_collateral_delta(&self) -> Result<u64> {
+        Ok(self
+            .collateral_vault
+            .amount
+            .checked_sub(self.open_position_request.swap_cache.taker_bal_before)
+            .ok_or(ErrorCode::ArithmeticUnderflow)?)
+    }
+
+    fn get_principal_delta(&self) -> Result<u64> {
+        Ok(self
+            .open_position_request
+            .swap_cache
+            .maker_bal_before
+            .checked_sub(self.currency_vault.amount)
+            .ok_or(ErrorCode::ArithmeticUnderflow)?)
+    }
+
+    fn validate(&self) -> Result<()> {
+        // Validate the same position was used in setup and cleanup
+        require_keys_eq!(
+            self.position.key(),
+            self.open_position_request.position,
+            ErrorCode::InvalidPosition
+        );
+
+        // Validate the same pool, and thus collateral_vault was used in setup and cleanup.
+        require_keys_eq!(
+            self.pool.key(),
+            self.open_position_request.pool_key,
+            ErrorCode::InvalidPool
+        );
+
+        // Validate the authority does not spend more tokens than requested.
+        require_gte!(
+            self.open_position_request.max_amount_in,
+            self.get_principal_delta()?,
+            ErrorCode::SwapAmountExceeded
+        );
+
+        // Validate the swap was executed at or better than the order's limit price
+        self.limit_open_order
+            .validate_execution_price(self.get_principal_delta()?, self.get_collateral_delta()?)?;
+
+        Ok(())
+    }
+
+    fn revoke_delegation(&self) -> Result<()> {
+        let cpi_accounts = Revoke {
+            source: self.currency_vault.to_account_info(),
+            authority: self.pool.to_account_info(),
+        };
+        if self.pool.is_long_pool {
+            let cpi_ctx = CpiContext {
+                program: self.currency_token_program.to_account_info(),
+                accounts: cpi_accounts,
+                remaining_accounts: Vec::new(),
+                signer_seeds: &[long_pool_signer_seeds!(self.pool)],
+            };
+            token_interface::revoke(cpi_ctx)
+        } else {
+            let cpi_ctx = CpiContext {
+                program: self.currency_token_program.to_account_info(),
+                accounts: cpi_accounts,
+                remaining_accounts: Vec::new(),
+                signer_seeds: &[short_pool_signer_seeds!(self.pool)],
+            };
+            token_interface::revoke(cpi_ctx)
+        }
+    }
+
+    fn transfer_remaining_principal_from_currency_vault(&self, amount: u64) -> Result<()> {
+        let cpi_accounts = TransferChecked {
+            from: self.currency_vault.to_account_info(),
+            mint: self.currency.to_account_info(),
+            to: self.vault.to_account_info(),
+            authority: self.pool.to_account_info(),
+        };
+        let cpi_ctx = CpiContext {
+            program: self.currency_token_program.to_account_info(),
+            accounts: cpi_accounts,
+            remaining_accounts: Vec::new(),
+            signer_seeds: &[short_pool_signer_seeds!(self.pool)],
+        };
+        token_interface::transfer_checked(cpi_ctx, amount, self.currency.decimals)
+    }
+
+    fn transfer_from_escrow_to_trader(&self, amount: u64) -> Result<()> {
+        let cpi_accounts = TransferChecked {
+            from: self.escrow.to_account_info(),
+            mint: self.escrow_mint.to_account_info(),
+            to: self.trader_payment_account.to_account_info(),
+            authority: self.limit_open_order.to_account_info(),
+        };
+        let cpi_ctx = CpiContext {
+            program: self.escrow_token_program.to_account_info(),
+            accounts: cpi_accounts,
+            remaining_accounts: Vec::new(),
+            signer_seeds: &[limit_open_order_signer_seeds!(self.limit_open_order)],
+        };
+        token_interface::transfer_checked(cpi_ctx, amount, self.escrow_mint.decimals)
+    }
+
+    /// Refunds whatever the order didn't use, such as the part of the fee the executor didn't
+    /// charge or tokens sent to the escrow after it was funded, then closes it
+    fn close_escrow(&self) -> Result<()> {
+        if self.escrow.amount > 0 {
+            self.transfer_from_escrow_to_trader(self.escrow.amount)?;
+        }
+        harvest_withheld_transfer_fees(
+            self.escrow_token_program.to_account_info(),
+            self.escrow_mint.to_account_info(),
+            self.escrow.to_account_info(),
+        )?;
+
+        let cpi_accounts = CloseAccount {
+            account: self.escrow.to_account_info(),
+            destination: self.trader.to_account_info(),
+            authority: self.limit_open_order.to_account_info(),
+        };
+        let cpi_ctx = CpiContext {
+            program: self.escrow_token_program.to_account_info(),
+            accounts: cpi_accounts,
+            remaining_accounts: Vec::new(),
+            signer_seeds: &[limit_open_order_signer_seeds!(self.limit_open_order)],
+        };
+        token_interface::close_account(cpi_ctx)
+    }
+
+    pub fn execute_limit_open_order_cleanup(&mut self) -> Result<()> {
+        self.validate()?;
+        self.revoke_delegation()?;
+
+        let collateral_received = self.get_collateral_delta()?;
+
+        if self.pool.is_long_pool {
+            self.position.collateral_amount = collateral_received;
+        } else {
+            let principal_used = self.get_principal_delta()?;
+
+            // The collateral received from the borrowed principal is capped by both the order's
+            // leverage and the protocol's maximum leverage.
+            require_gte!(
+                self.limit_open_order.compute_principal()?,
+                collateral_received,
+                ErrorCode::PrincipalTooHigh
+            );
+            require_gte!(
+                self.position
+                    .down_payment
+                    .checked_mul(self.debt_controller.max_leverage)
+                    .ok_or(ErrorCode::ArithmeticOverflow)?
+                    .checked_div(LEVERAGE_DENOMINATOR)
+                    .ok_or(ErrorCode::ArithmeticOverflow)?,
+                collateral_received,
+                ErrorCode::PrincipalTooHigh
+            );
+
+            // `max_amount_in` holds the principal that arrived in the `currency_vault`
+            let remaining_principal = self
+                .open_position_request
+                .max_amount_in
+                .checked_sub(principal_used)
+                .ok_or(ErrorCode::ArithmeticUnderflow)?;
+
+            // The position owes whatever the LP Vault didn't get back, including transfer fees
+            let mut principal_returned = 0;
+            if remaining_principal > 0 {
+                let balance_before = self.vault.amount;
+                self.transfer_remaining_principal_from_currency_vault(remaining_principal)?;
+                principal_returned = balance_increase(&mut self.vault, balance_before)?;
+            }
+
+            self.position.principal = self
+                .position
+                .principal
+                .checked_sub(principal_returned)
+                .ok_or(ErrorCode::ArithmeticUnderflow)?;
+            self.position.collateral_amount = collateral_received
+                .checked_add(self.position.down_payment)
+                .ok_or(ErrorCode::ArithmeticOverflow)?;
+        }
+
+        self.close_escrow()?;
+
+        emit!(PositionOpened::new(&self.position, self.pool.is_long_pool));
+        emit!(LimitOrderExecuted {
+            order: self.limit_open_order.key(),
+            position_id: self.position.key(),
+        });
+
+        Ok(())
+    }
+}
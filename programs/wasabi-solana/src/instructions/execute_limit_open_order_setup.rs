@@ -0,0 +1,324 @@
+use {
+    super::ExecuteLimitOpenOrderCleanup,
+    crate::{
+        error::ErrorCode,
+        limit_open_order_signer_seeds, long_pool_signer_seeds, lp_vault_signer_seeds,
+        short_pool_signer_seeds,
+        utils::{balance_increase, setup_transaction_introspection_validation},
+        BasePool, DebtController, GlobalSettings, LimitOpenOrder, LpVault, OpenPositionRequest,
+        Permission, Position, SwapCache,
+    },
+    anchor_lang::{prelude::*, solana_program::sysvar},
+    anchor_spl::token_interface::{
+        self, Approve, Mint, TokenAccount, TokenInterface, TransferChecked,
+    },
+};
+
+// Executes a `LimitOpenOrder` on behalf of the trader. The down payment and fee are taken from
+// the order's escrow instead of the trader's wallet, so only the backend authority signs.
+#[derive(Accounts)]
+pub struct ExecuteLimitOpenOrderSetup<'info> {
+    /// Backend authority that executes the order and swaps on behalf of the pool
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        has_one = authority,
+    )]
+    pub permission: Box<Account<'info, Permission>>,
+
+    /// CHECK: Validated against the `limit_open_order`
+    pub trader: AccountInfo<'info>,
+
+    #[account(
+        has_one = trader,
+        has_one = pool,
+        has_one = lp_vault,
+        has_one = escrow,
+    )]
+    pub limit_open_order: Box<Account<'info, LimitOpenOrder>>,
+
+    /// The order's token account that holds the down payment and fee
+    #[account(mut)]
+    pub escrow: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The LP Vault that the position will borrow from
+    #[account(
+        has_one = vault,
+    )]
+    pub lp_vault: Box<Account<'info, LpVault>>,
+
+    /// The LP Vault's token account.
+    #[account(mut)]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The pool that owns the Position
+    #[account(
+        has_one = collateral_vault,
+        has_one = currency_vault,
+        has_one = currency,
+        has_one = collateral,
+    )]
+    pub pool: Box<Account<'info, BasePool>>,
+
+    /// The collateral account that is the destination of the swap
+    #[account(mut)]
+    pub collateral_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    // The token account that is the source of the swap (where principal is sent)
+    #[account(mut)]
+    pub currency_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub currency: Box<InterfaceAccount<'info, Mint>>,
+    pub collateral: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"open_pos", trader.key().as_ref()],
+        bump,
+        space = 8 + std::mem::size_of::<OpenPositionRequest>(),
+    )]
+    pub open_position_request: Box<Account<'info, OpenPositionRequest>>,
+
+    // The position rent is paid by the authority, which receives the order's rent on cleanup.
+    #[account(
+        init,
+        payer = authority,
+        seeds = [
+            b"position",
+            trader.key().as_ref(),
+            pool.key().as_ref(),
+            lp_vault.key().as_ref(),
+            &limit_open_order.nonce.to_le_bytes(),
+        ],
+        bump,
+        space = 8 + std::mem::size_of::<Position>(),
+    )]
+    pub position: Box<Account<'info, Position>>,
+
+    #[account(
+        mut,
+        constraint = fee_wallet.owner == global_settings.fee_wallet
+    )]
+    pub fee_wallet: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        seeds = [b"debt_controller"],
+        bump,
+    )]
+    pub debt_controller: Box<Account<'info, DebtController>>,
+
+    #[account(
+        seeds = [b"global_settings"],
+        bump,
+    )]
+    pub global_settings: Box<Account<'info, GlobalSettings>>,
+
+    pub currency_token_program: Interface<'info, TokenInterface>,
+    pub collateral_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+    #[account(
+        address = sysvar::instructions::ID
+    )]
+    /// CHECK: Sysvar instruction check applied
+    pub sysvar_info: AccountInfo<'info>,
+}
+
+impl<'info> ExecuteLimitOpenOrderSetup<'info> {
+    /// `fee` is the open fee the protocol charges for the position, which the fee the trader
+    /// escrowed with the order must cover. Whatever it leaves is refunded on cleanup.
+    pub fn validate(ctx: &Context<Self>, fee: u64) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+
+        require_gt!(
+            ctx.accounts.limit_open_order.expiration,
+            now,
+            ErrorCode::PositionReqExpired
+        );
+        require_gte!(
+            ctx.accounts.limit_open_order.fee,
+            fee,
+            ErrorCode::InsufficientOrderFee
+        );
+
+        require!(
+            ctx.accounts.permission.can_cosign_swaps(),
+            ErrorCode::InvalidSwapCosigner
+        );
+        require!(
+            ctx.accounts.global_settings.can_trade(),
+            ErrorCode::UnpermittedIx
+        );
+        require!(
+            ctx.accounts.lp_vault.can_borrow(),
+            ErrorCode::VaultBorrowsPaused
+        );
+        ctx.accounts.pool.validate_can_open()?;
+
+        // Validate TX only has only one setup IX and has one following cleanup IX
+        setup_transaction_introspection_validation(
+            &ctx.accounts.sysvar_info,
+            &[ExecuteLimitOpenOrderCleanup::get_hash()],
+            true,
+        )?;
+
+        Ok(())
+    }
+
+    /// The mint and token program of the escrowed down payment
+    fn payment_mint_and_program(&self) -> (&InterfaceAccount<'info, Mint>, AccountInfo<'info>) {
+        if self.pool.is_long_pool {
+            (
+                &self.currency,
+                self.currency_token_program.to_account_info(),
+            )
+        } else {
+            (
+                &self.collateral,
+                self.collateral_token_program.to_account_info(),
+            )
+        }
+    }
+
+    fn transfer_from_escrow(&self, to: AccountInfo<'info>, amount: u64) -> Result<()> {
+        let (mint, token_program) = self.payment_mint_and_program();
+        let cpi_accounts = TransferChecked {
+            from: self.escrow.to_account_info(),
+            mint: mint.to_account_info(),
+            to,
+            authority: self.limit_open_order.to_account_info(),
+        };
+        let cpi_ctx = CpiContext {
+            program: token_program,
+            accounts: cpi_accounts,
+            remaining_accounts: Vec::new(),
+            signer_seeds: &[limit_open_order_signer_seeds!(self.limit_open_order)],
+        };
+        token_interface::transfer_checked(cpi_ctx, amount, mint.decimals)
+    }
+
+    fn transfer_borrow_amount_from_vault(&self, amount: u64) -> Result<()> {
+        let cpi_accounts = TransferChecked {
+            from: self.vault.to_account_info(),
+            mint: self.currency.to_account_info(),
+            to: self.currency_vault.to_account_info(),
+            authority: self.lp_vault.to_account_info(),
+        };
+        let cpi_ctx = CpiContext {
+            program: self.currency_token_program.to_account_info(),
+            accounts: cpi_accounts,
+            remaining_accounts: Vec::new(),
+            signer_seeds: &[lp_vault_signer_seeds!(self.lp_vault)],
+        };
+        token_interface::transfer_checked(cpi_ctx, amount, self.currency.decimals)
+    }
+
+    fn approve_authority_delegation(&self, amount: u64) -> Result<()> {
+        let cpi_accounts = Approve {
+            to: self.currency_vault.to_account_info(),
+            delegate: self.authority.to_account_info(),
+            authority: self.pool.to_account_info(),
+        };
+        if self.pool.is_long_pool {
+            let cpi_ctx = CpiContext {
+                program: self.currency_token_program.to_account_info(),
+                accounts: cpi_accounts,
+                remaining_accounts: Vec::new(),
+                signer_seeds: &[long_pool_signer_seeds!(self.pool)],
+            };
+            token_interface::approve(cpi_ctx, amount)
+        } else {
+            let cpi_ctx = CpiContext {
+                program: self.currency_token_program.to_account_info(),
+                accounts: cpi_accounts,
+                remaining_accounts: Vec::new(),
+                signer_seeds: &[short_pool_signer_seeds!(self.pool)],
+            };
+            token_interface::approve(cpi_ctx, amount)
+        }
+    }
+
+    /// `principal` is only used for short pools, where the amount of currency borrowed depends
+    /// on the price at execution. For long pools it is derived from the order's leverage. `fee`
+    /// is paid to the fee wallet out of the escrowed fee, and the rest stays in the escrow to be
+    /// refunded to the trader on cleanup.
+    pub fn execute_limit_open_order_setup(&mut self, principal: u64, fee: u64) -> Result<()> {
+        let down_payment = self.limit_open_order.down_payment;
+
+        // Amounts are measured as received by the pool's accounts, net of any transfer fee
+        let (principal, down_payment, max_amount_in) = if self.pool.is_long_pool {
+            let principal = self.limit_open_order.compute_principal()?;
+            let max_principal = self.debt_controller.compute_max_principal(down_payment)?;
+            require_gte!(max_principal, principal, ErrorCode::PrincipalTooHigh);
+
+            self.lp_vault
+                .validate_utilization(self.vault.amount, principal)?;
+            let balance_before = self.currency_vault.amount;
+            self.transfer_borrow_amount_from_vault(principal)?;
+            let principal_received = balance_increase(&mut self.currency_vault, balance_before)?;
+
+            let balance_before = self.currency_vault.amount;
+            self.transfer_from_escrow(self.currency_vault.to_account_info(), down_payment)?;
+            let down_payment = balance_increase(&mut self.currency_vault, balance_before)?;
+
+            let max_amount_in = principal_received
+                .checked_add(down_payment)
+                .ok_or(ErrorCode::ArithmeticOverflow)?;
+            (principal, down_payment, max_amount_in)
+        } else {
+            require_gt!(principal, 0, ErrorCode::ZeroAmount);
+
+            // Down payment is transferred to the `collateral_vault` since it's not used for
+            // swapping when opening a short position.
+            let balance_before = self.collateral_vault.amount;
+            self.transfer_from_escrow(self.collateral_vault.to_account_info(), down_payment)?;
+            let down_payment = balance_increase(&mut self.collateral_vault, balance_before)?;
+
+            require_gte!(
+                self.vault.amount,
+                principal,
+                ErrorCode::InsufficientAvailablePrincipal
+            );
+            self.lp_vault
+                .validate_utilization(self.vault.amount, principal)?;
+            let balance_before = self.currency_vault.amount;
+            self.transfer_borrow_amount_from_vault(principal)?;
+            let principal_received = balance_increase(&mut self.currency_vault, balance_before)?;
+            (principal, down_payment, principal_received)
+        };
+
+        if fee > 0 {
+            self.transfer_from_escrow(self.fee_wallet.to_account_info(), fee)?;
+        }
+
+        // Approve authority to make a swap on behalf of the `currency_vault`
+        self.approve_authority_delegation(max_amount_in)?;
+
+        self.open_position_request.set_inner(OpenPositionRequest {
+            min_target_amount: 0, // Enforced against the order's limit price on cleanup
+            max_amount_in,
+            pool_key: self.pool.key(),
+            position: self.position.key(),
+            swap_cache: SwapCache {
+                maker_bal_before: self.currency_vault.amount,
+                taker_bal_before: self.collateral_vault.amount,
+            },
+        });
+
+        self.position.set_inner(Position {
+            trader: self.trader.key(),
+            currency: self.currency.key(),
+            collateral: self.collateral.key(),
+            down_payment,
+            principal,
+            collateral_vault: self.collateral_vault.key(),
+            lp_vault: self.lp_vault.key(),
+            collateral_amount: 0,
+            fees_to_be_paid: fee,
+            last_funding_timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,357 @@
+use {
+    super::crystallize_fees_if_charged,
+    crate::{
+        error::ErrorCode,
+        events::{ExitFeeCharged, WithdrawalFilled, WithdrawalQueueReserved},
+        lp_vault_signer_seeds,
+        utils::balance_increase,
+        withdrawal_queue_signer_seeds, LpVault, WithdrawalQueue, WithdrawalRequest,
+    },
+    anchor_lang::prelude::*,
+    anchor_spl::{
+        token_2022::Token2022,
+        token_interface::{self, Burn, Mint, TokenAccount, TokenInterface, TransferChecked},
+    },
+};
+
+// Anyone can fill the request at the front of the queue. Its escrowed shares are redeemed at the
+// current share price, paid from the queue's reserve first and then from the LP Vault's
+// unborrowed tokens.
+
+#[derive(Accounts)]
+pub struct FillWithdrawalQueue<'info> {
+    #[account(
+        mut,
+        has_one = vault,
+        has_one = shares_mint,
+    )]
+    pub lp_vault: Box<Account<'info, LpVault>>,
+
+    #[account(mut)]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(address = lp_vault.asset)]
+    pub asset_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut)]
+    pub shares_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        has_one = lp_vault,
+        has_one = queue_vault,
+        has_one = shares_escrow,
+        seeds = [b"withdrawal_queue", lp_vault.key().as_ref()],
+        bump = withdrawal_queue.bump,
+    )]
+    pub withdrawal_queue: Box<Account<'info, WithdrawalQueue>>,
+
+    #[account(mut)]
+    pub queue_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub shares_escrow: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The request at the front of the queue
+    #[account(
+        mut,
+        seeds = [
+            b"withdrawal_request",
+            lp_vault.key().as_ref(),
+            &withdrawal_queue.next_fill_id.to_le_bytes(),
+        ],
+        bump = withdrawal_request.bump,
+    )]
+    pub withdrawal_request: Box<Account<'info, WithdrawalRequest>>,
+
+    /// Receives the vault's accrued fees before the share price is used, required while the vault
+    /// charges fees
+    #[account(
+        mut,
+        address = lp_vault.fee_shares_account,
+    )]
+    pub fee_shares_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    pub asset_token_program: Interface<'info, TokenInterface>,
+    pub shares_token_program: Program<'info, Token2022>,
+}
+
+impl<'info> FillWithdrawalQueue<'info> {
+    fn burn_escrowed_shares(&self, amount: u64) -> Result<()> {
+        let cpi_accounts = Burn {
+            mint: self.shares_mint.to_account_info(),
+            from: self.shares_escrow.to_account_info(),
+            authority: self.withdrawal_queue.to_account_info(),
+        };
+        let cpi_ctx = CpiContext {
+            program: self.shares_token_program.to_account_info(),
+            accounts: cpi_accounts,
+            remaining_accounts: Vec::new(),
+            signer_seeds: &[withdrawal_queue_signer_seeds!(self.withdrawal_queue)],
+        };
+        token_interface::burn(cpi_ctx, amount)
+    }
+
+    fn transfer_from_queue_to_vault(&self, amount: u64) -> Result<()> {
+        let cpi_accounts = TransferChecked {
+            from: self.queue_vault.to_account_info(),
+            mint: self.asset_mint.to_account_info(),
+            to: self.vault.to_account_info(),
+            authority: self.withdrawal_queue.to_account_info(),
+        };
+        let cpi_ctx = CpiContext {
+            program: self.asset_token_program.to_account_info(),
+            accounts: cpi_accounts,
+            remaining_accounts: Vec::new(),
+            signer_seeds: &[withdrawal_queue_signer_seeds!(self.withdrawal_queue)],
+        };
+        token_interface::transfer_checked(cpi_ctx, amount, self.asset_mint.decimals)
+    }
+
+    pub fn fill_withdrawal_queue(&mut self) -> Result<()> {
+        require!(
+            self.lp_vault.can_withdraw(),
+            ErrorCode::VaultWithdrawalsPaused
+        );
+
+        let now = Clock::get()?.unix_timestamp;
+        self.lp_vault.vest(now)?;
+        crystallize_fees_if_charged(
+            &mut self.lp_vault,
+            &mut self.shares_mint,
+            self.fee_shares_account.as_deref(),
+            &self.shares_token_program,
+        )?;
+
+        let shares = self.withdrawal_request.shares;
+        let assets = self
+            .lp_vault
+            .preview_redeem(shares, self.shares_mint.supply)?;
+        self.burn_escrowed_shares(shares)?;
+
+        self.lp_vault.total_assets = self
+            .lp_vault
+            .total_assets
+            .checked_sub(assets)
+            .ok_or(ErrorCode::ArithmeticUnderflow)?;
+
+        // The exit fee stays in the vault rather than being paid out
+        let exit_fee = LpVault::fee_on(assets, self.withdrawal_request.exit_fee_bps)?;
+        if exit_fee > 0 {
+            self.lp_vault.add_unvested_assets(exit_fee, now)?;
+            emit!(ExitFeeCharged {
+                vault: self.lp_vault.shares_mint,
+                owner: self.withdrawal_request.owner,
+                fee: exit_fee,
+            });
+        }
+        let assets = assets - exit_fee;
+
+        let (from_reserve, released) = self
+            .withdrawal_queue
+            .fill(&mut self.withdrawal_request, assets)?;
+        self.lp_vault.queue_reserved_assets = self
+            .lp_vault
+            .queue_reserved_assets
+            .checked_sub(from_reserve + released)
+            .ok_or(ErrorCode::ArithmeticUnderflow)?;
+
+        // Whatever the reserve doesn't cover must come from the vault's unborrowed tokens
+        let shortfall = assets - from_reserve;
+        require_gte!(
+            self.vault.amount,
+            shortfall,
+            ErrorCode::InsufficientAvailablePrincipal
+        );
+        let balance_before = self.queue_vault.amount;
+        transfer_from_vault_to_queue(
+            &self.lp_vault,
+            &self.vault,
+            &self.queue_vault,
+            &self.asset_mint,
+            &self.asset_token_program,
+            shortfall,
+        )?;
+        let received = balance_increase(&mut self.queue_vault, balance_before)?;
+
+        // Any transfer fee withheld from the shortfall is borne by the request, as it would be
+        // on a direct withdrawal
+        let transfer_fee = shortfall - received;
+        self.withdrawal_request.assets = self
+            .withdrawal_request
+            .assets
+            .checked_sub(transfer_fee)
+            .ok_or(ErrorCode::ArithmeticUnderflow)?;
+        self.withdrawal_queue.unclaimed_assets = self
+            .withdrawal_queue
+            .unclaimed_assets
+            .checked_sub(transfer_fee)
+            .ok_or(ErrorCode::ArithmeticUnderflow)?;
+
+        if released > 0 {
+            self.vault.reload()?;
+            let balance_before = self.vault.amount;
+            self.transfer_from_queue_to_vault(released)?;
+            let released_received = balance_increase(&mut self.vault, balance_before)?;
+
+            // Any transfer fee withheld from the released reserve is a loss for the LP Vault
+            self.lp_vault.total_assets = self
+                .lp_vault
+                .total_assets
+                .checked_sub(released - released_received)
+                .ok_or(ErrorCode::ArithmeticUnderflow)?;
+        }
+
+        emit!(WithdrawalFilled {
+            vault: self.lp_vault.shares_mint,
+            owner: self.withdrawal_request.owner,
+            request: self.withdrawal_request.key(),
+            request_id: self.withdrawal_request.id,
+            assets: self.withdrawal_request.assets,
+            shares,
+        });
+
+        Ok(())
+    }
+}
+
+fn transfer_from_vault_to_queue<'info>(
+    lp_vault: &Account<'info, LpVault>,
+    vault: &InterfaceAccount<'info, TokenAccount>,
+    queue_vault: &InterfaceAccount<'info, TokenAccount>,
+    asset_mint: &InterfaceAccount<'info, Mint>,
+    asset_token_program: &Interface<'info, TokenInterface>,
+    amount: u64,
+) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+
+    let cpi_accounts = TransferChecked {
+        from: vault.to_account_info(),
+        mint: asset_mint.to_account_info(),
+        to: queue_vault.to_account_info(),
+        authority: lp_vault.to_account_info(),
+    };
+    let cpi_ctx = CpiContext {
+        program: asset_token_program.to_account_info(),
+        accounts: cpi_accounts,
+        remaining_accounts: Vec::new(),
+        signer_seeds: &[lp_vault_signer_seeds!(lp_vault)],
+    };
+    token_interface::transfer_checked(cpi_ctx, amount, asset_mint.decimals)
+}
+
+/// Reserves up to `available` tokens from the LP Vault for pending withdrawal requests, moving
+/// them to the queue's token account so they can no longer be borrowed.
+pub(crate) fn reserve_for_queued_withdrawals<'info>(
+    withdrawal_queue: &mut WithdrawalQueue,
+    lp_vault: &mut Account<'info, LpVault>,
+    vault: &InterfaceAccount<'info, TokenAccount>,
+    queue_vault: &mut InterfaceAccount<'info, TokenAccount>,
+    asset_mint: &InterfaceAccount<'info, Mint>,
+    asset_token_program: &Interface<'info, TokenInterface>,
+    available: u64,
+) -> Result<()> {
+    require_keys_eq!(
+        withdrawal_queue.queue_vault,
+        queue_vault.key(),
+        anchor_lang::error::ErrorCode::ConstraintHasOne
+    );
+
+    let amount = withdrawal_queue.reservable(available);
+    if amount == 0 {
+        return Ok(());
+    }
+    let balance_before = queue_vault.amount;
+    transfer_from_vault_to_queue(
+        lp_vault,
+        vault,
+        queue_vault,
+        asset_mint,
+        asset_token_program,
+        amount,
+    )?;
+    let received = balance_increase(queue_vault, balance_before)?;
+
+    withdrawal_queue.reserve(received)?;
+    lp_vault.queue_reserved_assets = lp_vault
+        .queue_reserved_assets
+        .checked_add(received)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+    // Any transfer fee withheld from the reserve is a loss for the LP Vault
+    lp_vault.total_assets = lp_vault
+        .total_assets
+        .checked_sub(amount - received)
+        .ok_or(ErrorCode::ArithmeticUnderflow)?;
+
+    emit!(WithdrawalQueueReserved {
+        vault: lp_vault.shares_mint,
+        assets: received,
+        reserved_assets: withdrawal_queue.reserved_assets,
+    });
+
+    Ok(())
+}
+
+/// Reserves up to `available` deposited or repaid tokens for the LP Vault's withdrawal queue.
+/// Inflows must not bypass the queue, so once it has been initialised `withdrawal_queue` and
+/// `queue_vault` are required.
+pub(crate) fn reserve_repayment_for_queue<'info>(
+    withdrawal_queue: Option<&AccountInfo<'info>>,
+    queue_vault: Option<&mut InterfaceAccount<'info, TokenAccount>>,
+    lp_vault: &mut Account<'info, LpVault>,
+    vault: &InterfaceAccount<'info, TokenAccount>,
+    asset_mint: &InterfaceAccount<'info, Mint>,
+    asset_token_program: &Interface<'info, TokenInterface>,
+    available: u64,
+) -> Result<()> {
+    if !lp_vault.has_withdrawal_queue {
+        return Ok(());
+    }
+    let (Some(withdrawal_queue), Some(queue_vault)) = (withdrawal_queue, queue_vault) else {
+        return err!(ErrorCode::MissingWithdrawalQueueAccounts);
+    };
+
+    let mut queue = WithdrawalQueue::try_deserialize(&mut &withdrawal_queue.data.borrow()[..])?;
+    reserve_for_queued_withdrawals(
+        &mut queue,
+        lp_vault,
+        vault,
+        queue_vault,
+        asset_mint,
+        asset_token_program,
+        available,
+    )?;
+    queue.try_serialize(&mut &mut withdrawal_queue.data.borrow_mut()[..])
+}
+
+/// Reserves the LP Vault's unborrowed tokens for its withdrawal queue before `amount` is paid out
+/// of `vault` directly, so withdrawals can't jump the queue. Once the queue has been initialised
+/// `withdrawal_queue` and `queue_vault` are required.
+pub(crate) fn reserve_before_withdrawal<'info>(
+    withdrawal_queue: Option<&AccountInfo<'info>>,
+    queue_vault: Option<&mut InterfaceAccount<'info, TokenAccount>>,
+    lp_vault: &mut Account<'info, LpVault>,
+    vault: &mut InterfaceAccount<'info, TokenAccount>,
+    asset_mint: &InterfaceAccount<'info, Mint>,
+    asset_token_program: &Interface<'info, TokenInterface>,
+    amount: u64,
+) -> Result<()> {
+    reserve_repayment_for_queue(
+        withdrawal_queue,
+        queue_vault,
+        lp_vault,
+        vault,
+        asset_mint,
+        asset_token_program,
+        vault.amount,
+    )?;
+    vault.reload()?;
+    require_gte!(
+        vault.amount,
+        amount,
+        ErrorCode::InsufficientAvailablePrincipal
+    );
+    Ok(())
+}
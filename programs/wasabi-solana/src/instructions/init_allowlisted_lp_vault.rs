@@ -0,0 +1,151 @@
+use {
+    super::{
+        allowlist_extra_account_metas, initialize_shares_token_metadata, InitLpVaultArgs,
+        EXTRA_ACCOUNT_METAS_LEN,
+    },
+    crate::{
+        error::ErrorCode, events::NewVault, validate_mint, AllowedMint, LpVault, MintPolicy,
+        Permission,
+    },
+    anchor_lang::prelude::*,
+    anchor_spl::{
+        associated_token::AssociatedToken,
+        token_interface::{Mint, Token2022, TokenAccount, TokenInterface},
+    },
+    spl_tlv_account_resolution::state::ExtraAccountMetaList,
+    spl_transfer_hook_interface::instruction::ExecuteInstruction,
+};
+
+// Same as `InitLpVaultWithTokenMetadata`, but the vault starts with its allowlist enabled and the
+// shares mint is created with a transfer hook, so shares can only be transferred to allowlisted
+// wallets.
+
+#[derive(Accounts)]
+pub struct InitAllowlistedLpVault<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// The key that has permission to init the vault
+    pub authority: Signer<'info>,
+
+    #[account(
+        has_one = authority,
+    )]
+    pub permission: Box<Account<'info, Permission>>,
+
+    #[account(
+        init,
+        payer = payer,
+        seeds = [b"lp_vault", asset_mint.key().as_ref()],
+        bump,
+        space = 8 + std::mem::size_of::<LpVault>(),
+    )]
+    pub lp_vault: Box<Account<'info, LpVault>>,
+
+    pub asset_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Overrides the default `MintPolicy`, which rejects every risky mint feature
+    #[account(
+        seeds = [b"mint_policy"],
+        bump = mint_policy.bump,
+    )]
+    pub mint_policy: Option<Box<Account<'info, MintPolicy>>>,
+
+    /// Present if the asset mint is exempt from the `MintPolicy`
+    #[account(
+        seeds = [b"allowed_mint", asset_mint.key().as_ref()],
+        bump = allowed_asset_mint.bump,
+    )]
+    pub allowed_asset_mint: Option<Box<Account<'info, AllowedMint>>>,
+
+    // Due to stack frame limit we should init the `lp_vault`'s ata beforehand
+    #[account(
+        associated_token::mint = asset_mint,
+        associated_token::authority = lp_vault,
+        associated_token::token_program = asset_token_program,
+    )]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init,
+        payer = payer,
+        seeds = [lp_vault.key().as_ref(), asset_mint.key().as_ref()],
+        bump,
+        mint::authority = lp_vault,
+        mint::decimals = asset_mint.decimals,
+        mint::token_program = shares_token_program,
+        extensions::metadata_pointer::authority = lp_vault,
+        extensions::metadata_pointer::metadata_address = shares_mint,
+        extensions::transfer_hook::authority = lp_vault,
+        extensions::transfer_hook::program_id = crate::ID,
+    )]
+    pub shares_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// CHECK: Initialized with the accounts the transfer hook needs
+    #[account(
+        init,
+        payer = payer,
+        seeds = [b"extra-account-metas", shares_mint.key().as_ref()],
+        bump,
+        space = ExtraAccountMetaList::size_of(EXTRA_ACCOUNT_METAS_LEN)?,
+    )]
+    pub extra_account_meta_list: AccountInfo<'info>,
+
+    pub asset_token_program: Interface<'info, TokenInterface>,
+    pub shares_token_program: Program<'info, Token2022>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> InitAllowlistedLpVault<'info> {
+    pub fn validate(ctx: &Context<InitAllowlistedLpVault>) -> Result<()> {
+        require!(
+            ctx.accounts.permission.can_init_vault(),
+            ErrorCode::InvalidPermissions
+        );
+        validate_mint(
+            &ctx.accounts.asset_mint.to_account_info(),
+            ctx.accounts.mint_policy.as_deref().map(|policy| &**policy),
+            ctx.accounts
+                .allowed_asset_mint
+                .as_deref()
+                .map(|allowed| &**allowed),
+        )?;
+        Ok(())
+    }
+
+    pub fn init_allowlisted_lp_vault(
+        &mut self,
+        args: &InitLpVaultArgs,
+        bumps: &InitAllowlistedLpVaultBumps,
+    ) -> Result<()> {
+        // The vault signs the metadata CPI, so its seeds must be set first
+        self.lp_vault.set_inner(LpVault {
+            allowlist_enabled: true,
+            ..LpVault::new(
+                bumps.lp_vault,
+                self.asset_mint.key(),
+                self.vault.key(),
+                self.shares_mint.key(),
+                Clock::get()?.unix_timestamp,
+            )
+        });
+
+        initialize_shares_token_metadata(
+            &self.payer,
+            &self.lp_vault,
+            &self.shares_mint,
+            &self.shares_token_program,
+            &self.system_program,
+            args,
+        )?;
+
+        ExtraAccountMetaList::init::<ExecuteInstruction>(
+            &mut self.extra_account_meta_list.try_borrow_mut_data()?,
+            &allowlist_extra_account_metas(&self.lp_vault.key())?,
+        )?;
+
+        emit!(NewVault::new(&self.lp_vault));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,38 @@
+use {
+    crate::{Depositor, LpVault},
+    anchor_lang::prelude::*,
+};
+
+#[derive(Accounts)]
+pub struct InitDepositor<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub lp_vault: Account<'info, LpVault>,
+
+    #[account(
+        init,
+        payer = owner,
+        seeds = [b"depositor", lp_vault.key().as_ref(), owner.key().as_ref()],
+        bump,
+        space = 8 + std::mem::size_of::<Depositor>(),
+    )]
+    pub depositor: Account<'info, Depositor>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> InitDepositor<'info> {
+    pub fn init_depositor(&mut self, bumps: &InitDepositorBumps) -> Result<()> {
+        self.depositor.set_inner(Depositor {
+            owner: self.owner.key(),
+            lp_vault: self.lp_vault.key(),
+            deposited: 0,
+            bump: bumps.depositor,
+            // Shares transferred to a new wallet wait out the cooldown like a deposit
+            last_deposit_timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+}
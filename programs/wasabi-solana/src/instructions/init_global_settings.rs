@@ -43,6 +43,7 @@ impl<'info> InitGlobalSettings<'info> {
             fee_wallet: args.fee_wallet,
             liquidation_wallet: args.liquidation_wallet,
             statuses: args.statuses,
+            max_exit_order_execution_fee: u64::MAX,
         });
 
         self.super_admin_permission.set_inner(Permission {
@@ -0,0 +1,187 @@
+use {
+    crate::{
+        debt_controller::LEVERAGE_DENOMINATOR, error::ErrorCode, events::LimitOrderPlaced,
+        utils::balance_increase, BasePool, DebtController, GlobalSettings, LimitOpenOrder, LpVault,
+    },
+    anchor_lang::prelude::*,
+    anchor_spl::{
+        associated_token::AssociatedToken,
+        token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked},
+    },
+};
+
+// Only the trader can place a limit open order. The order is executed by a backend authority
+// through `execute_limit_open_order_setup` and `execute_limit_open_order_cleanup`.
+
+#[derive(Accounts)]
+#[instruction(args: InitLimitOpenOrderArgs)]
+pub struct InitLimitOpenOrder<'info> {
+    #[account(mut)]
+    pub trader: Signer<'info>,
+
+    /// The trader's account that holds the down payment currency
+    #[account(
+        mut,
+        associated_token::mint = payment_mint,
+        associated_token::authority = trader,
+        associated_token::token_program = token_program,
+    )]
+    pub trader_payment_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The pool the position will be opened in
+    pub pool: Box<Account<'info, BasePool>>,
+
+    /// The LP Vault that the position will borrow from
+    #[account(
+        constraint = lp_vault.asset == pool.currency @ ErrorCode::InvalidPool,
+    )]
+    pub lp_vault: Box<Account<'info, LpVault>>,
+
+    /// The mint of the down payment: `currency` for long, `collateral` for short pools
+    #[account(
+        constraint = payment_mint.key() == if pool.is_long_pool {
+            pool.currency
+        } else {
+            pool.collateral
+        } @ ErrorCode::MintMismatch,
+    )]
+    pub payment_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init,
+        payer = trader,
+        seeds = [
+            b"limit_open_order",
+            trader.key().as_ref(),
+            pool.key().as_ref(),
+            &args.nonce.to_le_bytes(),
+        ],
+        bump,
+        space = 8 + std::mem::size_of::<LimitOpenOrder>(),
+    )]
+    pub limit_open_order: Box<Account<'info, LimitOpenOrder>>,
+
+    /// Holds the down payment and fee until the order is executed or cancelled
+    #[account(
+        init,
+        payer = trader,
+        associated_token::mint = payment_mint,
+        associated_token::authority = limit_open_order,
+        associated_token::token_program = token_program,
+    )]
+    pub escrow: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        seeds = [b"debt_controller"],
+        bump,
+    )]
+    pub debt_controller: Box<Account<'info, DebtController>>,
+
+    #[account(
+        seeds = [b"global_settings"],
+        bump,
+    )]
+    pub global_settings: Box<Account<'info, GlobalSettings>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct InitLimitOpenOrderArgs {
+    /// Nonce used to derive both the order and the position addresses
+    pub nonce: u16,
+    pub down_payment: u64,
+    pub fee: u64,
+    /// Scaled by `LEVERAGE_DENOMINATOR`
+    pub leverage: u64,
+    pub maker_amount: u64,
+    pub taker_amount: u64,
+    pub expiration: i64,
+}
+
+impl<'info> InitLimitOpenOrder<'info> {
+    pub fn validate(
+        ctx: &Context<InitLimitOpenOrder>,
+        args: &InitLimitOpenOrderArgs,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.global_settings.can_trade(),
+            ErrorCode::UnpermittedIx
+        );
+        require!(
+            !ctx.accounts.pool.is_reduce_only(),
+            ErrorCode::PoolReduceOnly
+        );
+
+        let now = Clock::get()?.unix_timestamp;
+        require_gt!(args.expiration, now, ErrorCode::PositionReqExpired);
+
+        require_gt!(args.down_payment, 0, ErrorCode::ZeroAmount);
+        require_gt!(args.maker_amount, 0, ErrorCode::ZeroAmount);
+        require_gt!(args.taker_amount, 0, ErrorCode::ZeroAmount);
+
+        require_gt!(
+            args.leverage,
+            LEVERAGE_DENOMINATOR,
+            ErrorCode::InvalidLeverage
+        );
+        require_gte!(
+            ctx.accounts.debt_controller.max_leverage,
+            args.leverage,
+            ErrorCode::InvalidLeverage
+        );
+
+        Ok(())
+    }
+
+    fn transfer_from_trader_to_escrow(&self, amount: u64) -> Result<()> {
+        let cpi_accounts = TransferChecked {
+            from: self.trader_payment_account.to_account_info(),
+            mint: self.payment_mint.to_account_info(),
+            to: self.escrow.to_account_info(),
+            authority: self.trader.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(self.token_program.to_account_info(), cpi_accounts);
+        token_interface::transfer_checked(cpi_ctx, amount, self.payment_mint.decimals)
+    }
+
+    pub fn init_limit_open_order(
+        &mut self,
+        args: &InitLimitOpenOrderArgs,
+        bumps: &InitLimitOpenOrderBumps,
+    ) -> Result<()> {
+        let balance_before = self.escrow.amount;
+        self.transfer_from_trader_to_escrow(
+            args.down_payment
+                .checked_add(args.fee)
+                .ok_or(ErrorCode::ArithmeticOverflow)?,
+        )?;
+
+        // Any transfer fee withheld by the escrow comes out of the down payment
+        let down_payment = balance_increase(&mut self.escrow, balance_before)?
+            .checked_sub(args.fee)
+            .ok_or(ErrorCode::ArithmeticUnderflow)?;
+        require_gt!(down_payment, 0, ErrorCode::ZeroAmount);
+
+        self.limit_open_order.set_inner(LimitOpenOrder {
+            trader: self.trader.key(),
+            pool: self.pool.key(),
+            lp_vault: self.lp_vault.key(),
+            escrow: self.escrow.key(),
+            nonce: args.nonce,
+            down_payment,
+            fee: args.fee,
+            leverage: args.leverage,
+            maker_amount: args.maker_amount,
+            taker_amount: args.taker_amount,
+            expiration: args.expiration,
+            bump: bumps.limit_open_order,
+        });
+
+        emit!(LimitOrderPlaced::new(&self.limit_open_order));
+
+        Ok(())
+    }
+}
@@ -1,5 +1,5 @@
 use {
-    crate::{error::ErrorCode, BasePool, Permission},
+    crate::{error::ErrorCode, validate_mint, AllowedMint, BasePool, MintPolicy, Permission},
     anchor_lang::prelude::*,
     anchor_spl::{
         associated_token::AssociatedToken,
@@ -22,6 +22,27 @@ pub struct InitLongPool<'info> {
     pub collateral: Box<InterfaceAccount<'info, Mint>>,
     pub currency: Box<InterfaceAccount<'info, Mint>>,
 
+    /// Overrides the default `MintPolicy`, which rejects every risky mint feature
+    #[account(
+        seeds = [b"mint_policy"],
+        bump = mint_policy.bump,
+    )]
+    pub mint_policy: Option<Box<Account<'info, MintPolicy>>>,
+
+    /// Present if the collateral mint is exempt from the `MintPolicy`
+    #[account(
+        seeds = [b"allowed_mint", collateral.key().as_ref()],
+        bump = allowed_collateral.bump,
+    )]
+    pub allowed_collateral: Option<Box<Account<'info, AllowedMint>>>,
+
+    /// Present if the currency mint is exempt from the `MintPolicy`
+    #[account(
+        seeds = [b"allowed_mint", currency.key().as_ref()],
+        bump = allowed_currency.bump,
+    )]
+    pub allowed_currency: Option<Box<Account<'info, AllowedMint>>>,
+
     #[account(
         init,
         payer = payer,
@@ -61,6 +82,24 @@ impl<'info> InitLongPool<'info> {
             ctx.accounts.permission.can_init_pool(),
             ErrorCode::InvalidPermissions
         );
+
+        let mint_policy = ctx.accounts.mint_policy.as_deref().map(|policy| &**policy);
+        validate_mint(
+            &ctx.accounts.collateral.to_account_info(),
+            mint_policy,
+            ctx.accounts
+                .allowed_collateral
+                .as_deref()
+                .map(|allowed| &**allowed),
+        )?;
+        validate_mint(
+            &ctx.accounts.currency.to_account_info(),
+            mint_policy,
+            ctx.accounts
+                .allowed_currency
+                .as_deref()
+                .map(|allowed| &**allowed),
+        )?;
         Ok(())
     }
 
@@ -72,6 +111,7 @@ impl<'info> InitLongPool<'info> {
             currency: self.currency.key(),
             currency_vault: self.currency_vault.key(),
             bump: bumps.pool,
+            statuses: 0,
         });
 
         Ok(())
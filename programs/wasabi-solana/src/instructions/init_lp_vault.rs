@@ -1,6 +1,10 @@
+use anchor_lang::prelude::*;
+#[cfg(feature = "metaplex")]
 use {
-    crate::{error::ErrorCode, events::NewVault, LpVault, Permission},
-    anchor_lang::prelude::*,
+    crate::{
+        error::ErrorCode, events::NewVault, validate_mint, AllowedMint, LpVault, MintPolicy,
+        Permission,
+    },
     anchor_spl::{
         associated_token::AssociatedToken,
         metadata::Metadata,
@@ -12,6 +16,10 @@ use {
     },
 };
 
+// Creates the shares metadata through Metaplex. See `init_lp_vault_with_token_metadata` for
+// vaults using the Token-2022 metadata extension instead.
+
+#[cfg(feature = "metaplex")]
 #[derive(Accounts)]
 pub struct InitLpVault<'info> {
     #[account(mut)]
@@ -35,6 +43,20 @@ pub struct InitLpVault<'info> {
 
     pub asset_mint: Box<InterfaceAccount<'info, Mint>>,
 
+    /// Overrides the default `MintPolicy`, which rejects every risky mint feature
+    #[account(
+        seeds = [b"mint_policy"],
+        bump = mint_policy.bump,
+    )]
+    pub mint_policy: Option<Box<Account<'info, MintPolicy>>>,
+
+    /// Present if the asset mint is exempt from the `MintPolicy`
+    #[account(
+        seeds = [b"allowed_mint", asset_mint.key().as_ref()],
+        bump = allowed_asset_mint.bump,
+    )]
+    pub allowed_asset_mint: Option<Box<Account<'info, AllowedMint>>>,
+
     // Due to stack frame limit we should init the `lp_vault`'s ata beforehand
     #[account(
         associated_token::mint = asset_mint,
@@ -78,17 +100,26 @@ pub struct InitLpVault<'info> {
 
 #[derive(AnchorSerialize, AnchorDeserialize)]
 pub struct InitLpVaultArgs {
-    name: String,
-    symbol: String,
-    uri: String,
+    pub(crate) name: String,
+    pub(crate) symbol: String,
+    pub(crate) uri: String,
 }
 
+#[cfg(feature = "metaplex")]
 impl<'info> InitLpVault<'info> {
     pub fn validate(ctx: &Context<InitLpVault>) -> Result<()> {
         require!(
             ctx.accounts.permission.can_init_vault(),
             ErrorCode::InvalidPermissions
         );
+        validate_mint(
+            &ctx.accounts.asset_mint.to_account_info(),
+            ctx.accounts.mint_policy.as_deref().map(|policy| &**policy),
+            ctx.accounts
+                .allowed_asset_mint
+                .as_deref()
+                .map(|allowed| &**allowed),
+        )?;
         Ok(())
     }
 
@@ -119,7 +150,8 @@ impl<'info> InitLpVault<'info> {
             .mint(&self.shares_mint.to_account_info(), false)
             .authority(&self.lp_vault.to_account_info())
             .payer(&self.payer.to_account_info())
-            .update_authority(&self.authority.to_account_info(), true)
+            // The vault is the update authority so `update_shares_metadata` can sign for it
+            .update_authority(&self.lp_vault.to_account_info(), true)
             .system_program(&self.system_program)
             .sysvar_instructions(&self.sysvar_instructions.to_account_info())
             .spl_token_program(Some(&self.shares_token_program.to_account_info()))
@@ -140,15 +172,13 @@ impl<'info> InitLpVault<'info> {
     ) -> Result<()> {
         self.initialize_token_metadata(&args, bumps)?;
 
-        self.lp_vault.set_inner(LpVault {
-            bump: bumps.lp_vault,
-            asset: self.asset_mint.key(),
-            vault: self.vault.key(),
-            shares_mint: self.shares_mint.key(),
-            total_assets: 0,
-            total_borrowed: 0,
-            max_borrow: 0,
-        });
+        self.lp_vault.set_inner(LpVault::new(
+            bumps.lp_vault,
+            self.asset_mint.key(),
+            self.vault.key(),
+            self.shares_mint.key(),
+            Clock::get()?.unix_timestamp,
+        ));
 
         emit!(NewVault::new(&self.lp_vault));
 
@@ -0,0 +1,196 @@
+use {
+    super::InitLpVaultArgs,
+    crate::{
+        error::ErrorCode, events::NewVault, lp_vault_signer_seeds, validate_mint, AllowedMint,
+        LpVault, MintPolicy, Permission,
+    },
+    anchor_lang::{
+        prelude::*,
+        system_program::{self, Transfer},
+    },
+    anchor_spl::{
+        associated_token::AssociatedToken,
+        token_2022_extensions::{
+            spl_pod::optional_keys::OptionalNonZeroPubkey,
+            spl_token_metadata_interface::state::TokenMetadata, token_metadata_initialize,
+            TokenMetadataInitialize,
+        },
+        token_interface::{Mint, Token2022, TokenAccount, TokenInterface},
+    },
+};
+
+// Same as `InitLpVault`, but the shares metadata is stored on the mint itself through the
+// Token-2022 metadata-pointer and token-metadata extensions.
+
+#[derive(Accounts)]
+pub struct InitLpVaultWithTokenMetadata<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// The key that has permission to init the vault
+    pub authority: Signer<'info>,
+
+    #[account(
+        has_one = authority,
+    )]
+    pub permission: Box<Account<'info, Permission>>,
+
+    #[account(
+        init,
+        payer = payer,
+        seeds = [b"lp_vault", asset_mint.key().as_ref()],
+        bump,
+        space = 8 + std::mem::size_of::<LpVault>(),
+    )]
+    pub lp_vault: Box<Account<'info, LpVault>>,
+
+    pub asset_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Overrides the default `MintPolicy`, which rejects every risky mint feature
+    #[account(
+        seeds = [b"mint_policy"],
+        bump = mint_policy.bump,
+    )]
+    pub mint_policy: Option<Box<Account<'info, MintPolicy>>>,
+
+    /// Present if the asset mint is exempt from the `MintPolicy`
+    #[account(
+        seeds = [b"allowed_mint", asset_mint.key().as_ref()],
+        bump = allowed_asset_mint.bump,
+    )]
+    pub allowed_asset_mint: Option<Box<Account<'info, AllowedMint>>>,
+
+    // Due to stack frame limit we should init the `lp_vault`'s ata beforehand
+    #[account(
+        associated_token::mint = asset_mint,
+        associated_token::authority = lp_vault,
+        associated_token::token_program = asset_token_program,
+    )]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init,
+        payer = payer,
+        seeds = [lp_vault.key().as_ref(), asset_mint.key().as_ref()],
+        bump,
+        mint::authority = lp_vault,
+        mint::decimals = asset_mint.decimals,
+        mint::token_program = shares_token_program,
+        extensions::metadata_pointer::authority = lp_vault,
+        extensions::metadata_pointer::metadata_address = shares_mint,
+    )]
+    pub shares_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    pub asset_token_program: Interface<'info, TokenInterface>,
+    pub shares_token_program: Program<'info, Token2022>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> InitLpVaultWithTokenMetadata<'info> {
+    pub fn validate(ctx: &Context<InitLpVaultWithTokenMetadata>) -> Result<()> {
+        require!(
+            ctx.accounts.permission.can_init_vault(),
+            ErrorCode::InvalidPermissions
+        );
+        validate_mint(
+            &ctx.accounts.asset_mint.to_account_info(),
+            ctx.accounts.mint_policy.as_deref().map(|policy| &**policy),
+            ctx.accounts
+                .allowed_asset_mint
+                .as_deref()
+                .map(|allowed| &**allowed),
+        )?;
+        Ok(())
+    }
+
+    pub fn init_lp_vault_with_token_metadata(
+        &mut self,
+        args: &InitLpVaultArgs,
+        bumps: &InitLpVaultWithTokenMetadataBumps,
+    ) -> Result<()> {
+        // The vault signs the metadata CPI, so its seeds must be set first
+        self.lp_vault.set_inner(LpVault::new(
+            bumps.lp_vault,
+            self.asset_mint.key(),
+            self.vault.key(),
+            self.shares_mint.key(),
+            Clock::get()?.unix_timestamp,
+        ));
+
+        initialize_shares_token_metadata(
+            &self.payer,
+            &self.lp_vault,
+            &self.shares_mint,
+            &self.shares_token_program,
+            &self.system_program,
+            args,
+        )?;
+
+        emit!(NewVault::new(&self.lp_vault));
+
+        Ok(())
+    }
+}
+
+/// Tops up the mint so it stays rent exempt once the token program reallocs it for the metadata
+fn fund_token_metadata<'info>(
+    payer: &Signer<'info>,
+    shares_mint: &InterfaceAccount<'info, Mint>,
+    system_program: &Program<'info, System>,
+    metadata: &TokenMetadata,
+) -> Result<()> {
+    let shares_mint = shares_mint.to_account_info();
+    let space = shares_mint
+        .data_len()
+        .checked_add(metadata.tlv_size_of()?)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+    let lamports = Rent::get()?
+        .minimum_balance(space)
+        .saturating_sub(shares_mint.lamports());
+
+    if lamports > 0 {
+        let cpi_accounts = Transfer {
+            from: payer.to_account_info(),
+            to: shares_mint,
+        };
+        let cpi_ctx = CpiContext::new(system_program.to_account_info(), cpi_accounts);
+        system_program::transfer(cpi_ctx, lamports)?;
+    }
+
+    Ok(())
+}
+
+/// Stores the shares metadata on a mint created with the metadata-pointer extension
+pub(crate) fn initialize_shares_token_metadata<'info>(
+    payer: &Signer<'info>,
+    lp_vault: &Account<'info, LpVault>,
+    shares_mint: &InterfaceAccount<'info, Mint>,
+    shares_token_program: &Program<'info, Token2022>,
+    system_program: &Program<'info, System>,
+    args: &InitLpVaultArgs,
+) -> Result<()> {
+    let metadata = TokenMetadata {
+        update_authority: OptionalNonZeroPubkey(lp_vault.key()),
+        mint: shares_mint.key(),
+        name: args.name.clone(),
+        symbol: args.symbol.clone(),
+        uri: args.uri.clone(),
+        additional_metadata: Vec::new(),
+    };
+    fund_token_metadata(payer, shares_mint, system_program, &metadata)?;
+
+    let cpi_accounts = TokenMetadataInitialize {
+        program_id: shares_token_program.to_account_info(),
+        metadata: shares_mint.to_account_info(),
+        update_authority: lp_vault.to_account_info(),
+        mint_authority: lp_vault.to_account_info(),
+        mint: shares_mint.to_account_info(),
+    };
+    let cpi_ctx = CpiContext {
+        program: shares_token_program.to_account_info(),
+        accounts: cpi_accounts,
+        remaining_accounts: Vec::new(),
+        signer_seeds: &[lp_vault_signer_seeds!(lp_vault)],
+    };
+    token_metadata_initialize(cpi_ctx, metadata.name, metadata.symbol, metadata.uri)
+}
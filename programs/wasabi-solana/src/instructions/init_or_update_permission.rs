@@ -2,6 +2,7 @@ use {
     crate::{
         AuthorityStatus, Permission, COSIGN_PERMISSION, INIT_POOL_PERMISSION,
         INIT_VAULT_PERMISSION, LIQUIDATE_PERMISSION, VAULT_BORROW_PERMISSION,
+        VAULT_MANAGER_PERMISSION,
     },
     anchor_lang::prelude::*,
 };
@@ -46,6 +47,7 @@ pub struct InitOrUpdatePermissionArgs {
     can_cosign_swaps: bool,
     can_init_pools: bool,
     can_borrow_from_vaults: bool,
+    can_manage_vaults: bool,
 }
 
 impl InitOrUpdatePermissionArgs {
@@ -66,6 +68,9 @@ impl InitOrUpdatePermissionArgs {
         if self.can_borrow_from_vaults {
             res += VAULT_BORROW_PERMISSION
         }
+        if self.can_manage_vaults {
+            res += VAULT_MANAGER_PERMISSION
+        }
         res
     }
 }
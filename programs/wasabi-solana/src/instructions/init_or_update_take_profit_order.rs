@@ -1,6 +1,11 @@
 use {
-    crate::{events::ExitOrder, Position, TakeProfitOrder},
-    anchor_lang::prelude::*,
+    crate::{
+        error::ErrorCode, events::ExitOrder, utils::transfer_lamports_from_pda, BasePool,
+        DebtController, ExitOrderPrice, ExitOrderType, GlobalSettings, OrderSide, Position,
+        TakeProfitOrder, DEFAULT_PRICE_DECIMALS,
+    },
+    anchor_lang::{prelude::*, system_program},
+    anchor_spl::token_interface::Mint,
 };
 
 // Only Position's trader can invoke InitTakeProfitOrder.
@@ -16,6 +21,18 @@ pub struct InitOrUpdateTakeProfitOrder<'info> {
     )]
     pub position: Account<'info, Position>,
 
+    /// The pool that owns the Position
+    #[account(
+        constraint = pool.collateral_vault == position.collateral_vault @ ErrorCode::InvalidPool,
+    )]
+    pub pool: Account<'info, BasePool>,
+
+    #[account(address = position.currency)]
+    pub currency: InterfaceAccount<'info, Mint>,
+
+    #[account(address = position.collateral)]
+    pub collateral: InterfaceAccount<'info, Mint>,
+
     #[account(
         init_if_needed,
         payer = trader,
@@ -25,19 +42,129 @@ pub struct InitOrUpdateTakeProfitOrder<'info> {
     )]
     pub take_profit_order: Account<'info, TakeProfitOrder>,
 
+    #[account(
+        seeds = [b"debt_controller"],
+        bump,
+    )]
+    pub debt_controller: Account<'info, DebtController>,
+
+    #[account(
+        seeds = [b"global_settings"],
+        bump,
+    )]
+    pub global_settings: Account<'info, GlobalSettings>,
+
     pub system_program: Program<'info, System>,
 }
 
 impl<'info> InitOrUpdateTakeProfitOrder<'info> {
+    /// Tops up or refunds the escrowed execution fee so the order holds exactly `execution_fee`.
+    /// Only raising the fee is checked against the cap, so lowering the cap doesn't lock orders.
+    fn fund_execution_fee(&self, execution_fee: u64) -> Result<()> {
+        let escrowed = self.take_profit_order.execution_fee;
+        if execution_fee > escrowed {
+            require_gte!(
+                self.global_settings.max_exit_order_execution_fee,
+                execution_fee,
+                ErrorCode::InvalidFee
+            );
+            let cpi_ctx = CpiContext::new(
+                self.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: self.trader.to_account_info(),
+                    to: self.take_profit_order.to_account_info(),
+                },
+            );
+            system_program::transfer(cpi_ctx, execution_fee - escrowed)?;
+        } else if escrowed > execution_fee {
+            transfer_lamports_from_pda(
+                &self.take_profit_order.to_account_info(),
+                &self.trader.to_account_info(),
+                escrowed - execution_fee,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Places the order from amounts, keeping the execution fee it already escrows
     pub fn init_or_update_take_profit_order(
         &mut self,
         maker_amount: u64,
         taker_amount: u64,
     ) -> Result<()> {
+        let execution_fee = self.take_profit_order.execution_fee;
+        self.init_or_update_take_profit_order_with_fee(maker_amount, taker_amount, execution_fee)
+    }
+
+    /// Places the order from amounts, escrowing exactly `execution_fee` for the executor
+    pub fn init_or_update_take_profit_order_with_fee(
+        &mut self,
+        maker_amount: u64,
+        taker_amount: u64,
+        execution_fee: u64,
+    ) -> Result<()> {
+        require_gt!(maker_amount, 0, ErrorCode::ZeroAmount);
+
+        let price = ExitOrderPrice::from_amounts(
+            ExitOrderType::TakeProfit,
+            maker_amount,
+            taker_amount,
+            self.collateral.decimals,
+            self.currency.decimals,
+            DEFAULT_PRICE_DECIMALS,
+        )?;
+
+        self.set_order(maker_amount, taker_amount, price, execution_fee)
+    }
+
+    /// Places the order at a normalized `price`, which is converted to amounts covering the whole
+    /// position using the decimals of the position's mints.
+    pub fn init_or_update_take_profit_order_at_price(
+        &mut self,
+        price: ExitOrderPrice,
+        execution_fee: u64,
+    ) -> Result<()> {
+        let maker_amount = self.position.collateral_amount;
+        let taker_amount = price.to_taker_amount(
+            maker_amount,
+            self.collateral.decimals,
+            self.currency.decimals,
+        )?;
+
+        self.set_order(maker_amount, taker_amount, price, execution_fee)
+    }
+
+    fn set_order(
+        &mut self,
+        maker_amount: u64,
+        taker_amount: u64,
+        price: ExitOrderPrice,
+        execution_fee: u64,
+    ) -> Result<()> {
+        let interest = self.debt_controller.compute_max_interest(
+            self.position.principal,
+            self.position.last_funding_timestamp,
+            Clock::get()?.unix_timestamp,
+        )?;
+
+        // Reject orders that can never be filled or would close the position with bad debt
+        self.position.validate_exit_order(
+            self.pool.is_long_pool,
+            maker_amount,
+            taker_amount,
+            interest,
+        )?;
+
+        self.fund_execution_fee(execution_fee)?;
+
         self.take_profit_order.set_inner(TakeProfitOrder {
             maker_amount,
             taker_amount,
             position: self.position.key(),
+            execution_fee,
+            price,
+            side: OrderSide::new(self.pool.is_long_pool),
         });
 
         emit!(ExitOrder {
@@ -0,0 +1,91 @@
+use {
+    crate::{error::ErrorCode, LpVault, Permission, RewardPool},
+    anchor_lang::prelude::*,
+    anchor_spl::{
+        associated_token::AssociatedToken,
+        token_2022::Token2022,
+        token_interface::{Mint, TokenAccount, TokenInterface},
+    },
+};
+
+#[derive(Accounts)]
+pub struct InitRewardPool<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// The key that has permission to init the vault
+    pub authority: Signer<'info>,
+
+    #[account(
+        has_one = authority,
+    )]
+    pub permission: Box<Account<'info, Permission>>,
+
+    #[account(has_one = shares_mint)]
+    pub lp_vault: Box<Account<'info, LpVault>>,
+
+    pub shares_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    pub reward_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init,
+        payer = payer,
+        seeds = [b"reward_pool", lp_vault.key().as_ref(), reward_mint.key().as_ref()],
+        bump,
+        space = 8 + std::mem::size_of::<RewardPool>(),
+    )]
+    pub reward_pool: Box<Account<'info, RewardPool>>,
+
+    /// Holds the funded rewards until they are claimed
+    #[account(
+        init,
+        payer = payer,
+        associated_token::mint = reward_mint,
+        associated_token::authority = reward_pool,
+        associated_token::token_program = reward_token_program,
+    )]
+    pub reward_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Holds the staked shares
+    #[account(
+        init,
+        payer = payer,
+        associated_token::mint = shares_mint,
+        associated_token::authority = reward_pool,
+        associated_token::token_program = shares_token_program,
+    )]
+    pub stake_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub reward_token_program: Interface<'info, TokenInterface>,
+    pub shares_token_program: Program<'info, Token2022>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> InitRewardPool<'info> {
+    pub fn validate(ctx: &Context<InitRewardPool>) -> Result<()> {
+        require!(
+            ctx.accounts.permission.can_init_vault(),
+            ErrorCode::InvalidPermissions
+        );
+        Ok(())
+    }
+
+    pub fn init_reward_pool(&mut self, bumps: &InitRewardPoolBumps) -> Result<()> {
+        self.reward_pool.set_inner(RewardPool {
+            lp_vault: self.lp_vault.key(),
+            shares_mint: self.shares_mint.key(),
+            reward_mint: self.reward_mint.key(),
+            reward_vault: self.reward_vault.key(),
+            stake_vault: self.stake_vault.key(),
+            emission_rate: 0,
+            remaining_rewards: 0,
+            total_staked: 0,
+            reward_per_share: 0,
+            last_update_timestamp: Clock::get()?.unix_timestamp,
+            bump: bumps.reward_pool,
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,57 @@
+use {
+    crate::{error::ErrorCode, LpVault, Permission, SharePriceHistory},
+    anchor_lang::prelude::*,
+    anchor_spl::token_interface::Mint,
+};
+
+#[derive(Accounts)]
+pub struct InitSharePriceHistory<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// The key that has permission to init the vault
+    pub authority: Signer<'info>,
+
+    #[account(
+        has_one = authority,
+    )]
+    pub permission: Account<'info, Permission>,
+
+    #[account(
+        mut,
+        has_one = shares_mint,
+    )]
+    pub lp_vault: Account<'info, LpVault>,
+
+    pub shares_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = payer,
+        seeds = [b"share_price_history", lp_vault.key().as_ref()],
+        bump,
+        space = 8 + std::mem::size_of::<SharePriceHistory>(),
+    )]
+    pub share_price_history: Box<Account<'info, SharePriceHistory>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> InitSharePriceHistory<'info> {
+    pub fn validate(ctx: &Context<InitSharePriceHistory>) -> Result<()> {
+        require!(
+            ctx.accounts.permission.can_init_vault(),
+            ErrorCode::InvalidPermissions
+        );
+        Ok(())
+    }
+
+    pub fn init_share_price_history(&mut self, bumps: &InitSharePriceHistoryBumps) -> Result<()> {
+        self.share_price_history.set_inner(SharePriceHistory::new(
+            self.lp_vault.key(),
+            bumps.share_price_history,
+        ));
+        self.lp_vault.has_share_price_history = true;
+        self.share_price_history
+            .checkpoint(&self.lp_vault, self.shares_mint.supply)
+    }
+}
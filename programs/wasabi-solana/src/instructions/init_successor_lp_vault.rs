@@ -0,0 +1,177 @@
+use {
+    super::{initialize_shares_token_metadata, InitLpVaultArgs},
+    crate::{
+        error::ErrorCode,
+        events::{NewVault, SuccessorVaultSet},
+        mint_features, validate_mint, AllowedMint, LpVault, MintPolicy, Permission,
+        MINT_TRANSFER_HOOK,
+    },
+    anchor_lang::prelude::*,
+    anchor_spl::{
+        associated_token::AssociatedToken,
+        token_interface::{Mint, Token2022, TokenAccount, TokenInterface},
+    },
+};
+
+// Creates the next version of a decommissioned vault for the same asset, which its LPs can
+// migrate into with `migrate_lp_shares`. The shares metadata is stored on the mint like
+// `InitLpVaultWithTokenMetadata`, and the successor carries over the predecessor's configuration.
+// Vaults whose shares mint has the allowlist transfer hook are succeeded with
+// `init_allowlisted_successor_lp_vault` instead.
+
+#[derive(Accounts)]
+#[instruction(version: u8)]
+pub struct InitSuccessorLpVault<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// The key that has permission to init the vault
+    pub authority: Signer<'info>,
+
+    #[account(
+        has_one = authority,
+    )]
+    pub permission: Box<Account<'info, Permission>>,
+
+    #[account(
+        mut,
+        constraint = predecessor_lp_vault.asset == asset_mint.key() @ ErrorCode::InvalidSuccessorVault,
+    )]
+    pub predecessor_lp_vault: Box<Account<'info, LpVault>>,
+
+    #[account(address = predecessor_lp_vault.shares_mint)]
+    pub predecessor_shares_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init,
+        payer = payer,
+        seeds = [b"lp_vault", asset_mint.key().as_ref(), &[version]],
+        bump,
+        space = 8 + std::mem::size_of::<LpVault>(),
+    )]
+    pub lp_vault: Box<Account<'info, LpVault>>,
+
+    pub asset_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Overrides the default `MintPolicy`, which rejects every risky mint feature
+    #[account(
+        seeds = [b"mint_policy"],
+        bump = mint_policy.bump,
+    )]
+    pub mint_policy: Option<Box<Account<'info, MintPolicy>>>,
+
+    /// Present if the asset mint is exempt from the `MintPolicy`
+    #[account(
+        seeds = [b"allowed_mint", asset_mint.key().as_ref()],
+        bump = allowed_asset_mint.bump,
+    )]
+    pub allowed_asset_mint: Option<Box<Account<'info, AllowedMint>>>,
+
+    // Due to stack frame limit we should init the `lp_vault`'s ata beforehand
+    #[account(
+        associated_token::mint = asset_mint,
+        associated_token::authority = lp_vault,
+        associated_token::token_program = asset_token_program,
+    )]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init,
+        payer = payer,
+        seeds = [lp_vault.key().as_ref(), asset_mint.key().as_ref()],
+        bump,
+        mint::authority = lp_vault,
+        mint::decimals = asset_mint.decimals,
+        mint::token_program = shares_token_program,
+        extensions::metadata_pointer::authority = lp_vault,
+        extensions::metadata_pointer::metadata_address = shares_mint,
+    )]
+    pub shares_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    pub asset_token_program: Interface<'info, TokenInterface>,
+    pub shares_token_program: Program<'info, Token2022>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> InitSuccessorLpVault<'info> {
+    pub fn validate(ctx: &Context<InitSuccessorLpVault>, version: u8) -> Result<()> {
+        validate_successor(
+            &ctx.accounts.permission,
+            &ctx.accounts.predecessor_lp_vault,
+            &ctx.accounts.predecessor_shares_mint.to_account_info(),
+            version,
+            false,
+        )?;
+        validate_mint(
+            &ctx.accounts.asset_mint.to_account_info(),
+            ctx.accounts.mint_policy.as_deref().map(|policy| &**policy),
+            ctx.accounts
+                .allowed_asset_mint
+                .as_deref()
+                .map(|allowed| &**allowed),
+        )?;
+        Ok(())
+    }
+
+    pub fn init_successor_lp_vault(
+        &mut self,
+        args: &InitLpVaultArgs,
+        bumps: &InitSuccessorLpVaultBumps,
+    ) -> Result<()> {
+        // The vault signs the metadata CPI, so its seeds must be set first
+        self.lp_vault
+            .set_inner(self.predecessor_lp_vault.new_successor(
+                bumps.lp_vault,
+                self.vault.key(),
+                self.shares_mint.key(),
+                Clock::get()?.unix_timestamp,
+            )?);
+
+        initialize_shares_token_metadata(
+            &self.payer,
+            &self.lp_vault,
+            &self.shares_mint,
+            &self.shares_token_program,
+            &self.system_program,
+            args,
+        )?;
+
+        self.predecessor_lp_vault.successor = self.lp_vault.key();
+
+        emit!(NewVault::new(&self.lp_vault));
+        emit!(SuccessorVaultSet {
+            vault: self.predecessor_lp_vault.shares_mint,
+            successor: self.lp_vault.shares_mint,
+        });
+
+        Ok(())
+    }
+}
+
+/// Ensures `version` is the next version of the decommissioned `predecessor`, which has no
+/// successor yet, and that the successor's shares mint has the allowlist transfer hook exactly
+/// when the predecessor's does
+pub(crate) fn validate_successor(
+    permission: &Permission,
+    predecessor: &LpVault,
+    predecessor_shares_mint: &AccountInfo,
+    version: u8,
+    transfer_hook: bool,
+) -> Result<()> {
+    require!(permission.can_init_vault(), ErrorCode::InvalidPermissions);
+
+    require!(
+        predecessor.decommissioned,
+        ErrorCode::VaultNotDecommissioned
+    );
+    require!(
+        predecessor.successor == Pubkey::default()
+            && predecessor.version.checked_add(1) == Some(version),
+        ErrorCode::InvalidSuccessorVault
+    );
+    require!(
+        (mint_features(predecessor_shares_mint)? & MINT_TRANSFER_HOOK != 0) == transfer_hook,
+        ErrorCode::InvalidSuccessorVault
+    );
+    Ok(())
+}
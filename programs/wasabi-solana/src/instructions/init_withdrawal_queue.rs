@@ -0,0 +1,95 @@
+use {
+    crate::{error::ErrorCode, LpVault, Permission, WithdrawalQueue},
+    anchor_lang::prelude::*,
+    anchor_spl::{
+        associated_token::AssociatedToken,
+        token_2022::Token2022,
+        token_interface::{Mint, TokenAccount, TokenInterface},
+    },
+};
+
+#[derive(Accounts)]
+pub struct InitWithdrawalQueue<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// The key that has permission to init the vault
+    pub authority: Signer<'info>,
+
+    #[account(
+        has_one = authority,
+    )]
+    pub permission: Account<'info, Permission>,
+
+    #[account(
+        mut,
+        has_one = shares_mint,
+    )]
+    pub lp_vault: Account<'info, LpVault>,
+
+    #[account(address = lp_vault.asset)]
+    pub asset_mint: InterfaceAccount<'info, Mint>,
+
+    pub shares_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = payer,
+        seeds = [b"withdrawal_queue", lp_vault.key().as_ref()],
+        bump,
+        space = 8 + std::mem::size_of::<WithdrawalQueue>(),
+    )]
+    pub withdrawal_queue: Account<'info, WithdrawalQueue>,
+
+    /// Holds the reserved and filled assets until they are claimed
+    #[account(
+        init,
+        payer = payer,
+        associated_token::mint = asset_mint,
+        associated_token::authority = withdrawal_queue,
+        associated_token::token_program = asset_token_program,
+    )]
+    pub queue_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// Holds the shares of pending requests until they are filled
+    #[account(
+        init,
+        payer = payer,
+        associated_token::mint = shares_mint,
+        associated_token::authority = withdrawal_queue,
+        associated_token::token_program = shares_token_program,
+    )]
+    pub shares_escrow: InterfaceAccount<'info, TokenAccount>,
+
+    pub asset_token_program: Interface<'info, TokenInterface>,
+    pub shares_token_program: Program<'info, Token2022>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> InitWithdrawalQueue<'info> {
+    pub fn validate(ctx: &Context<InitWithdrawalQueue>) -> Result<()> {
+        require!(
+            ctx.accounts.permission.can_init_vault(),
+            ErrorCode::InvalidPermissions
+        );
+        Ok(())
+    }
+
+    pub fn init_withdrawal_queue(&mut self, bumps: &InitWithdrawalQueueBumps) -> Result<()> {
+        self.withdrawal_queue.set_inner(WithdrawalQueue {
+            lp_vault: self.lp_vault.key(),
+            queue_vault: self.queue_vault.key(),
+            shares_escrow: self.shares_escrow.key(),
+            pending_shares: 0,
+            pending_assets: 0,
+            reserved_assets: 0,
+            unclaimed_assets: 0,
+            next_request_id: 0,
+            next_fill_id: 0,
+            bump: bumps.withdrawal_queue,
+        });
+        self.lp_vault.has_withdrawal_queue = true;
+
+        Ok(())
+    }
+}
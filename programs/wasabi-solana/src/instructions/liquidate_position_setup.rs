@@ -23,7 +23,7 @@ impl<'info> LiquidatePositionSetup<'info> {
         ClosePositionSetup::validate(
             &ctx.accounts.close_position_setup,
             expiration,
-            LiquidatePositionCleanup::get_hash(),
+            &[LiquidatePositionCleanup::get_hash()],
         )?;
 
         Ok(())
@@ -0,0 +1,36 @@
+use {
+    crate::{utils::migrate_account, BasePool, BasePoolV0},
+    anchor_lang::prelude::*,
+};
+
+// Anyone can migrate a pool created with the original `BasePool` layout, paying the rent for the
+// added `statuses`. Migrated pools start with no paused operations.
+
+#[derive(Accounts)]
+pub struct MigrateBasePool<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: A pool in the original layout, checked when it is parsed
+    #[account(
+        mut,
+        owner = crate::ID,
+    )]
+    pub pool: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> MigrateBasePool<'info> {
+    pub fn migrate_base_pool(&mut self) -> Result<()> {
+        let legacy = BasePoolV0::try_from_account_data(&self.pool.try_borrow_data()?)?;
+
+        migrate_account(
+            &self.pool,
+            &self.payer,
+            &self.system_program,
+            &legacy.migrate(),
+            8 + std::mem::size_of::<BasePool>(),
+        )
+    }
+}
@@ -0,0 +1,103 @@
+use {
+    crate::{
+        error::ErrorCode, utils::migrate_account, BasePool, ExitOrderPrice, ExitOrderType,
+        ExitOrderV0, OrderSide, Position, StopLossOrder, TakeProfitOrder, DEFAULT_PRICE_DECIMALS,
+    },
+    anchor_lang::{prelude::*, Discriminator},
+    anchor_spl::token_interface::Mint,
+};
+
+// Anyone can migrate a take profit or stop loss order created with the original layout, paying
+// the rent for the added fields. Orders from before execution fees were escrowed hold none, so
+// they are executed without paying the executor. The pool must be migrated first.
+
+#[derive(Accounts)]
+pub struct MigrateExitOrder<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub position: Account<'info, Position>,
+
+    /// The pool that owns the Position
+    #[account(
+        constraint = pool.collateral_vault == position.collateral_vault @ ErrorCode::InvalidPool,
+    )]
+    pub pool: Account<'info, BasePool>,
+
+    #[account(address = position.currency)]
+    pub currency: InterfaceAccount<'info, Mint>,
+
+    #[account(address = position.collateral)]
+    pub collateral: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: The position's take profit or stop loss order in the original layout, checked
+    /// against its seeds and when it is parsed
+    #[account(
+        mut,
+        owner = crate::ID,
+    )]
+    pub order: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> MigrateExitOrder<'info> {
+    pub fn migrate_exit_order(&mut self, order_type: ExitOrderType) -> Result<()> {
+        let discriminator = match order_type {
+            ExitOrderType::TakeProfit => TakeProfitOrder::DISCRIMINATOR,
+            ExitOrderType::StopLoss => StopLossOrder::DISCRIMINATOR,
+        };
+        let (order_address, _) = Pubkey::find_program_address(
+            &[order_type.seed(), self.position.key().as_ref()],
+            &crate::ID,
+        );
+        require_keys_eq!(
+            self.order.key(),
+            order_address,
+            anchor_lang::error::ErrorCode::ConstraintSeeds
+        );
+
+        let legacy =
+            ExitOrderV0::try_from_account_data(&self.order.try_borrow_data()?, discriminator)?;
+        let price = ExitOrderPrice::from_amounts_with_min_decimals(
+            order_type,
+            legacy.maker_amount,
+            legacy.taker_amount,
+            self.collateral.decimals,
+            self.currency.decimals,
+            DEFAULT_PRICE_DECIMALS,
+        )?;
+        let side = OrderSide::new(self.pool.is_long_pool);
+
+        match order_type {
+            ExitOrderType::TakeProfit => migrate_account(
+                &self.order,
+                &self.payer,
+                &self.system_program,
+                &TakeProfitOrder {
+                    position: legacy.position,
+                    maker_amount: legacy.maker_amount,
+                    taker_amount: legacy.taker_amount,
+                    execution_fee: 0,
+                    price,
+                    side,
+                },
+                8 + std::mem::size_of::<TakeProfitOrder>(),
+            ),
+            ExitOrderType::StopLoss => migrate_account(
+                &self.order,
+                &self.payer,
+                &self.system_program,
+                &StopLossOrder {
+                    position: legacy.position,
+                    maker_amount: legacy.maker_amount,
+                    taker_amount: legacy.taker_amount,
+                    execution_fee: 0,
+                    price,
+                    side,
+                },
+                8 + std::mem::size_of::<StopLossOrder>(),
+            ),
+        }
+    }
+}
@@ -0,0 +1,38 @@
+use {
+    crate::{utils::migrate_account, GlobalSettings, GlobalSettingsV0},
+    anchor_lang::prelude::*,
+};
+
+// Anyone can migrate the global settings created with the original `GlobalSettings` layout,
+// paying the rent for the added fields. Exit order execution fees start uncapped.
+
+#[derive(Accounts)]
+pub struct MigrateGlobalSettings<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: The global settings in the original layout, checked when they are parsed
+    #[account(
+        mut,
+        seeds = [b"global_settings"],
+        bump,
+    )]
+    pub global_settings: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> MigrateGlobalSettings<'info> {
+    pub fn migrate_global_settings(&mut self) -> Result<()> {
+        let legacy =
+            GlobalSettingsV0::try_from_account_data(&self.global_settings.try_borrow_data()?)?;
+
+        migrate_account(
+            &self.global_settings,
+            &self.payer,
+            &self.system_program,
+            &legacy.migrate(),
+            8 + std::mem::size_of::<GlobalSettings>(),
+        )
+    }
+}
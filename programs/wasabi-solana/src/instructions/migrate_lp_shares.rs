@@ -0,0 +1,290 @@
+use {
+    super::{
+        crystallize_fees_if_charged, process_deposit, reserve_before_withdrawal,
+        reserve_repayment_for_queue,
+    },
+    crate::{
+        error::ErrorCode, events::SharesMigrated, lp_vault_signer_seeds, state::GlobalSettings,
+        utils::balance_increase, AllowlistedWallet, Depositor, LpVault,
+    },
+    anchor_lang::prelude::*,
+    anchor_spl::{
+        associated_token::AssociatedToken,
+        token_2022::Token2022,
+        token_interface::{
+            self, Burn, Mint, MintTo, TokenAccount, TokenInterface, TransferChecked,
+        },
+    },
+};
+
+#[derive(Accounts)]
+pub struct MigrateLpShares<'info> {
+    /// The key of the user that owns the shares
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The owner's shares in the decommissioned vault
+    #[account(
+        mut,
+        associated_token::mint = shares_mint,
+        associated_token::authority = owner,
+        associated_token::token_program = shares_token_program,
+    )]
+    pub owner_shares_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Receives the owner's shares in the successor vault
+    #[account(
+        init_if_needed,
+        payer = owner,
+        associated_token::mint = successor_shares_mint,
+        associated_token::authority = owner,
+        associated_token::token_program = shares_token_program,
+    )]
+    pub owner_successor_shares_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        has_one = vault,
+        has_one = shares_mint,
+        has_one = successor @ ErrorCode::InvalidSuccessorVault,
+    )]
+    pub lp_vault: Box<Account<'info, LpVault>>,
+
+    #[account(mut)]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub shares_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// CHECK: The decommissioned vault's withdrawal queue, required once it has one.
+    /// Migrations are then only paid from what it leaves.
+    #[account(
+        mut,
+        seeds = [b"withdrawal_queue", lp_vault.key().as_ref()],
+        bump,
+    )]
+    pub withdrawal_queue: Option<UncheckedAccount<'info>>,
+
+    /// Required once the decommissioned vault's withdrawal queue exists
+    #[account(mut)]
+    pub queue_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    #[account(
+        mut,
+        address = lp_vault.successor @ ErrorCode::InvalidSuccessorVault,
+    )]
+    pub successor: Box<Account<'info, LpVault>>,
+
+    #[account(
+        mut,
+        address = successor.vault,
+    )]
+    pub successor_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        address = successor.shares_mint,
+    )]
+    pub successor_shares_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// CHECK: The successor's withdrawal queue, required once the successor has one. The
+    /// migrated assets are then reserved for it.
+    #[account(
+        mut,
+        seeds = [b"withdrawal_queue", successor.key().as_ref()],
+        bump,
+    )]
+    pub successor_withdrawal_queue: Option<UncheckedAccount<'info>>,
+
+    /// Required once the successor's withdrawal queue exists
+    #[account(mut)]
+    pub successor_queue_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    #[account(address = lp_vault.asset)]
+    pub asset_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Receives the decommissioned vault's accrued fees before its share price is used, required
+    /// while it charges fees
+    #[account(
+        mut,
+        address = lp_vault.fee_shares_account,
+    )]
+    pub fee_shares_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// Receives the successor's accrued fees before its share price is used, required while it
+    /// charges fees
+    #[account(
+        mut,
+        address = successor.fee_shares_account,
+    )]
+    pub successor_fee_shares_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    #[account(
+        seeds = [b"global_settings"],
+        bump,
+    )]
+    pub global_settings: Box<Account<'info, GlobalSettings>>,
+
+    /// Tracks the owner's net deposits and last deposit time in the successor, required while it
+    /// has a per-wallet cap or a cooldown
+    #[account(
+        mut,
+        seeds = [b"depositor", successor.key().as_ref(), owner.key().as_ref()],
+        bump = successor_depositor.bump,
+    )]
+    pub successor_depositor: Option<Box<Account<'info, Depositor>>>,
+
+    /// Approves the owner to deposit into the successor while it is allowlisted
+    #[account(
+        seeds = [b"allowlisted_wallet", successor.key().as_ref(), owner.key().as_ref()],
+        bump = successor_allowlisted_wallet.bump,
+    )]
+    pub successor_allowlisted_wallet: Option<Box<Account<'info, AllowlistedWallet>>>,
+
+    pub asset_token_program: Interface<'info, TokenInterface>,
+    pub shares_token_program: Program<'info, Token2022>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> MigrateLpShares<'info> {
+    pub fn validate(ctx: &Context<MigrateLpShares>, shares_amount: u64) -> Result<()> {
+        require_gt!(shares_amount, 0, ErrorCode::ZeroAmount);
+        require!(
+            ctx.accounts.lp_vault.can_withdraw(),
+            ErrorCode::VaultWithdrawalsPaused
+        );
+
+        // Migrating is a deposit into the successor, subject to the same checks
+        let successor = &ctx.accounts.successor;
+        require!(
+            ctx.accounts.global_settings.can_lp(),
+            ErrorCode::UnpermittedIx
+        );
+        require!(!successor.decommissioned, ErrorCode::VaultDecommissioned);
+        require!(successor.can_deposit(), ErrorCode::VaultDepositsPaused);
+        require!(
+            !successor.allowlist_enabled || ctx.accounts.successor_allowlisted_wallet.is_some(),
+            ErrorCode::WalletNotAllowlisted
+        );
+        Ok(())
+    }
+
+    fn burn_shares_from_owner(&self, amount: u64) -> Result<()> {
+        let cpi_accounts = Burn {
+            mint: self.shares_mint.to_account_info(),
+            from: self.owner_shares_account.to_account_info(),
+            authority: self.owner.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(self.shares_token_program.to_account_info(), cpi_accounts);
+        token_interface::burn(cpi_ctx, amount)
+    }
+
+    fn transfer_token_to_successor_vault(&self, amount: u64) -> Result<()> {
+        let cpi_accounts = TransferChecked {
+            from: self.vault.to_account_info(),
+            mint: self.asset_mint.to_account_info(),
+            to: self.successor_vault.to_account_info(),
+            authority: self.lp_vault.to_account_info(),
+        };
+        let cpi_ctx = CpiContext {
+            program: self.asset_token_program.to_account_info(),
+            accounts: cpi_accounts,
+            remaining_accounts: Vec::new(),
+            signer_seeds: &[lp_vault_signer_seeds!(self.lp_vault)],
+        };
+        token_interface::transfer_checked(cpi_ctx, amount, self.asset_mint.decimals)
+    }
+
+    fn mint_successor_shares_to_owner(&self, amount: u64) -> Result<()> {
+        let cpi_accounts = MintTo {
+            mint: self.successor_shares_mint.to_account_info(),
+            to: self.owner_successor_shares_account.to_account_info(),
+            authority: self.successor.to_account_info(),
+        };
+        let cpi_ctx = CpiContext {
+            program: self.shares_token_program.to_account_info(),
+            accounts: cpi_accounts,
+            remaining_accounts: Vec::new(),
+            signer_seeds: &[lp_vault_signer_seeds!(self.successor)],
+        };
+        token_interface::mint_to(cpi_ctx, amount)
+    }
+
+    /// Redeems `shares_amount` from the decommissioned vault straight into its successor, so the
+    /// owner's balance carries over at the same value without paying an exit fee. The assets are
+    /// deposited into the successor under its caps and cooldown. Reverts if fewer than
+    /// `min_shares` successor shares would be minted.
+    pub fn migrate_lp_shares(&mut self, shares_amount: u64, min_shares: u64) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        self.lp_vault.vest(now)?;
+        crystallize_fees_if_charged(
+            &mut self.lp_vault,
+            &mut self.shares_mint,
+            self.fee_shares_account.as_deref(),
+            &self.shares_token_program,
+        )?;
+        self.successor.vest(now)?;
+        crystallize_fees_if_charged(
+            &mut self.successor,
+            &mut self.successor_shares_mint,
+            self.successor_fee_shares_account.as_deref(),
+            &self.shares_token_program,
+        )?;
+
+        let assets = self
+            .lp_vault
+            .preview_redeem(shares_amount, self.shares_mint.supply)?;
+        self.lp_vault.total_assets = self
+            .lp_vault
+            .total_assets
+            .checked_sub(assets)
+            .ok_or(ErrorCode::ArithmeticUnderflow)?;
+        self.burn_shares_from_owner(shares_amount)?;
+
+        reserve_before_withdrawal(
+            self.withdrawal_queue.as_deref(),
+            self.queue_vault.as_deref_mut(),
+            &mut self.lp_vault,
+            &mut self.vault,
+            &self.asset_mint,
+            &self.asset_token_program,
+            assets,
+        )?;
+        let balance_before = self.successor_vault.amount;
+        self.transfer_token_to_successor_vault(assets)?;
+        let received = balance_increase(&mut self.successor_vault, balance_before)?;
+
+        let successor_shares = process_deposit(
+            &mut self.successor,
+            self.successor_shares_mint.supply,
+            self.successor_depositor
+                .as_deref_mut()
+                .map(|depositor| &mut **depositor),
+            received,
+            min_shares,
+            true,
+        )?;
+        self.mint_successor_shares_to_owner(successor_shares)?;
+        reserve_repayment_for_queue(
+            self.successor_withdrawal_queue.as_deref(),
+            self.successor_queue_vault.as_deref_mut(),
+            &mut self.successor,
+            &self.successor_vault,
+            &self.asset_mint,
+            &self.asset_token_program,
+            received,
+        )?;
+
+        emit!(SharesMigrated {
+            vault: self.lp_vault.shares_mint,
+            successor: self.successor.shares_mint,
+            owner: self.owner.key(),
+            assets: received,
+            shares: shares_amount,
+            successor_shares,
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,38 @@
+use {
+    crate::{utils::migrate_account, LpVault, LpVaultV0},
+    anchor_lang::prelude::*,
+};
+
+// Anyone can migrate a vault created with the original `LpVault` layout, paying the rent for the
+// added fields. The added fields are set like a new vault's, so nothing is capped, charged or
+// paused until the vault's admins configure it.
+
+#[derive(Accounts)]
+pub struct MigrateLpVault<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: A vault in the original layout, checked when it is parsed
+    #[account(
+        mut,
+        owner = crate::ID,
+    )]
+    pub lp_vault: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> MigrateLpVault<'info> {
+    pub fn migrate_lp_vault(&mut self) -> Result<()> {
+        let legacy = LpVaultV0::try_from_account_data(&self.lp_vault.try_borrow_data()?)?;
+        let lp_vault = legacy.migrate(Clock::get()?.unix_timestamp);
+
+        migrate_account(
+            &self.lp_vault,
+            &self.payer,
+            &self.system_program,
+            &lp_vault,
+            8 + std::mem::size_of::<LpVault>(),
+        )
+    }
+}
@@ -1,6 +1,13 @@
+pub mod allow_mint;
+pub mod allowlist_wallet;
+pub mod batch_update_exit_orders;
+pub mod cancel_limit_open_order;
 pub mod claim_position;
+pub mod claim_rewards;
+pub mod claim_withdrawal;
 pub mod close_long_position_cleanup;
 pub mod close_long_position_setup;
+pub mod close_lp_vault;
 pub mod close_position_cleanup;
 pub mod close_position_setup;
 pub mod close_short_position_cleanup;
@@ -8,33 +15,68 @@ pub mod close_short_position_setup;
 pub mod close_stop_loss_order;
 pub mod close_strategy;
 pub mod close_take_profit_order;
+pub mod crystallize_vault_fees;
+pub mod decommission_lp_vault;
 pub mod deposit;
+pub mod deposit_for;
+pub mod deposit_native;
 pub mod donate;
+pub mod execute_limit_open_order_cleanup;
+pub mod execute_limit_open_order_setup;
+pub mod fill_withdrawal_queue;
+pub mod init_allowlisted_lp_vault;
+pub mod init_allowlisted_successor_lp_vault;
 pub mod init_debt_controller;
+pub mod init_depositor;
 pub mod init_global_settings;
+pub mod init_limit_open_order;
 pub mod init_long_pool;
 pub mod init_lp_vault;
+pub mod init_lp_vault_with_token_metadata;
 pub mod init_or_update_permission;
 pub mod init_or_update_stop_loss_order;
 pub mod init_or_update_take_profit_order;
+pub mod init_reward_pool;
+pub mod init_share_price_history;
 pub mod init_short_pool;
 pub mod init_strategy;
+pub mod init_successor_lp_vault;
+pub mod init_withdrawal_queue;
 pub mod liquidate_position_cleanup;
 pub mod liquidate_position_setup;
+pub mod migrate_base_pool;
+pub mod migrate_exit_order;
+pub mod migrate_global_settings;
+pub mod migrate_lp_shares;
+pub mod migrate_lp_vault;
 pub mod open_long_position_cleanup;
 pub mod open_long_position_setup;
 pub mod open_short_position_cleanup;
 pub mod open_short_position_setup;
 pub mod redeem;
+pub mod remove_allowed_mint;
+pub mod remove_allowlisted_wallet;
 pub mod remove_permission;
+pub mod request_withdrawal;
 pub mod set_fee_wallet;
 pub mod set_liquidation_fee;
 pub mod set_liquidation_wallet;
 pub mod set_lp_state;
+pub mod set_lp_vault_status;
 pub mod set_max_apy;
+pub mod set_max_exit_order_execution_fee;
 pub mod set_max_leverage;
+pub mod set_mint_policy;
+pub mod set_pool_status;
 pub mod set_super_admin;
 pub mod set_trading_state;
+pub mod set_vault_allowlist;
+pub mod set_vault_cooldown;
+pub mod set_vault_deposit_caps;
+pub mod set_vault_fees;
+pub mod set_vault_max_utilization;
+pub mod set_vault_vesting_period;
+pub mod stake_shares;
 pub mod stop_loss_cleanup;
 pub mod stop_loss_setup;
 pub mod strategy_claim_yield;
@@ -44,12 +86,26 @@ pub mod strategy_withdraw_cleanup;
 pub mod strategy_withdraw_setup;
 pub mod take_profit_cleanup;
 pub mod take_profit_setup;
+pub mod top_up_reward_pool;
+pub mod transfer_hook;
+pub mod unstake_shares;
+#[cfg(feature = "metaplex")]
+pub mod update_shares_metadata;
 pub mod update_vault_max_borrow;
+pub mod vault_views;
 pub mod withdraw;
+pub mod withdraw_to;
 
+pub use allow_mint::*;
+pub use allowlist_wallet::*;
+pub use batch_update_exit_orders::*;
+pub use cancel_limit_open_order::*;
 pub use claim_position::*;
+pub use claim_rewards::*;
+pub use claim_withdrawal::*;
 pub use close_long_position_cleanup::*;
 pub use close_long_position_setup::*;
+pub use close_lp_vault::*;
 pub use close_position_cleanup::*;
 pub use close_position_setup::*;
 pub use close_short_position_cleanup::*;
@@ -57,33 +113,68 @@ pub use close_short_position_setup::*;
 pub use close_stop_loss_order::*;
 pub use close_strategy::*;
 pub use close_take_profit_order::*;
+pub use crystallize_vault_fees::*;
+pub use decommission_lp_vault::*;
 pub use deposit::*;
+pub use deposit_for::*;
+pub use deposit_native::*;
 pub use donate::*;
+pub use execute_limit_open_order_cleanup::*;
+pub use execute_limit_open_order_setup::*;
+pub use fill_withdrawal_queue::*;
+pub use init_allowlisted_lp_vault::*;
+pub use init_allowlisted_successor_lp_vault::*;
 pub use init_debt_controller::*;
+pub use init_depositor::*;
 pub use init_global_settings::*;
+pub use init_limit_open_order::*;
 pub use init_long_pool::*;
 pub use init_lp_vault::*;
+pub use init_lp_vault_with_token_metadata::*;
 pub use init_or_update_permission::*;
 pub use init_or_update_stop_loss_order::*;
 pub use init_or_update_take_profit_order::*;
+pub use init_reward_pool::*;
+pub use init_share_price_history::*;
 pub use init_short_pool::*;
 pub use init_strategy::*;
+pub use init_successor_lp_vault::*;
+pub use init_withdrawal_queue::*;
 pub use liquidate_position_cleanup::*;
 pub use liquidate_position_setup::*;
+pub use migrate_base_pool::*;
+pub use migrate_exit_order::*;
+pub use migrate_global_settings::*;
+pub use migrate_lp_shares::*;
+pub use migrate_lp_vault::*;
 pub use open_long_position_cleanup::*;
 pub use open_long_position_setup::*;
 pub use open_short_position_cleanup::*;
 pub use open_short_position_setup::*;
 pub use redeem::*;
+pub use remove_allowed_mint::*;
+pub use remove_allowlisted_wallet::*;
 pub use remove_permission::*;
+pub use request_withdrawal::*;
 pub use set_fee_wallet::*;
 pub use set_liquidation_fee::*;
 pub use set_liquidation_wallet::*;
 pub use set_lp_state::*;
+pub use set_lp_vault_status::*;
 pub use set_max_apy::*;
+pub use set_max_exit_order_execution_fee::*;
 pub use set_max_leverage::*;
+pub use set_mint_policy::*;
+pub use set_pool_status::*;
 pub use set_super_admin::*;
 pub use set_trading_state::*;
+pub use set_vault_allowlist::*;
+pub use set_vault_cooldown::*;
+pub use set_vault_deposit_caps::*;
+pub use set_vault_fees::*;
+pub use set_vault_max_utilization::*;
+pub use set_vault_vesting_period::*;
+pub use stake_shares::*;
 pub use stop_loss_cleanup::*;
 pub use stop_loss_setup::*;
 pub use strategy_claim_yield::*;
@@ -93,5 +184,12 @@ pub use strategy_withdraw_cleanup::*;
 pub use strategy_withdraw_setup::*;
 pub use take_profit_cleanup::*;
 pub use take_profit_setup::*;
+pub use top_up_reward_pool::*;
+pub use transfer_hook::*;
+pub use unstake_shares::*;
+#[cfg(feature = "metaplex")]
+pub use update_shares_metadata::*;
 pub use update_vault_max_borrow::*;
+pub use vault_views::*;
 pub use withdraw::*;
+pub use withdraw_to::*;
@@ -1,13 +1,16 @@
 use {
     super::OpenLongPositionCleanup,
     crate::{
-        error::ErrorCode, long_pool_signer_seeds, lp_vault_signer_seeds,
-        utils::setup_transaction_introspection_validation, BasePool, DebtController,
-        GlobalSettings, LpVault, OpenPositionRequest, Permission, Position, SwapCache,
+        error::ErrorCode,
+        long_pool_signer_seeds, lp_vault_signer_seeds,
+        utils::{balance_increase, setup_transaction_introspection_validation, wrap_sol},
+        BasePool, DebtController, GlobalSettings, LpVault, OpenPositionRequest, Permission,
+        Position, SwapCache,
     },
     anchor_lang::{prelude::*, solana_program::sysvar},
-    anchor_spl::token_interface::{
-        self, Approve, Mint, TokenAccount, TokenInterface, TransferChecked,
+    anchor_spl::{
+        token::spl_token::native_mint,
+        token_interface::{self, Approve, Mint, TokenAccount, TokenInterface, TransferChecked},
     },
 };
 
@@ -130,11 +133,16 @@ impl<'info> OpenLongPositionSetup<'info> {
             ctx.accounts.global_settings.can_trade(),
             ErrorCode::UnpermittedIx
         );
+        require!(
+            ctx.accounts.lp_vault.can_borrow(),
+            ErrorCode::VaultBorrowsPaused
+        );
+        ctx.accounts.pool.validate_can_open()?;
 
         // Validate TX only has only one setup IX and has one following cleanup IX
         setup_transaction_introspection_validation(
             &ctx.accounts.sysvar_info,
-            OpenLongPositionCleanup::get_hash(),
+            &[OpenLongPositionCleanup::get_hash()],
             true,
         )?;
 
@@ -204,16 +212,23 @@ impl<'info> OpenLongPositionSetup<'info> {
         fee: u64,
         #[allow(unused_variables)] expiration: i64,
     ) -> Result<()> {
+        self.lp_vault
+            .validate_utilization(self.vault.amount, principal)?;
+        let balance_before = self.currency_vault.amount;
         self.transfer_borrow_amount_from_vault(principal)?;
+        let principal_received = balance_increase(&mut self.currency_vault, balance_before)?;
+
+        // Only the down payment received, net of any transfer fee, counts towards the position
+        let balance_before = self.currency_vault.amount;
         self.transfer_down_payment_from_user(down_payment)?;
+        let down_payment = balance_increase(&mut self.currency_vault, balance_before)?;
         self.transfer_from_user_to_fee_wallet(fee)?;
-        self.currency_vault.reload()?;
 
         let max_principal = self.debt_controller.compute_max_principal(down_payment)?;
 
         require_gte!(max_principal, principal, ErrorCode::PrincipalTooHigh);
 
-        let total_swap_amount = principal
+        let total_swap_amount = principal_received
             .checked_add(down_payment)
             .ok_or(ErrorCode::ArithmeticOverflow)?;
 
@@ -224,9 +239,7 @@ impl<'info> OpenLongPositionSetup<'info> {
         // order to track the entire amount being swapped.
         self.open_position_request.set_inner(OpenPositionRequest {
             min_target_amount,
-            max_amount_in: down_payment
-                .checked_add(principal)
-                .ok_or(ErrorCode::ArithmeticOverflow)?,
+            max_amount_in: total_swap_amount,
             pool_key: self.pool.key(),
             position: self.position.key(),
             swap_cache: SwapCache {
@@ -249,4 +262,42 @@ impl<'info> OpenLongPositionSetup<'info> {
 
         Ok(())
     }
+
+    /// Same as `open_long_position_setup`, but the down payment and fee are wrapped from the
+    /// owner's SOL. Exactly what the setup spends is wrapped, so any wSOL the owner already held in
+    /// the account is left untouched.
+    pub fn open_long_position_setup_native(
+        &mut self,
+        nonce: u16,
+        min_target_amount: u64,
+        down_payment: u64,
+        principal: u64,
+        fee: u64,
+        expiration: i64,
+    ) -> Result<()> {
+        require_keys_eq!(
+            self.currency.key(),
+            native_mint::ID,
+            ErrorCode::MintMismatch
+        );
+
+        wrap_sol(
+            self.owner.to_account_info(),
+            self.owner_currency_account.to_account_info(),
+            self.token_program.to_account_info(),
+            self.system_program.to_account_info(),
+            down_payment
+                .checked_add(fee)
+                .ok_or(ErrorCode::ArithmeticOverflow)?,
+        )?;
+
+        self.open_long_position_setup(
+            nonce,
+            min_target_amount,
+            down_payment,
+            principal,
+            fee,
+            expiration,
+        )
+    }
 }
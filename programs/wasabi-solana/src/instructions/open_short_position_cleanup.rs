@@ -1,8 +1,11 @@
 use {
     crate::{
-        debt_controller::LEVERAGE_DENOMINATOR, error::ErrorCode, events::PositionOpened,
-        short_pool_signer_seeds, utils::get_function_hash, BasePool, DebtController, LpVault,
-        OpenPositionRequest, Position,
+        debt_controller::LEVERAGE_DENOMINATOR,
+        error::ErrorCode,
+        events::PositionOpened,
+        short_pool_signer_seeds,
+        utils::{balance_increase, get_function_hash},
+        BasePool, DebtController, LpVault, OpenPositionRequest, Position,
     },
     anchor_lang::prelude::*,
     anchor_spl::token_interface::{
@@ -163,23 +166,31 @@ impl<'info> OpenShortPositionCleanup<'info> {
             ErrorCode::PrincipalTooHigh
         );
 
+        // `max_amount_in` holds the principal that arrived in the `currency_vault`
+        let principal_received = self.open_position_request.max_amount_in;
         require_gte!(
-            self.position.principal,
+            principal_received,
             principal_used,
             ErrorCode::ValueDeviatedTooMuch
         );
 
-        let remaining_principal = self
-            .position
-            .principal
+        let remaining_principal = principal_received
             .checked_sub(principal_used)
             .ok_or(ErrorCode::ArithmeticUnderflow)?;
 
+        // The position owes whatever the LP Vault didn't get back, including transfer fees
+        let mut principal_returned = 0;
         if remaining_principal > 0 {
+            let balance_before = self.vault.amount;
             self.transfer_remaining_principal_from_currency_vault(remaining_principal)?;
+            principal_returned = balance_increase(&mut self.vault, balance_before)?;
         }
 
-        self.position.principal = principal_used;
+        self.position.principal = self
+            .position
+            .principal
+            .checked_sub(principal_returned)
+            .ok_or(ErrorCode::ArithmeticUnderflow)?;
         self.position.collateral_amount = collateral_received
             .checked_add(self.position.down_payment)
             .ok_or(ErrorCode::ArithmeticOverflow)?;
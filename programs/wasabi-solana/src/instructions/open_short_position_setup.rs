@@ -1,9 +1,10 @@
 use {
     super::OpenShortPositionCleanup,
     crate::{
-        error::ErrorCode, lp_vault_signer_seeds, short_pool_signer_seeds,
-        utils::setup_transaction_introspection_validation, BasePool, GlobalSettings, LpVault,
-        OpenPositionRequest, Permission, Position, SwapCache,
+        error::ErrorCode,
+        lp_vault_signer_seeds, short_pool_signer_seeds,
+        utils::{balance_increase, setup_transaction_introspection_validation},
+        BasePool, GlobalSettings, LpVault, OpenPositionRequest, Permission, Position, SwapCache,
     },
     anchor_lang::{prelude::*, solana_program::sysvar},
     anchor_spl::token_interface::{
@@ -120,11 +121,16 @@ impl<'info> OpenShortPositionSetup<'info> {
             ctx.accounts.global_settings.can_trade(),
             ErrorCode::UnpermittedIx
         );
+        require!(
+            ctx.accounts.lp_vault.can_borrow(),
+            ErrorCode::VaultBorrowsPaused
+        );
+        ctx.accounts.pool.validate_can_open()?;
 
         // Validate TX only has only one setup IX and has one following cleanup IX
         setup_transaction_introspection_validation(
             &ctx.accounts.sysvar_info,
-            OpenShortPositionCleanup::get_hash(),
+            &[OpenShortPositionCleanup::get_hash()],
             true,
         )?;
 
@@ -200,28 +206,29 @@ impl<'info> OpenShortPositionSetup<'info> {
         #[allow(unused_variables)] expiration: i64,
     ) -> Result<()> {
         // Down payment is transferred from the user to the `collateral_vault` since it's not used
-        // for swapping when opening a short position.
+        // for swapping when opening a short position. The `collateral_vault` is reloaded so we can
+        // get the balance after the down payment has been made.
+        let balance_before = self.collateral_vault.amount;
         self.transfer_from_user_to_collateral_vault(down_payment)?;
+        let down_payment = balance_increase(&mut self.collateral_vault, balance_before)?;
 
         // Transfer fees
         self.transfer_from_user_to_fee_wallet(fee)?;
 
-        // Reload the `collateral_vault` so we can get the balance after the down payment has been
-        // made.
-        self.collateral_vault.reload()?;
-
         require_gte!(
             self.vault.amount,
             principal,
             ErrorCode::InsufficientAvailablePrincipal
         );
+        self.lp_vault
+            .validate_utilization(self.vault.amount, principal)?;
 
-        // Transfer the borrowed amount to the `currency_vault` to be used in a swap.
-        self.transfer_from_lp_vault_to_currency_vault(principal)?;
-
-        // Reload the `currency_vault` so we can get the balance after the principal has be
+        // Transfer the borrowed amount to the `currency_vault` to be used in a swap. The
+        // `currency_vault` is reloaded so we can get the balance after the principal has be
         // transferred.
-        self.currency_vault.reload()?;
+        let balance_before = self.currency_vault.amount;
+        self.transfer_from_lp_vault_to_currency_vault(principal)?;
+        let principal_received = balance_increase(&mut self.currency_vault, balance_before)?;
 
         // Approve the user to make a swap on behalf of the `currency_vault`
         self.approve_owner_delegation(principal)?;
@@ -230,8 +237,8 @@ impl<'info> OpenShortPositionSetup<'info> {
             position: self.position.key(),
             pool_key: self.pool.key(),
             min_target_amount,
-            max_amount_in: 0, // CHECK: Why isn't this being set - Close Position Request - set to
-            // collateral_amount - set to the `args.principal`
+            // The principal that arrived in the `currency_vault`, net of any transfer fee
+            max_amount_in: principal_received,
             swap_cache: SwapCache {
                 taker_bal_before: self.collateral_vault.amount,
                 maker_bal_before: self.currency_vault.amount,
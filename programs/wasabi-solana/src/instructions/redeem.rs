@@ -11,38 +11,47 @@ pub struct RedeemArgs {
 
 pub trait RedeemTrait {
     fn redeem(&mut self, shares_amount: u64) -> Result<()>;
+    fn redeem_with_min_assets(&mut self, shares_amount: u64, min_assets: u64) -> Result<()>;
 }
 
 impl RedeemTrait for DepositOrWithdraw<'_> {
     fn redeem(&mut self, shares_amount: u64) -> Result<()> {
-        require_gt!(shares_amount, 0, ErrorCode::ZeroAmount);
-        let shares_amount_u128 = shares_amount as u128;
-        let total_assets_u128 = self.lp_vault.total_assets as u128;
-        let shares_supply_u128 = self.shares_mint.supply as u128;
-
-        let token_transfer_amount = shares_amount_u128
-            .checked_mul(total_assets_u128)
-            .ok_or(ErrorCode::ArithmeticOverflow)?
-            .checked_div(shares_supply_u128)
-            .ok_or(ErrorCode::ArithmeticOverflow)?
-            .try_into()
-            .map_err(|_| ErrorCode::U64Overflow)?;
-
-        self.transfer_token_from_vault_to_owner(token_transfer_amount)?;
-        self.burn_shares_from_user(shares_amount)?;
+        self.redeem_with_min_assets(shares_amount, 0)
+    }
 
-        self.lp_vault.total_assets = self
+    /// Redeems `shares_amount`, reverting if fewer than `min_assets` would be received
+    fn redeem_with_min_assets(&mut self, shares_amount: u64, min_assets: u64) -> Result<()> {
+        require_gt!(shares_amount, 0, ErrorCode::ZeroAmount);
+        require!(
+            self.lp_vault.can_withdraw(),
+            ErrorCode::VaultWithdrawalsPaused
+        );
+        self.lp_vault.vest(Clock::get()?.unix_timestamp)?;
+        self.crystallize_fees()?;
+
+        let token_transfer_amount = self
             .lp_vault
-            .total_assets
-            .checked_sub(token_transfer_amount)
-            .ok_or(ErrorCode::ArithmeticUnderflow)?;
+            .preview_redeem(shares_amount, self.shares_mint.supply)?;
+
+        let exit_fee = self.process_withdrawal(token_transfer_amount)?;
+
+        require_gte!(
+            token_transfer_amount - exit_fee,
+            min_assets,
+            ErrorCode::SlippageExceeded
+        );
+
+        self.reserve_before_withdrawal(token_transfer_amount - exit_fee)?;
+        self.transfer_token_from_vault_to_owner(token_transfer_amount - exit_fee)?;
+        self.burn_shares_from_user(shares_amount)?;
+        self.checkpoint_share_price()?;
 
         emit!(Withdraw {
             vault: self.shares_mint.key(),
             sender: self.owner.key(),
             owner: self.owner.key(),
             receiver: self.owner_asset_account.key(),
-            assets: token_transfer_amount,
+            assets: token_transfer_amount - exit_fee,
             shares: shares_amount,
         });
 
@@ -0,0 +1,41 @@
+use {
+    crate::{
+        error::ErrorCode,
+        events::MintAllowlistUpdated,
+        state::{AllowedMint, Permission},
+    },
+    anchor_lang::prelude::*,
+};
+
+#[derive(Accounts)]
+pub struct RemoveAllowedMint<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"super_admin"],
+        bump,
+        has_one = authority,
+        constraint = super_admin.is_super_authority @ ErrorCode::InvalidPermissions
+    )]
+    pub super_admin: Account<'info, Permission>,
+
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"allowed_mint", allowed_mint.mint.as_ref()],
+        bump = allowed_mint.bump,
+    )]
+    pub allowed_mint: Account<'info, AllowedMint>,
+}
+
+impl<'info> RemoveAllowedMint<'info> {
+    pub fn remove_allowed_mint(&mut self) -> Result<()> {
+        emit!(MintAllowlistUpdated {
+            mint: self.allowed_mint.mint,
+            allowed: false,
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,52 @@
+use {
+    crate::{
+        error::ErrorCode,
+        events::WalletAllowlistUpdated,
+        state::{AllowlistedWallet, LpVault, Permission},
+    },
+    anchor_lang::prelude::*,
+};
+
+#[derive(Accounts)]
+pub struct RemoveAllowlistedWallet<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(has_one = authority)]
+    pub permission: Account<'info, Permission>,
+
+    pub lp_vault: Account<'info, LpVault>,
+
+    #[account(
+        mut,
+        close = authority,
+        has_one = lp_vault,
+        seeds = [
+            b"allowlisted_wallet",
+            lp_vault.key().as_ref(),
+            allowlisted_wallet.wallet.as_ref(),
+        ],
+        bump = allowlisted_wallet.bump,
+    )]
+    pub allowlisted_wallet: Account<'info, AllowlistedWallet>,
+}
+
+impl<'info> RemoveAllowlistedWallet<'info> {
+    pub fn validate(ctx: &Context<RemoveAllowlistedWallet>) -> Result<()> {
+        require!(
+            ctx.accounts.permission.can_manage_vaults(),
+            ErrorCode::InvalidPermissions
+        );
+        Ok(())
+    }
+
+    pub fn remove_allowlisted_wallet(&mut self) -> Result<()> {
+        emit!(WalletAllowlistUpdated {
+            vault: self.lp_vault.shares_mint,
+            wallet: self.allowlisted_wallet.wallet,
+            allowed: false,
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,219 @@
+use {
+    super::{crystallize_fees_if_charged, reserve_for_queued_withdrawals},
+    crate::{
+        error::ErrorCode, events::WithdrawalRequested, lp_vault_signer_seeds,
+        state::GlobalSettings, Depositor, LpVault, WithdrawalQueue, WithdrawalRequest,
+    },
+    anchor_lang::prelude::*,
+    anchor_spl::{
+        token_2022::Token2022,
+        token_interface::{self, Burn, Mint, MintTo, TokenAccount, TokenInterface},
+    },
+};
+
+// Takes a place in the withdrawal queue when the vault lacks unborrowed tokens. The shares are
+// moved into the queue's escrow, so they keep sharing the vault's gains and losses until the
+// request is filled at the share price of the time. Escrowing burns the owner's shares and mints
+// them to the escrow, as a transfer of hook-enabled shares can't be made from within this
+// program.
+
+#[derive(Accounts)]
+pub struct RequestWithdrawal<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        associated_token::mint = shares_mint,
+        associated_token::authority = owner,
+        associated_token::token_program = shares_token_program,
+    )]
+    /// The Owner's token account that stores share tokens
+    pub owner_shares_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        has_one = vault,
+        has_one = shares_mint,
+    )]
+    pub lp_vault: Box<Account<'info, LpVault>>,
+
+    #[account(mut)]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(address = lp_vault.asset)]
+    pub asset_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut)]
+    pub shares_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        has_one = lp_vault,
+        has_one = queue_vault,
+        has_one = shares_escrow,
+        seeds = [b"withdrawal_queue", lp_vault.key().as_ref()],
+        bump = withdrawal_queue.bump,
+    )]
+    pub withdrawal_queue: Box<Account<'info, WithdrawalQueue>>,
+
+    #[account(mut)]
+    pub queue_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub shares_escrow: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init,
+        payer = owner,
+        seeds = [
+            b"withdrawal_request",
+            lp_vault.key().as_ref(),
+            &withdrawal_queue.next_request_id.to_le_bytes(),
+        ],
+        bump,
+        space = 8 + std::mem::size_of::<WithdrawalRequest>(),
+    )]
+    pub withdrawal_request: Box<Account<'info, WithdrawalRequest>>,
+
+    #[account(
+        seeds = [b"global_settings"],
+        bump,
+    )]
+    pub global_settings: Account<'info, GlobalSettings>,
+
+    /// Tracks the owner's net deposits and last deposit time, required while the vault has a
+    /// cooldown
+    #[account(
+        mut,
+        seeds = [b"depositor", lp_vault.key().as_ref(), owner.key().as_ref()],
+        bump = depositor.bump,
+    )]
+    pub depositor: Option<Box<Account<'info, Depositor>>>,
+
+    /// Receives the vault's accrued fees before the share price is used, required while the vault
+    /// charges fees
+    #[account(
+        mut,
+        address = lp_vault.fee_shares_account,
+    )]
+    pub fee_shares_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    pub asset_token_program: Interface<'info, TokenInterface>,
+    pub shares_token_program: Program<'info, Token2022>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> RequestWithdrawal<'info> {
+    pub fn validate(ctx: &Context<RequestWithdrawal>, shares_amount: u64) -> Result<()> {
+        require_gt!(shares_amount, 0, ErrorCode::ZeroAmount);
+
+        require!(
+            ctx.accounts.global_settings.can_lp(),
+            ErrorCode::UnpermittedIx
+        );
+        require!(
+            ctx.accounts.lp_vault.can_withdraw(),
+            ErrorCode::VaultWithdrawalsPaused
+        );
+
+        Ok(())
+    }
+
+    fn burn_shares_from_owner(&self, amount: u64) -> Result<()> {
+        let cpi_accounts = Burn {
+            mint: self.shares_mint.to_account_info(),
+            from: self.owner_shares_account.to_account_info(),
+            authority: self.owner.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(self.shares_token_program.to_account_info(), cpi_accounts);
+        token_interface::burn(cpi_ctx, amount)
+    }
+
+    fn mint_shares_to_escrow(&self, amount: u64) -> Result<()> {
+        let cpi_accounts = MintTo {
+            mint: self.shares_mint.to_account_info(),
+            to: self.shares_escrow.to_account_info(),
+            authority: self.lp_vault.to_account_info(),
+        };
+        let cpi_ctx = CpiContext {
+            program: self.shares_token_program.to_account_info(),
+            accounts: cpi_accounts,
+            remaining_accounts: Vec::new(),
+            signer_seeds: &[lp_vault_signer_seeds!(self.lp_vault)],
+        };
+        token_interface::mint_to(cpi_ctx, amount)
+    }
+
+    /// The exit fee rate the request is filled with, fixed now so waiting in the queue doesn't
+    /// run down the cooldown
+    fn exit_fee_bps(&self, now: i64) -> Result<u16> {
+        if self.lp_vault.lp_cooldown == 0 {
+            return Ok(0);
+        }
+        let depositor = self.depositor.as_ref().ok_or(ErrorCode::MissingDepositor)?;
+        self.lp_vault
+            .exit_fee_rate(depositor.last_deposit_timestamp, now)
+    }
+
+    pub fn request_withdrawal(
+        &mut self,
+        shares_amount: u64,
+        bumps: &RequestWithdrawalBumps,
+    ) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        self.lp_vault.vest(now)?;
+        crystallize_fees_if_charged(
+            &mut self.lp_vault,
+            &mut self.shares_mint,
+            self.fee_shares_account.as_deref(),
+            &self.shares_token_program,
+        )?;
+
+        let requested_assets = self
+            .lp_vault
+            .preview_redeem(shares_amount, self.shares_mint.supply)?;
+        require_gt!(requested_assets, 0, ErrorCode::ZeroAmount);
+        let exit_fee_bps = self.exit_fee_bps(now)?;
+
+        self.burn_shares_from_owner(shares_amount)?;
+        self.mint_shares_to_escrow(shares_amount)?;
+
+        if let Some(depositor) = self.depositor.as_mut() {
+            depositor.record_withdrawal(requested_assets);
+        }
+
+        let id = self.withdrawal_queue.next_request_id;
+        self.withdrawal_request.set_inner(WithdrawalRequest {
+            owner: self.owner.key(),
+            lp_vault: self.lp_vault.key(),
+            id,
+            shares: shares_amount,
+            requested_assets,
+            exit_fee_bps,
+            assets: 0,
+            bump: bumps.withdrawal_request,
+        });
+        self.withdrawal_queue.enqueue(&self.withdrawal_request)?;
+
+        emit!(WithdrawalRequested {
+            vault: self.lp_vault.shares_mint,
+            owner: self.owner.key(),
+            request: self.withdrawal_request.key(),
+            request_id: id,
+            assets: requested_assets,
+            shares: shares_amount,
+        });
+
+        // Reserve whatever is not currently borrowed for the queue
+        reserve_for_queued_withdrawals(
+            &mut self.withdrawal_queue,
+            &mut self.lp_vault,
+            &self.vault,
+            &mut self.queue_vault,
+            &self.asset_mint,
+            &self.asset_token_program,
+            self.vault.amount,
+        )
+    }
+}
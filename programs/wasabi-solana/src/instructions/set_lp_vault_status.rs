@@ -0,0 +1,39 @@
+use {
+    crate::{
+        error::ErrorCode,
+        events::LpVaultStatusUpdated,
+        state::{LpVault, Permission, VAULT_STATUSES},
+    },
+    anchor_lang::prelude::*,
+};
+
+#[derive(Accounts)]
+pub struct SetLpVaultStatus<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(mut)]
+    pub lp_vault: Account<'info, LpVault>,
+
+    #[account(
+        seeds = [b"super_admin"],
+        bump,
+        has_one = authority,
+        constraint = super_admin.is_super_authority @ ErrorCode::InvalidPermissions
+    )]
+    pub super_admin: Account<'info, Permission>,
+}
+
+impl<'info> SetLpVaultStatus<'info> {
+    pub fn set_lp_vault_status(&mut self, statuses: u16) -> Result<()> {
+        require!(statuses & !VAULT_STATUSES == 0, ErrorCode::InvalidStatus);
+
+        self.lp_vault.statuses = statuses;
+
+        emit!(LpVaultStatusUpdated {
+            vault: self.lp_vault.shares_mint,
+            statuses,
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,32 @@
+use {
+    crate::{state::GlobalSettings, Permission},
+    anchor_lang::prelude::*,
+};
+
+#[derive(Accounts)]
+pub struct SetMaxExitOrderExecutionFee<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        has_one = authority,
+        seeds = [b"super_admin"],
+        bump,
+    )]
+    pub super_admin_permission: Account<'info, Permission>,
+
+    #[account(
+        mut,
+        seeds = [b"global_settings"],
+        bump,
+    )]
+    pub global_settings: Account<'info, GlobalSettings>,
+}
+
+impl<'info> SetMaxExitOrderExecutionFee<'info> {
+    pub fn set_max_exit_order_execution_fee(&mut self, max_execution_fee: u64) -> Result<()> {
+        self.global_settings.max_exit_order_execution_fee = max_execution_fee;
+
+        Ok(())
+    }
+}
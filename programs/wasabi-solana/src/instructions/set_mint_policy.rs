@@ -0,0 +1,55 @@
+use {
+    crate::{
+        error::ErrorCode,
+        events::MintPolicyUpdated,
+        state::{MintPolicy, Permission, MINT_FEATURES},
+    },
+    anchor_lang::prelude::*,
+};
+
+#[derive(Accounts)]
+pub struct SetMintPolicy<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"super_admin"],
+        bump,
+        has_one = authority,
+        constraint = super_admin.is_super_authority @ ErrorCode::InvalidPermissions
+    )]
+    pub super_admin: Account<'info, Permission>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        seeds = [b"mint_policy"],
+        bump,
+        space = 8 + std::mem::size_of::<MintPolicy>(),
+    )]
+    pub mint_policy: Account<'info, MintPolicy>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> SetMintPolicy<'info> {
+    pub fn set_mint_policy(
+        &mut self,
+        rejected_features: u16,
+        bumps: &SetMintPolicyBumps,
+    ) -> Result<()> {
+        require!(
+            rejected_features & !MINT_FEATURES == 0,
+            ErrorCode::InvalidMintPolicy
+        );
+
+        self.mint_policy.set_inner(MintPolicy {
+            rejected_features,
+            bump: bumps.mint_policy,
+        });
+
+        emit!(MintPolicyUpdated { rejected_features });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,39 @@
+use {
+    crate::{
+        error::ErrorCode,
+        events::PoolStatusUpdated,
+        state::{BasePool, Permission, POOL_STATUSES},
+    },
+    anchor_lang::prelude::*,
+};
+
+#[derive(Accounts)]
+pub struct SetPoolStatus<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(mut)]
+    pub pool: Account<'info, BasePool>,
+
+    #[account(
+        seeds = [b"super_admin"],
+        bump,
+        has_one = authority,
+        constraint = super_admin.is_super_authority @ ErrorCode::InvalidPermissions
+    )]
+    pub super_admin: Account<'info, Permission>,
+}
+
+impl<'info> SetPoolStatus<'info> {
+    pub fn set_pool_status(&mut self, statuses: u16) -> Result<()> {
+        require!(statuses & !POOL_STATUSES == 0, ErrorCode::InvalidStatus);
+
+        self.pool.statuses = statuses;
+
+        emit!(PoolStatusUpdated {
+            pool: self.pool.key(),
+            statuses,
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,38 @@
+use {
+    crate::{error::ErrorCode, events::VaultAllowlistUpdated, LpVault, Permission},
+    anchor_lang::prelude::*,
+};
+
+#[derive(Accounts)]
+pub struct SetVaultAllowlist<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        has_one = authority,
+    )]
+    pub permission: Account<'info, Permission>,
+
+    #[account(mut)]
+    pub lp_vault: Account<'info, LpVault>,
+}
+
+impl<'info> SetVaultAllowlist<'info> {
+    pub fn validate(ctx: &Context<SetVaultAllowlist>) -> Result<()> {
+        require!(
+            ctx.accounts.permission.can_manage_vaults(),
+            ErrorCode::InvalidPermissions
+        );
+        Ok(())
+    }
+
+    pub fn set_vault_allowlist(&mut self, allowlist_enabled: bool) -> Result<()> {
+        self.lp_vault.allowlist_enabled = allowlist_enabled;
+
+        emit!(VaultAllowlistUpdated {
+            vault: self.lp_vault.shares_mint,
+            allowlist_enabled,
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,53 @@
+use {
+    crate::{
+        error::ErrorCode, events::VaultCooldownUpdated, LpVault, Permission, MAX_EXIT_FEE_BPS,
+        MAX_LP_COOLDOWN,
+    },
+    anchor_lang::prelude::*,
+};
+
+#[derive(Accounts)]
+pub struct SetVaultCooldown<'info> {
+    /// The key that has permission to init the vault
+    pub authority: Signer<'info>,
+
+    #[account(
+        has_one = authority,
+    )]
+    pub permission: Account<'info, Permission>,
+
+    #[account(mut)]
+    pub lp_vault: Account<'info, LpVault>,
+}
+
+impl<'info> SetVaultCooldown<'info> {
+    pub fn validate(
+        ctx: &Context<SetVaultCooldown>,
+        lp_cooldown: i64,
+        exit_fee_bps: u16,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.permission.can_init_vault(),
+            ErrorCode::InvalidPermissions
+        );
+        require!(
+            (0..=MAX_LP_COOLDOWN).contains(&lp_cooldown),
+            ErrorCode::InvalidLpCooldown
+        );
+        require!(exit_fee_bps <= MAX_EXIT_FEE_BPS, ErrorCode::InvalidFee);
+        Ok(())
+    }
+
+    pub fn set_vault_cooldown(&mut self, lp_cooldown: i64, exit_fee_bps: u16) -> Result<()> {
+        self.lp_vault.lp_cooldown = lp_cooldown;
+        self.lp_vault.exit_fee_bps = exit_fee_bps;
+
+        emit!(VaultCooldownUpdated {
+            vault: self.lp_vault.shares_mint,
+            lp_cooldown,
+            exit_fee_bps,
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,45 @@
+use {
+    crate::{error::ErrorCode, events::VaultDepositCapsUpdated, LpVault, Permission},
+    anchor_lang::prelude::*,
+};
+
+#[derive(Accounts)]
+pub struct SetVaultDepositCaps<'info> {
+    /// The key that has permission to init the vault
+    pub authority: Signer<'info>,
+
+    #[account(
+        has_one = authority,
+    )]
+    pub permission: Account<'info, Permission>,
+
+    #[account(mut)]
+    pub lp_vault: Account<'info, LpVault>,
+}
+
+impl<'info> SetVaultDepositCaps<'info> {
+    pub fn validate(ctx: &Context<SetVaultDepositCaps>) -> Result<()> {
+        require!(
+            ctx.accounts.permission.can_init_vault(),
+            ErrorCode::InvalidPermissions
+        );
+        Ok(())
+    }
+
+    pub fn set_vault_deposit_caps(
+        &mut self,
+        deposit_cap: u64,
+        wallet_deposit_cap: u64,
+    ) -> Result<()> {
+        self.lp_vault.deposit_cap = deposit_cap;
+        self.lp_vault.wallet_deposit_cap = wallet_deposit_cap;
+
+        emit!(VaultDepositCapsUpdated {
+            vault: self.lp_vault.shares_mint,
+            deposit_cap,
+            wallet_deposit_cap,
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,91 @@
+use {
+    super::crystallize_fees,
+    crate::{
+        error::ErrorCode, events::VaultFeesUpdated, LpVault, Permission, MAX_MANAGEMENT_FEE_BPS,
+        MAX_PERFORMANCE_FEE_BPS,
+    },
+    anchor_lang::prelude::*,
+    anchor_spl::{
+        token_2022::Token2022,
+        token_interface::{Mint, TokenAccount},
+    },
+};
+
+#[derive(Accounts)]
+pub struct SetVaultFees<'info> {
+    /// The key that has permission to init the vault
+    pub authority: Signer<'info>,
+
+    #[account(
+        has_one = authority,
+    )]
+    pub permission: Account<'info, Permission>,
+
+    #[account(
+        mut,
+        has_one = shares_mint,
+    )]
+    pub lp_vault: Account<'info, LpVault>,
+
+    #[account(mut)]
+    pub shares_mint: InterfaceAccount<'info, Mint>,
+
+    /// The shares token account that will receive the fees, including those accrued so far
+    #[account(
+        mut,
+        token::mint = shares_mint,
+        token::token_program = shares_token_program,
+    )]
+    pub fee_shares_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub shares_token_program: Program<'info, Token2022>,
+}
+
+impl<'info> SetVaultFees<'info> {
+    pub fn validate(
+        ctx: &Context<SetVaultFees>,
+        performance_fee_bps: u16,
+        management_fee_bps: u16,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.permission.can_init_vault(),
+            ErrorCode::InvalidPermissions
+        );
+        require!(
+            performance_fee_bps <= MAX_PERFORMANCE_FEE_BPS,
+            ErrorCode::InvalidFee
+        );
+        require!(
+            management_fee_bps <= MAX_MANAGEMENT_FEE_BPS,
+            ErrorCode::InvalidFee
+        );
+        Ok(())
+    }
+
+    pub fn set_vault_fees(
+        &mut self,
+        performance_fee_bps: u16,
+        management_fee_bps: u16,
+    ) -> Result<()> {
+        // Fees accrued at the previous rates are crystallised before the new rates apply
+        self.lp_vault.fee_shares_account = self.fee_shares_account.key();
+        crystallize_fees(
+            &mut self.lp_vault,
+            &mut self.shares_mint,
+            &self.fee_shares_account,
+            &self.shares_token_program,
+        )?;
+
+        self.lp_vault.performance_fee_bps = performance_fee_bps;
+        self.lp_vault.management_fee_bps = management_fee_bps;
+
+        emit!(VaultFeesUpdated {
+            vault: self.lp_vault.shares_mint,
+            fee_shares_account: self.fee_shares_account.key(),
+            performance_fee_bps,
+            management_fee_bps,
+        });
+
+        Ok(())
+    }
+}
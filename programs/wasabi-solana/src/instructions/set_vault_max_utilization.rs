@@ -0,0 +1,46 @@
+use {
+    crate::{
+        error::ErrorCode, events::VaultMaxUtilizationUpdated, LpVault, Permission,
+        UTILIZATION_BPS_DENOMINATOR,
+    },
+    anchor_lang::prelude::*,
+};
+
+#[derive(Accounts)]
+pub struct SetVaultMaxUtilization<'info> {
+    /// The key that has permission to init the vault
+    pub authority: Signer<'info>,
+
+    #[account(
+        has_one = authority,
+    )]
+    pub permission: Account<'info, Permission>,
+
+    #[account(mut)]
+    pub lp_vault: Account<'info, LpVault>,
+}
+
+impl<'info> SetVaultMaxUtilization<'info> {
+    pub fn validate(ctx: &Context<SetVaultMaxUtilization>, max_utilization_bps: u16) -> Result<()> {
+        require!(
+            ctx.accounts.permission.can_init_vault(),
+            ErrorCode::InvalidPermissions
+        );
+        require!(
+            max_utilization_bps <= UTILIZATION_BPS_DENOMINATOR,
+            ErrorCode::InvalidUtilization
+        );
+        Ok(())
+    }
+
+    pub fn set_vault_max_utilization(&mut self, max_utilization_bps: u16) -> Result<()> {
+        self.lp_vault.max_utilization_bps = max_utilization_bps;
+
+        emit!(VaultMaxUtilizationUpdated {
+            vault: self.lp_vault.shares_mint,
+            max_utilization_bps,
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,48 @@
+use {
+    crate::{
+        error::ErrorCode, events::VaultVestingPeriodUpdated, LpVault, Permission,
+        MAX_VESTING_PERIOD,
+    },
+    anchor_lang::prelude::*,
+};
+
+#[derive(Accounts)]
+pub struct SetVaultVestingPeriod<'info> {
+    /// The key that has permission to init the vault
+    pub authority: Signer<'info>,
+
+    #[account(
+        has_one = authority,
+    )]
+    pub permission: Account<'info, Permission>,
+
+    #[account(mut)]
+    pub lp_vault: Account<'info, LpVault>,
+}
+
+impl<'info> SetVaultVestingPeriod<'info> {
+    pub fn validate(ctx: &Context<SetVaultVestingPeriod>, vesting_period: i64) -> Result<()> {
+        require!(
+            ctx.accounts.permission.can_init_vault(),
+            ErrorCode::InvalidPermissions
+        );
+        require!(
+            (0..=MAX_VESTING_PERIOD).contains(&vesting_period),
+            ErrorCode::InvalidVestingPeriod
+        );
+        Ok(())
+    }
+
+    pub fn set_vault_vesting_period(&mut self, vesting_period: i64) -> Result<()> {
+        // Assets already unvested keep vesting on their current schedule
+        self.lp_vault.vest(Clock::get()?.unix_timestamp)?;
+        self.lp_vault.vesting_period = vesting_period;
+
+        emit!(VaultVestingPeriodUpdated {
+            vault: self.lp_vault.shares_mint,
+            vesting_period,
+        });
+
+        Ok(())
+    }
+}
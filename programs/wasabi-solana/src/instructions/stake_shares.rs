@@ -0,0 +1,108 @@
+use {
+    crate::{events::SharesStaked, lp_vault_signer_seeds, LpVault, RewardPool, RewardStake},
+    anchor_lang::prelude::*,
+    anchor_spl::{
+        token_2022::Token2022,
+        token_interface::{self, Burn, Mint, MintTo, TokenAccount},
+    },
+};
+
+// Staked shares are escrowed in the pool's stake vault by burning the owner's shares and minting
+// them to the vault, as a transfer of hook-enabled shares can't be made from within this program.
+
+#[derive(Accounts)]
+pub struct StakeShares<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        associated_token::mint = shares_mint,
+        associated_token::authority = owner,
+        associated_token::token_program = shares_token_program,
+    )]
+    pub owner_shares_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        has_one = lp_vault,
+        has_one = shares_mint,
+        has_one = stake_vault,
+    )]
+    pub reward_pool: Box<Account<'info, RewardPool>>,
+
+    /// Mints the escrowed shares
+    #[account(has_one = shares_mint)]
+    pub lp_vault: Box<Account<'info, LpVault>>,
+
+    #[account(mut)]
+    pub stake_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub shares_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        seeds = [b"reward_stake", reward_pool.key().as_ref(), owner.key().as_ref()],
+        bump,
+        space = 8 + std::mem::size_of::<RewardStake>(),
+    )]
+    pub reward_stake: Box<Account<'info, RewardStake>>,
+
+    pub shares_token_program: Program<'info, Token2022>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> StakeShares<'info> {
+    fn burn_shares_from_owner(&self, amount: u64) -> Result<()> {
+        let cpi_accounts = Burn {
+            mint: self.shares_mint.to_account_info(),
+            from: self.owner_shares_account.to_account_info(),
+            authority: self.owner.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(self.shares_token_program.to_account_info(), cpi_accounts);
+        token_interface::burn(cpi_ctx, amount)
+    }
+
+    fn mint_shares_to_stake_vault(&self, amount: u64) -> Result<()> {
+        let cpi_accounts = MintTo {
+            mint: self.shares_mint.to_account_info(),
+            to: self.stake_vault.to_account_info(),
+            authority: self.lp_vault.to_account_info(),
+        };
+        let cpi_ctx = CpiContext {
+            program: self.shares_token_program.to_account_info(),
+            accounts: cpi_accounts,
+            remaining_accounts: Vec::new(),
+            signer_seeds: &[lp_vault_signer_seeds!(self.lp_vault)],
+        };
+        token_interface::mint_to(cpi_ctx, amount)
+    }
+
+    pub fn stake_shares(&mut self, amount: u64, bumps: &StakeSharesBumps) -> Result<()> {
+        if self.reward_stake.owner == Pubkey::default() {
+            self.reward_stake.set_inner(RewardStake {
+                owner: self.owner.key(),
+                reward_pool: self.reward_pool.key(),
+                staked: 0,
+                reward_debt: 0,
+                pending_rewards: 0,
+                bump: bumps.reward_stake,
+            });
+        }
+
+        self.reward_pool.update(Clock::get()?.unix_timestamp)?;
+        self.reward_stake.stake(&mut self.reward_pool, amount)?;
+        self.burn_shares_from_owner(amount)?;
+        self.mint_shares_to_stake_vault(amount)?;
+
+        emit!(SharesStaked {
+            reward_pool: self.reward_pool.key(),
+            owner: self.owner.key(),
+            amount,
+        });
+
+        Ok(())
+    }
+}
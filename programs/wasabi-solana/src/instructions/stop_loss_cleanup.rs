@@ -1,7 +1,9 @@
 use {
     crate::{
-        error::ErrorCode, instructions::close_position_cleanup::*, utils::get_function_hash,
-        StopLossOrder,
+        error::ErrorCode,
+        instructions::close_position_cleanup::*,
+        utils::{get_function_hash, transfer_lamports_from_pda},
+        validate_exit_order_execution, ExitOrderType, OrderSide, StopLossOrder,
     },
     anchor_lang::prelude::*,
 };
@@ -28,52 +30,47 @@ impl<'info> StopLossCleanup<'info> {
             .close_position_cleanup
             .close_position_cleanup(&CloseAction::ExitOrder(1))?;
 
-        if self.close_position_cleanup.pool.is_long_pool {
-            // Handle additional checks for a Stop Loss Order of a long pool
+        let (actual_maker_amount, actual_taker_amount) =
+            if self.stop_loss_order.side == OrderSide::Long {
+                // The whole position is sold, so the currency received before fees is what the
+                // order's `maker_amount` was sold for.
+                // actualTakerAmount = payout + closeFee + interestPaid + principalRepaid
+                let actual_taker_amount: u64 = (close_amounts.payout as u128)
+                    .checked_add(close_amounts.close_fee as u128)
+                    .ok_or(ErrorCode::ArithmeticOverflow)?
+                    .checked_add(close_amounts.interest_paid as u128)
+                    .ok_or(ErrorCode::ArithmeticOverflow)?
+                    .checked_add(close_amounts.principal_repaid as u128)
+                    .ok_or(ErrorCode::ArithmeticOverflow)?
+                    .try_into()
+                    .map_err(|_| ErrorCode::U64Overflow)?;
+                (self.stop_loss_order.maker_amount, actual_taker_amount)
+            } else {
+                // Collateral is sold to buy back the principal and interest
+                let actual_taker_amount = close_amounts
+                    .interest_paid
+                    .checked_add(close_amounts.principal_repaid)
+                    .ok_or(ErrorCode::ArithmeticOverflow)?;
+                (close_amounts.collateral_spent, actual_taker_amount)
+            };
 
-            // uint256 actualTakerAmount = closeAmounts.payout + closeAmounts.closeFee + closeAmounts.interestPaid + closeAmounts.principalRepaid;
-            // if (actualTakerAmount > _order.takerAmount) revert PriceTargetNotReached();
-            let payout_u128 = close_amounts.payout as u128;
-            let actual_taker_amount = payout_u128
-                .checked_add(close_amounts.close_fee as u128)
-                .ok_or(ErrorCode::ArithmeticOverflow)?
-                .checked_add(close_amounts.interest_paid as u128)
-                .ok_or(ErrorCode::ArithmeticOverflow)?
-                .checked_add(close_amounts.principal_repaid as u128)
-                .ok_or(ErrorCode::ArithmeticOverflow)?
-                .try_into()
-                .map_err(|_| ErrorCode::U64Overflow)?;
+        validate_exit_order_execution(
+            ExitOrderType::StopLoss,
+            &self.stop_loss_order.price,
+            actual_maker_amount,
+            actual_taker_amount,
+            self.close_position_cleanup.collateral.decimals,
+            self.close_position_cleanup.currency.decimals,
+        )?;
 
-            require_gte!(
-                self.stop_loss_order.taker_amount,
-                actual_taker_amount,
-                ErrorCode::PriceTargetNotReached
-            );
-        } else {
-            // Handle additional checks for a Take Profit Order of a short pool
-
-            // if (actualMakerAmount * _order.takerAmount < _order.makerAmount * actualTakerAmount)
-            // revert PriceTargetNotReached();
-
-            // order price      = order.makerAmount / order.takerAmount
-            // executed price   = actualMakerAmount / actualTakerAmount
-            // SL: executed price >= order price
-            //      actualMakerAmount / actualTakerAmount >= order.makerAmount / order.takerAmount
-            //      actualMakerAmount * order.takerAmount >= order.makerAmount * actualTakerAmount
-            let interest_paid_u128 = close_amounts.interest_paid as u128;
-            let collateral_spent_u128 = close_amounts.collateral_spent as u128;
-            let actual_taker_amount = interest_paid_u128
-                .checked_add(close_amounts.principal_repaid as u128)
-                .ok_or(ErrorCode::ArithmeticOverflow)?;
-            let lhs = collateral_spent_u128
-                .checked_mul(self.stop_loss_order.taker_amount as u128)
-                .ok_or(ErrorCode::ArithmeticOverflow)?;
-            let rhs = actual_taker_amount
-                .checked_mul(self.stop_loss_order.maker_amount as u128)
-                .ok_or(ErrorCode::ArithmeticOverflow)?;
-
-            require_gte!(lhs, rhs, ErrorCode::PriceTargetNotReached);
-        }
+        // Pay the executor from the escrowed execution fee, the remainder is refunded on close
+        transfer_lamports_from_pda(
+            &self.stop_loss_order.to_account_info(),
+            &self.close_position_cleanup.authority.to_account_info(),
+            self.close_position_cleanup
+                .close_position_request
+                .execution_fee,
+        )?;
 
         self.stop_loss_order
             .close(self.close_position_cleanup.owner.to_account_info())?;
@@ -2,7 +2,7 @@ use {
     super::StopLossCleanup,
     crate::{
         error::ErrorCode, instructions::close_position_setup::*, long_pool_signer_seeds,
-        short_pool_signer_seeds,
+        short_pool_signer_seeds, StopLossOrder,
     },
     anchor_lang::prelude::*,
 };
@@ -10,10 +10,20 @@ use {
 #[derive(Accounts)]
 pub struct StopLossSetup<'info> {
     pub close_position_setup: ClosePositionSetup<'info>,
+
+    #[account(
+        seeds = [b"stop_loss_order", close_position_setup.position.key().as_ref()],
+        bump,
+    )]
+    pub stop_loss_order: Account<'info, StopLossOrder>,
 }
 
 impl<'info> StopLossSetup<'info> {
-    pub fn validate(ctx: &Context<StopLossSetup>, expiration: i64) -> Result<()> {
+    pub fn validate(
+        ctx: &Context<StopLossSetup>,
+        execution_fee: u64,
+        expiration: i64,
+    ) -> Result<()> {
         // Validate the authority can co-sign swaps
         require!(
             ctx.accounts
@@ -23,10 +33,17 @@ impl<'info> StopLossSetup<'info> {
             ErrorCode::InvalidPermissions
         );
 
+        // The executor can't charge more than the trader prefunded
+        require_gte!(
+            ctx.accounts.stop_loss_order.execution_fee,
+            execution_fee,
+            ErrorCode::ExecutionFeeExceeded
+        );
+
         ClosePositionSetup::validate(
             &ctx.accounts.close_position_setup,
             expiration,
-            StopLossCleanup::get_hash(),
+            &[StopLossCleanup::get_hash()],
         )?;
 
         Ok(())
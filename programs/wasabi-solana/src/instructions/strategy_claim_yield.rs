@@ -2,6 +2,7 @@ use {
     crate::{
         error::ErrorCode,
         events::StrategyClaim,
+        instructions::checkpoint_share_price,
         state::{LpVault, Permission, Strategy},
         utils::{get_function_hash, get_shares_mint_address, validate_difference},
     },
@@ -34,6 +35,19 @@ pub struct StrategyClaimYield<'info> {
         bump
     )]
     pub strategy: Account<'info, Strategy>,
+
+    /// Required once the share price history exists
+    #[account(address = lp_vault.shares_mint)]
+    pub shares_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+
+    /// CHECK: The LP Vault's share price history, required once the vault has one. The share
+    /// price after the claim is then recorded in it.
+    #[account(
+        mut,
+        seeds = [b"share_price_history", lp_vault.key().as_ref()],
+        bump,
+    )]
+    pub share_price_history: Option<UncheckedAccount<'info>>,
 }
 
 impl<'info> StrategyClaimYield<'info> {
@@ -58,6 +72,10 @@ impl<'info> StrategyClaimYield<'info> {
         let interest_earned = new_quote.abs_diff(self.strategy.total_borrowed_amount);
         let mut interest_earned_i64: i64 = interest_earned.try_into()?;
 
+        // Losses are realised instantly while yield vests
+        let now = Clock::get()?.unix_timestamp;
+        self.lp_vault.vest(now)?;
+
         if new_quote <= self.strategy.total_borrowed_amount {
             self.strategy.total_borrowed_amount = self
                 .strategy
@@ -84,19 +102,23 @@ impl<'info> StrategyClaimYield<'info> {
                 .checked_add(interest_earned)
                 .ok_or(ErrorCode::ArithmeticOverflow)?;
 
-            self.lp_vault.total_assets = self
-                .lp_vault
-                .total_assets
-                .checked_add(interest_earned)
-                .ok_or(ErrorCode::ArithmeticOverflow)?;
+            self.lp_vault.add_unvested_assets(interest_earned, now)?;
 
             self.lp_vault.total_borrowed = self
                 .lp_vault
                 .total_borrowed
                 .checked_add(interest_earned)
                 .ok_or(ErrorCode::ArithmeticOverflow)?;
+
+            self.lp_vault.accrue_performance_fee(interest_earned)?;
         }
 
+        checkpoint_share_price(
+            self.share_price_history.as_deref(),
+            &self.lp_vault,
+            self.shares_mint.as_deref_mut(),
+        )?;
+
         emit!(StrategyClaim {
             strategy: self.strategy.key(),
             vault_address: shares_mint,
@@ -77,10 +77,16 @@ impl<'info> StrategyDepositSetup<'info> {
             ErrorCode::InvalidPermissions
         );
 
+        require!(
+            ctx.accounts.lp_vault.can_borrow(),
+            ErrorCode::VaultBorrowsPaused
+        );
+        ctx.accounts.lp_vault.validate_strategy_borrow(amount_in)?;
+
         // Ensure there is a cleanup instruction
         setup_transaction_introspection_validation(
             &ctx.accounts.sysvar_info,
-            StrategyDepositCleanup::get_hash(),
+            &[StrategyDepositCleanup::get_hash()],
             false,
         )?;
 
@@ -2,6 +2,7 @@ use {
     crate::{
         error::ErrorCode,
         events::StrategyWithdraw,
+        instructions::reserve_repayment_for_queue,
         lp_vault_signer_seeds,
         state::{LpVault, Permission, Strategy, StrategyRequest},
         utils::{get_function_hash, get_shares_mint_address},
@@ -59,6 +60,39 @@ pub struct StrategyWithdrawCleanup<'info> {
     ///CHECK: Applied by constraint
     #[account(address = crate::ID)]
     pub wasabi_program: AccountInfo<'info>,
+
+    /// CHECK: The LP Vault's withdrawal queue, required once the vault has one. The principal
+    /// received is then reserved for it.
+    #[account(
+        mut,
+        seeds = [b"withdrawal_queue", lp_vault.key().as_ref()],
+        bump,
+    )]
+    pub withdrawal_queue: Option<UncheckedAccount<'info>>,
+
+    /// Required once the withdrawal queue exists
+    #[account(mut)]
+    pub queue_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// Required once the withdrawal queue exists
+    #[account(address = lp_vault.asset)]
+    pub asset_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+
+    /// Required once the withdrawal queue exists
+    pub asset_token_program: Option<Interface<'info, TokenInterface>>,
+
+    /// Required once the share price history exists
+    #[account(address = lp_vault.shares_mint)]
+    pub shares_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+
+    /// CHECK: The LP Vault's share price history, required once the vault has one. The share
+    /// price after the claim is then recorded in it.
+    #[account(
+        mut,
+        seeds = [b"share_price_history", lp_vault.key().as_ref()],
+        bump,
+    )]
+    pub share_price_history: Option<UncheckedAccount<'info>>,
 }
 
 impl<'info> StrategyWithdrawCleanup<'info> {
@@ -169,6 +203,17 @@ impl<'info> StrategyWithdrawCleanup<'info> {
             principal_received
         };
 
+        // The program id stands in for the missing optional accounts
+        let shares_mint = self
+            .shares_mint
+            .as_ref()
+            .map_or(self.wasabi_program.to_account_info(), |shares_mint| {
+                shares_mint.to_account_info()
+            });
+        let share_price_history = self.share_price_history.as_ref().map_or(
+            self.wasabi_program.to_account_info(),
+            |share_price_history| share_price_history.to_account_info(),
+        );
         let sighash = StrategyClaimYield::get_hash();
         let mut ix_data = Vec::with_capacity(16);
 
@@ -182,6 +227,8 @@ impl<'info> StrategyWithdrawCleanup<'info> {
                 AccountMeta::new(self.lp_vault.key(), false),
                 AccountMeta::new_readonly(self.collateral.key(), false),
                 AccountMeta::new(self.strategy.key(), false),
+                AccountMeta::new_readonly(shares_mint.key(), false),
+                AccountMeta::new(share_price_history.key(), false),
             ],
             data: ix_data,
         };
@@ -195,6 +242,9 @@ impl<'info> StrategyWithdrawCleanup<'info> {
                 self.lp_vault.to_account_info(),
                 self.collateral.to_account_info(),
                 self.strategy.to_account_info(),
+                shares_mint,
+                share_price_history,
+                self.wasabi_program.to_account_info(),
             ],
         )?;
 
@@ -223,6 +273,24 @@ impl<'info> StrategyWithdrawCleanup<'info> {
 
         self.strategy.last_updated = Clock::get()?.unix_timestamp;
 
+        // Repayments go to queued withdrawals first
+        if self.lp_vault.has_withdrawal_queue {
+            let (Some(asset_mint), Some(asset_token_program)) =
+                (self.asset_mint.as_ref(), self.asset_token_program.as_ref())
+            else {
+                return err!(ErrorCode::MissingWithdrawalQueueAccounts);
+            };
+            reserve_repayment_for_queue(
+                self.withdrawal_queue.as_deref(),
+                self.queue_vault.as_deref_mut(),
+                &mut self.lp_vault,
+                &self.vault,
+                asset_mint,
+                asset_token_program,
+                principal_received,
+            )?;
+        }
+
         emit!(StrategyWithdraw {
             strategy: self.strategy.key(),
             vault_address: get_shares_mint_address(&self.lp_vault.key(), &self.strategy.currency),
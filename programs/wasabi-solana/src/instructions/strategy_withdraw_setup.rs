@@ -76,7 +76,7 @@ impl<'info> StrategyWithdrawSetup<'info> {
 
         setup_transaction_introspection_validation(
             &ctx.accounts.sysvar_info,
-            StrategyWithdrawCleanup::get_hash(),
+            &[StrategyWithdrawCleanup::get_hash()],
             false,
         )?;
 
@@ -1,7 +1,9 @@
 use {
     crate::{
-        error::ErrorCode, instructions::close_position_cleanup::*, utils::get_function_hash,
-        TakeProfitOrder,
+        error::ErrorCode,
+        instructions::close_position_cleanup::*,
+        utils::{get_function_hash, transfer_lamports_from_pda},
+        validate_exit_order_execution, ExitOrderType, OrderSide, TakeProfitOrder,
     },
     anchor_lang::prelude::*,
 };
@@ -27,50 +29,47 @@ impl<'info> TakeProfitCleanup<'info> {
         let close_amounts = self
             .close_position_cleanup
             .close_position_cleanup(&CloseAction::ExitOrder(0))?;
-        if self.close_position_cleanup.pool.is_long_pool {
-            // Handle additional checks for a Take Profit Order of a long pool
+        let (actual_maker_amount, actual_taker_amount) =
+            if self.take_profit_order.side == OrderSide::Long {
+                // The whole position is sold, so the currency received before fees is what the
+                // order's `maker_amount` was sold for.
+                // actualTakerAmount = payout + closeFee + interestPaid + principalRepaid
+                let actual_taker_amount: u64 = (close_amounts.payout as u128)
+                    .checked_add(close_amounts.close_fee as u128)
+                    .ok_or(ErrorCode::ArithmeticOverflow)?
+                    .checked_add(close_amounts.interest_paid as u128)
+                    .ok_or(ErrorCode::ArithmeticOverflow)?
+                    .checked_add(close_amounts.principal_repaid as u128)
+                    .ok_or(ErrorCode::ArithmeticOverflow)?
+                    .try_into()
+                    .map_err(|_| ErrorCode::U64Overflow)?;
+                (self.take_profit_order.maker_amount, actual_taker_amount)
+            } else {
+                // Collateral is sold to buy back the principal and interest
+                let actual_taker_amount = close_amounts
+                    .interest_paid
+                    .checked_add(close_amounts.principal_repaid)
+                    .ok_or(ErrorCode::ArithmeticOverflow)?;
+                (close_amounts.collateral_spent, actual_taker_amount)
+            };
 
-            // uint256 actualTakerAmount = closeAmounts.payout + closeAmounts.closeFee + closeAmounts.interestPaid + closeAmounts.principalRepaid;
-            // if (actualTakerAmount < _order.takerAmount) revert PriceTargetNotReached();
-            let payout_u128 = close_amounts.payout as u128;
-            let target_amount: u64 = payout_u128
-                .checked_add(close_amounts.close_fee as u128)
-                .ok_or(ErrorCode::ArithmeticOverflow)?
-                .checked_add(close_amounts.interest_paid as u128)
-                .ok_or(ErrorCode::ArithmeticOverflow)?
-                .checked_add(close_amounts.principal_repaid as u128)
-                .ok_or(ErrorCode::ArithmeticOverflow)?
-                .try_into()
-                .map_err(|_| ErrorCode::U64Overflow)?;
+        validate_exit_order_execution(
+            ExitOrderType::TakeProfit,
+            &self.take_profit_order.price,
+            actual_maker_amount,
+            actual_taker_amount,
+            self.close_position_cleanup.collateral.decimals,
+            self.close_position_cleanup.currency.decimals,
+        )?;
 
-            require_gte!(
-                target_amount,
-                self.take_profit_order.taker_amount,
-                ErrorCode::PriceTargetNotReached
-            );
-        } else {
-            // Handle additional checks for a Take Profit Order of a short pool
-
-            // order price      = order.makerAmount / order.takerAmount
-            // executed price   = actualMakerAmount / actualTakerAmount
-            // TP: executed price <= order price
-            //      actualMakerAmount / actualTakerAmount <= order.makerAmount / order.takerAmount
-            //      actualMakerAmount * order.takerAmount <= order.makerAmount * actualTakerAmount
-            let interest_paid_u128 = close_amounts.interest_paid as u128;
-            let collateral_spent_u128 = close_amounts.collateral_spent as u128;
-
-            let actual_taker_amount = interest_paid_u128
-                .checked_add(close_amounts.principal_repaid as u128)
-                .ok_or(ErrorCode::ArithmeticOverflow)?;
-            let lhs = collateral_spent_u128
-                .checked_mul(self.take_profit_order.taker_amount as u128)
-                .ok_or(ErrorCode::ArithmeticOverflow)?;
-            let rhs = actual_taker_amount
-                .checked_mul(self.take_profit_order.maker_amount as u128)
-                .ok_or(ErrorCode::ArithmeticOverflow)?;
-
-            require_gte!(rhs, lhs, ErrorCode::PriceTargetNotReached);
-        }
+        // Pay the executor from the escrowed execution fee, the remainder is refunded on close
+        transfer_lamports_from_pda(
+            &self.take_profit_order.to_account_info(),
+            &self.close_position_cleanup.authority.to_account_info(),
+            self.close_position_cleanup
+                .close_position_request
+                .execution_fee,
+        )?;
 
         self.take_profit_order
             .close(self.close_position_cleanup.owner.to_account_info())?;
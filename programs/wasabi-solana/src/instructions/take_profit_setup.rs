@@ -2,7 +2,7 @@ use {
     super::TakeProfitCleanup,
     crate::{
         error::ErrorCode, instructions::close_position_setup::*, long_pool_signer_seeds,
-        short_pool_signer_seeds,
+        short_pool_signer_seeds, TakeProfitOrder,
     },
     anchor_lang::prelude::*,
 };
@@ -10,10 +10,20 @@ use {
 #[derive(Accounts)]
 pub struct TakeProfitSetup<'info> {
     pub close_position_setup: ClosePositionSetup<'info>,
+
+    #[account(
+        seeds = [b"take_profit_order", close_position_setup.position.key().as_ref()],
+        bump,
+    )]
+    pub take_profit_order: Account<'info, TakeProfitOrder>,
 }
 
 impl<'info> TakeProfitSetup<'info> {
-    pub fn validate(ctx: &Context<TakeProfitSetup>, expiration: i64) -> Result<()> {
+    pub fn validate(
+        ctx: &Context<TakeProfitSetup>,
+        execution_fee: u64,
+        expiration: i64,
+    ) -> Result<()> {
         // Validate the authority can co-sign swaps
         require!(
             ctx.accounts
@@ -23,10 +33,17 @@ impl<'info> TakeProfitSetup<'info> {
             ErrorCode::InvalidPermissions
         );
 
+        // The executor can't charge more than the trader prefunded
+        require_gte!(
+            ctx.accounts.take_profit_order.execution_fee,
+            execution_fee,
+            ErrorCode::ExecutionFeeExceeded
+        );
+
         ClosePositionSetup::validate(
             &ctx.accounts.close_position_setup,
             expiration,
-            TakeProfitCleanup::get_hash(),
+            &[TakeProfitCleanup::get_hash()],
         )?;
 
         Ok(())
@@ -0,0 +1,91 @@
+use {
+    crate::{
+        error::ErrorCode, events::RewardPoolToppedUp, utils::balance_increase, Permission,
+        RewardPool,
+    },
+    anchor_lang::prelude::*,
+    anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+#[derive(Accounts)]
+pub struct TopUpRewardPool<'info> {
+    /// The key that has permission to init the vault, and funds the rewards
+    pub authority: Signer<'info>,
+
+    #[account(
+        has_one = authority,
+    )]
+    pub permission: Account<'info, Permission>,
+
+    #[account(
+        mut,
+        has_one = reward_mint,
+        has_one = reward_vault,
+    )]
+    pub reward_pool: Box<Account<'info, RewardPool>>,
+
+    #[account(mut)]
+    pub reward_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub reward_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        token::mint = reward_mint,
+        token::authority = authority,
+        token::token_program = reward_token_program,
+    )]
+    pub authority_reward_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub reward_token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> TopUpRewardPool<'info> {
+    pub fn validate(ctx: &Context<TopUpRewardPool>) -> Result<()> {
+        require!(
+            ctx.accounts.permission.can_init_vault(),
+            ErrorCode::InvalidPermissions
+        );
+        Ok(())
+    }
+
+    fn transfer_rewards_to_vault(&self, amount: u64) -> Result<()> {
+        let cpi_accounts = TransferChecked {
+            from: self.authority_reward_account.to_account_info(),
+            mint: self.reward_mint.to_account_info(),
+            to: self.reward_vault.to_account_info(),
+            authority: self.authority.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(self.reward_token_program.to_account_info(), cpi_accounts);
+        token_interface::transfer_checked(cpi_ctx, amount, self.reward_mint.decimals)
+    }
+
+    /// Funds the pool with `amount` more rewards and sets the per-second `emission_rate`
+    pub fn top_up_reward_pool(&mut self, amount: u64, emission_rate: u64) -> Result<()> {
+        // Rewards accrued at the previous rate are emitted before the new rate applies
+        self.reward_pool.update(Clock::get()?.unix_timestamp)?;
+
+        let amount = if amount > 0 {
+            let balance_before = self.reward_vault.amount;
+            self.transfer_rewards_to_vault(amount)?;
+            balance_increase(&mut self.reward_vault, balance_before)?
+        } else {
+            0
+        };
+
+        self.reward_pool.remaining_rewards = self
+            .reward_pool
+            .remaining_rewards
+            .checked_add(amount)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        self.reward_pool.emission_rate = emission_rate;
+
+        emit!(RewardPoolToppedUp {
+            reward_pool: self.reward_pool.key(),
+            amount,
+            emission_rate,
+        });
+
+        Ok(())
+    }
+}
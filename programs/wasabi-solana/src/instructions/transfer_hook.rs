@@ -0,0 +1,110 @@
+use {
+    crate::{error::ErrorCode, LpVault},
+    anchor_lang::prelude::*,
+    anchor_spl::{
+        token_2022::spl_token_2022::{
+            extension::{
+                transfer_hook::TransferHookAccount, BaseStateWithExtensions, StateWithExtensions,
+            },
+            state::Account as SplTokenAccount,
+        },
+        token_interface::{Mint, TokenAccount},
+    },
+    spl_tlv_account_resolution::{account::ExtraAccountMeta, seeds::Seed},
+};
+
+// Token-2022 invokes this hook on every transfer of an allowlisted vault's shares, rejecting
+// transfers to wallets without an `AllowlistedWallet` while the vault's allowlist is enabled.
+
+/// Number of accounts the hook needs beyond those of the transfer
+pub const EXTRA_ACCOUNT_METAS_LEN: usize = 2;
+
+/// The LP Vault and the destination owner's `AllowlistedWallet`, in the order of `TransferHook`
+pub fn allowlist_extra_account_metas(
+    lp_vault: &Pubkey,
+) -> Result<[ExtraAccountMeta; EXTRA_ACCOUNT_METAS_LEN]> {
+    Ok([
+        ExtraAccountMeta::new_with_pubkey(lp_vault, false, false)?,
+        ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal {
+                    bytes: b"allowlisted_wallet".to_vec(),
+                },
+                // `lp_vault`
+                Seed::AccountKey { index: 5 },
+                // The owner of the `destination` token account
+                Seed::AccountData {
+                    account_index: 2,
+                    data_index: 32,
+                    length: 32,
+                },
+            ],
+            false,
+            false,
+        )?,
+    ])
+}
+
+#[derive(Accounts)]
+pub struct TransferHook<'info> {
+    #[account(token::mint = shares_mint)]
+    pub source: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub shares_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(token::mint = shares_mint)]
+    pub destination: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: The source's owner or delegate, verified by the token program
+    pub owner: AccountInfo<'info>,
+
+    /// CHECK: The shares mint's extra account metas, only checked by the token program
+    #[account(
+        seeds = [b"extra-account-metas", shares_mint.key().as_ref()],
+        bump,
+    )]
+    pub extra_account_meta_list: AccountInfo<'info>,
+
+    #[account(has_one = shares_mint)]
+    pub lp_vault: Box<Account<'info, LpVault>>,
+
+    /// CHECK: Must be an initialized `AllowlistedWallet` while the vault's allowlist is enabled
+    #[account(
+        seeds = [
+            b"allowlisted_wallet",
+            lp_vault.key().as_ref(),
+            destination.owner.as_ref(),
+        ],
+        bump,
+    )]
+    pub allowlisted_wallet: AccountInfo<'info>,
+}
+
+impl<'info> TransferHook<'info> {
+    /// Ensures the hook is invoked by the token program mid-transfer, not called directly
+    fn validate_transferring(&self) -> Result<()> {
+        let source = self.source.to_account_info();
+        let data = source.try_borrow_data()?;
+        let account = StateWithExtensions::<SplTokenAccount>::unpack(&data)?;
+        let extension = account.get_extension::<TransferHookAccount>()?;
+        require!(
+            bool::from(extension.transferring),
+            ErrorCode::NotTransferring
+        );
+        Ok(())
+    }
+
+    pub fn transfer_hook(&self, _amount: u64) -> Result<()> {
+        self.validate_transferring()?;
+
+        if self.lp_vault.allowlist_enabled {
+            require!(
+                self.allowlisted_wallet.owner == &crate::ID
+                    && !self.allowlisted_wallet.data_is_empty(),
+                ErrorCode::WalletNotAllowlisted
+            );
+        }
+
+        Ok(())
+    }
+}
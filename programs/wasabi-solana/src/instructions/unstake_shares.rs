@@ -0,0 +1,101 @@
+use {
+    crate::{
+        events::SharesUnstaked, lp_vault_signer_seeds, reward_pool_signer_seeds, LpVault,
+        RewardPool, RewardStake,
+    },
+    anchor_lang::prelude::*,
+    anchor_spl::{
+        token_2022::Token2022,
+        token_interface::{self, Burn, Mint, MintTo, TokenAccount},
+    },
+};
+
+#[derive(Accounts)]
+pub struct UnstakeShares<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        associated_token::mint = shares_mint,
+        associated_token::authority = owner,
+        associated_token::token_program = shares_token_program,
+    )]
+    pub owner_shares_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        has_one = lp_vault,
+        has_one = shares_mint,
+        has_one = stake_vault,
+    )]
+    pub reward_pool: Box<Account<'info, RewardPool>>,
+
+    /// Mints the escrowed shares
+    #[account(has_one = shares_mint)]
+    pub lp_vault: Box<Account<'info, LpVault>>,
+
+    #[account(mut)]
+    pub stake_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub shares_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        has_one = owner,
+        has_one = reward_pool,
+        seeds = [b"reward_stake", reward_pool.key().as_ref(), owner.key().as_ref()],
+        bump = reward_stake.bump,
+    )]
+    pub reward_stake: Box<Account<'info, RewardStake>>,
+
+    pub shares_token_program: Program<'info, Token2022>,
+}
+
+impl<'info> UnstakeShares<'info> {
+    fn burn_shares_from_stake_vault(&self, amount: u64) -> Result<()> {
+        let cpi_accounts = Burn {
+            mint: self.shares_mint.to_account_info(),
+            from: self.stake_vault.to_account_info(),
+            authority: self.reward_pool.to_account_info(),
+        };
+        let cpi_ctx = CpiContext {
+            program: self.shares_token_program.to_account_info(),
+            accounts: cpi_accounts,
+            remaining_accounts: Vec::new(),
+            signer_seeds: &[reward_pool_signer_seeds!(self.reward_pool)],
+        };
+        token_interface::burn(cpi_ctx, amount)
+    }
+
+    fn mint_shares_to_owner(&self, amount: u64) -> Result<()> {
+        let cpi_accounts = MintTo {
+            mint: self.shares_mint.to_account_info(),
+            to: self.owner_shares_account.to_account_info(),
+            authority: self.lp_vault.to_account_info(),
+        };
+        let cpi_ctx = CpiContext {
+            program: self.shares_token_program.to_account_info(),
+            accounts: cpi_accounts,
+            remaining_accounts: Vec::new(),
+            signer_seeds: &[lp_vault_signer_seeds!(self.lp_vault)],
+        };
+        token_interface::mint_to(cpi_ctx, amount)
+    }
+
+    /// Returns `amount` staked shares to the owner. Their rewards so far stay claimable.
+    pub fn unstake_shares(&mut self, amount: u64) -> Result<()> {
+        self.reward_pool.update(Clock::get()?.unix_timestamp)?;
+        self.reward_stake.unstake(&mut self.reward_pool, amount)?;
+        self.burn_shares_from_stake_vault(amount)?;
+        self.mint_shares_to_owner(amount)?;
+
+        emit!(SharesUnstaked {
+            reward_pool: self.reward_pool.key(),
+            owner: self.owner.key(),
+            amount,
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,103 @@
+use {
+    crate::{error::ErrorCode, lp_vault_signer_seeds, LpVault, Permission},
+    anchor_lang::{prelude::*, solana_program::sysvar},
+    anchor_spl::{metadata::Metadata, token_interface::Mint},
+    mpl_token_metadata::{
+        instructions::UpdateCpiBuilder,
+        types::{
+            CollectionDetailsToggle, CollectionToggle, Data, RuleSetToggle, UpdateArgs, UsesToggle,
+        },
+    },
+};
+
+// Vaults initialized before the LP Vault became the update authority of its shares metadata
+// need their update authority transferred to the LP Vault off-chain first.
+
+#[derive(Accounts)]
+pub struct UpdateSharesMetadata<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// The key that has permission to init the vault
+    pub authority: Signer<'info>,
+
+    #[account(
+        has_one = authority,
+    )]
+    pub permission: Box<Account<'info, Permission>>,
+
+    #[account(
+        has_one = shares_mint,
+    )]
+    pub lp_vault: Box<Account<'info, LpVault>>,
+
+    pub shares_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// CHECK: Validated by the seeds and the metadata program
+    #[account(
+        mut,
+        seeds = [
+            b"metadata",
+            token_metadata_program.key().as_ref(),
+            shares_mint.key().as_ref(),
+        ],
+        bump,
+        seeds::program = token_metadata_program.key(),
+    )]
+    pub shares_metadata: AccountInfo<'info>,
+
+    pub token_metadata_program: Program<'info, Metadata>,
+    pub system_program: Program<'info, System>,
+    /// CHECK: Sysvar instruction check applied
+    #[account(address = sysvar::instructions::ID)]
+    pub sysvar_instructions: AccountInfo<'info>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct UpdateSharesMetadataArgs {
+    name: String,
+    symbol: String,
+    uri: String,
+}
+
+impl<'info> UpdateSharesMetadata<'info> {
+    pub fn validate(ctx: &Context<UpdateSharesMetadata>) -> Result<()> {
+        require!(
+            ctx.accounts.permission.can_init_vault(),
+            ErrorCode::InvalidPermissions
+        );
+        Ok(())
+    }
+
+    pub fn update_shares_metadata(&self, args: UpdateSharesMetadataArgs) -> Result<()> {
+        let update_args = UpdateArgs::AsUpdateAuthorityV2 {
+            new_update_authority: None,
+            data: Some(Data {
+                name: args.name,
+                symbol: args.symbol,
+                uri: args.uri,
+                seller_fee_basis_points: 0,
+                creators: None,
+            }),
+            primary_sale_happened: None,
+            is_mutable: None,
+            collection: CollectionToggle::None,
+            collection_details: CollectionDetailsToggle::None,
+            uses: UsesToggle::None,
+            rule_set: RuleSetToggle::None,
+            token_standard: None,
+            authorization_data: None,
+        };
+
+        UpdateCpiBuilder::new(&self.token_metadata_program.to_account_info())
+            .authority(&self.lp_vault.to_account_info())
+            .mint(&self.shares_mint.to_account_info())
+            .metadata(&self.shares_metadata.to_account_info())
+            .payer(&self.payer.to_account_info())
+            .system_program(&self.system_program.to_account_info())
+            .sysvar_instructions(&self.sysvar_instructions.to_account_info())
+            .update_args(update_args)
+            .invoke_signed(&[lp_vault_signer_seeds!(self.lp_vault)])?;
+
+        Ok(())
+    }
+}
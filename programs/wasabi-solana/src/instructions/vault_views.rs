@@ -0,0 +1,175 @@
+use {
+    crate::{error::ErrorCode, Depositor, LpVault, SharePriceHistory, FEE_BPS_DENOMINATOR},
+    anchor_lang::prelude::*,
+    anchor_spl::{
+        token_2022::Token2022,
+        token_interface::{Mint, TokenAccount},
+    },
+};
+
+// Read-only views mirroring the share math of `deposit`, `withdraw` and `redeem`, net of the
+// exit fee the withdrawing wallet would pay. Results are returned via `set_return_data` so they
+// can be read through CPI or transaction simulation.
+
+#[derive(Accounts)]
+pub struct VaultView<'info> {
+    #[account(has_one = shares_mint)]
+    pub lp_vault: Box<Account<'info, LpVault>>,
+
+    pub shares_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The withdrawing wallet's record, required by the withdrawal views while the vault has a
+    /// cooldown
+    #[account(has_one = lp_vault)]
+    pub depositor: Option<Box<Account<'info, Depositor>>>,
+}
+
+impl VaultView<'_> {
+    pub fn preview_deposit(&self, assets: u64) -> Result<u64> {
+        let (lp_vault, shares_supply) = vested(&self.lp_vault, self.shares_mint.supply)?;
+        lp_vault.preview_deposit(assets, shares_supply)
+    }
+
+    pub fn preview_mint(&self, shares: u64) -> Result<u64> {
+        let (lp_vault, shares_supply) = vested(&self.lp_vault, self.shares_mint.supply)?;
+        lp_vault.preview_mint(shares, shares_supply)
+    }
+
+    /// The shares burned for the wallet to receive `assets` after the exit fee
+    pub fn preview_withdraw(&self, assets: u64) -> Result<u64> {
+        let fee_bps = exit_fee_rate(
+            &self.lp_vault,
+            self.depositor.as_deref().map(|depositor| &**depositor),
+        )?;
+        let (lp_vault, shares_supply) = vested(&self.lp_vault, self.shares_mint.supply)?;
+        lp_vault.preview_withdraw(gross_of_exit_fee(assets, fee_bps)?, shares_supply)
+    }
+
+    /// The assets the wallet receives for `shares` after the exit fee
+    pub fn preview_redeem(&self, shares: u64) -> Result<u64> {
+        let fee_bps = exit_fee_rate(
+            &self.lp_vault,
+            self.depositor.as_deref().map(|depositor| &**depositor),
+        )?;
+        let (lp_vault, shares_supply) = vested(&self.lp_vault, self.shares_mint.supply)?;
+        let assets = lp_vault.preview_redeem(shares, shares_supply)?;
+        Ok(assets - LpVault::fee_on(assets, fee_bps)?)
+    }
+
+    pub fn convert_to_shares(&self, assets: u64) -> Result<u64> {
+        let (lp_vault, shares_supply) = vested(&self.lp_vault, self.shares_mint.supply)?;
+        lp_vault.convert_to_shares(assets, shares_supply)
+    }
+
+    pub fn convert_to_assets(&self, shares: u64) -> Result<u64> {
+        let (lp_vault, shares_supply) = vested(&self.lp_vault, self.shares_mint.supply)?;
+        lp_vault.convert_to_assets(shares, shares_supply)
+    }
+}
+
+/// The vault and share supply as the next deposit or withdrawal will price them, with assets
+/// vested and the accrued fees diluting the supply up to now
+fn vested(lp_vault: &LpVault, shares_supply: u64) -> Result<(LpVault, u64)> {
+    let now = Clock::get()?.unix_timestamp;
+    let mut lp_vault = lp_vault.clone();
+    lp_vault.vest(now)?;
+    let shares_supply = shares_supply
+        .checked_add(lp_vault.pending_fee_shares(shares_supply, now)?)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+    Ok((lp_vault, shares_supply))
+}
+
+/// The exit fee rate `charge_exit_fee` would apply to the wallet withdrawing now
+fn exit_fee_rate(lp_vault: &LpVault, depositor: Option<&Depositor>) -> Result<u16> {
+    if lp_vault.lp_cooldown == 0 {
+        return Ok(0);
+    }
+    let depositor = depositor.ok_or(ErrorCode::MissingDepositor)?;
+    lp_vault.exit_fee_rate(
+        depositor.last_deposit_timestamp,
+        Clock::get()?.unix_timestamp,
+    )
+}
+
+/// The smallest withdrawal that leaves at least `assets` after an exit fee of `fee_bps`
+fn gross_of_exit_fee(assets: u64, fee_bps: u16) -> Result<u64> {
+    let mut gross: u64 = (assets as u128)
+        .checked_mul(FEE_BPS_DENOMINATOR as u128)
+        .ok_or(ErrorCode::ArithmeticOverflow)?
+        .div_ceil((FEE_BPS_DENOMINATOR - fee_bps) as u128)
+        .try_into()
+        .map_err(|_| ErrorCode::U64Overflow)?;
+    // The fee rounds up, so the quotient may fall one short
+    if gross - LpVault::fee_on(gross, fee_bps)? < assets {
+        gross = gross.checked_add(1).ok_or(ErrorCode::ArithmeticOverflow)?;
+    }
+    Ok(gross)
+}
+
+#[derive(Accounts)]
+pub struct MaxWithdrawView<'info> {
+    /// The key of the user that owns the shares
+    /// CHECK: No checks, only used to derive the shares account
+    pub owner: AccountInfo<'info>,
+
+    #[account(
+        associated_token::mint = shares_mint,
+        associated_token::authority = owner,
+        associated_token::token_program = shares_token_program,
+    )]
+    pub owner_shares_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        has_one = vault,
+        has_one = shares_mint,
+    )]
+    pub lp_vault: Box<Account<'info, LpVault>>,
+
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub shares_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The owner's record, required while the vault has a cooldown
+    #[account(
+        seeds = [b"depositor", lp_vault.key().as_ref(), owner.key().as_ref()],
+        bump = depositor.bump,
+    )]
+    pub depositor: Option<Box<Account<'info, Depositor>>>,
+
+    pub shares_token_program: Program<'info, Token2022>,
+}
+
+impl MaxWithdrawView<'_> {
+    /// The most assets `owner` can receive after the exit fee, limited by the unborrowed tokens in
+    /// the vault
+    pub fn max_withdraw(&self) -> Result<u64> {
+        let (lp_vault, shares_supply) = vested(&self.lp_vault, self.shares_mint.supply)?;
+        let owner_assets =
+            lp_vault.convert_to_assets(self.owner_shares_account.amount, shares_supply)?;
+        let fee_bps = exit_fee_rate(
+            &self.lp_vault,
+            self.depositor.as_deref().map(|depositor| &**depositor),
+        )?;
+        let owner_assets = owner_assets - LpVault::fee_on(owner_assets, fee_bps)?;
+
+        Ok(owner_assets.min(self.vault.amount))
+    }
+}
+
+#[derive(Accounts)]
+pub struct SharePriceView<'info> {
+    #[account(
+        seeds = [b"share_price_history", share_price_history.lp_vault.as_ref()],
+        bump = share_price_history.bump,
+    )]
+    pub share_price_history: Box<Account<'info, SharePriceHistory>>,
+}
+
+impl SharePriceView<'_> {
+    /// Time weighted average share price over the last `window` seconds, quoted as the assets per
+    /// `SHARE_PRICE_SCALE` shares
+    pub fn share_price_twap(&self, window: u32) -> Result<u64> {
+        self.share_price_history
+            .twap(window, Clock::get()?.unix_timestamp)
+    }
+}
@@ -6,46 +6,43 @@ use {
 
 pub trait WithdrawTrait {
     fn withdraw(&mut self, amount: u64) -> Result<()>;
+    fn withdraw_with_max_shares(&mut self, amount: u64, max_shares: u64) -> Result<()>;
 }
 
 impl WithdrawTrait for DepositOrWithdraw<'_> {
     fn withdraw(&mut self, amount: u64) -> Result<()> {
-        require_gt!(amount, 0, ErrorCode::ZeroAmount);
-        let amount_u128 = amount as u128;
-        let total_assets_u128 = self.lp_vault.total_assets as u128;
-        let shares_supply_u128 = self.shares_mint.supply as u128;
-
-        // Calculate proportional rounding protection
-        // Uses 0.1% (1/1000) of withdrawal amount as protection, minimum of 1
-        let rounding_protection = std::cmp::max(1, amount_u128.checked_div(1000).unwrap_or(1));
+        self.withdraw_with_max_shares(amount, u64::MAX)
+    }
 
-        let shares_burn_amount = amount_u128
-            .checked_mul(shares_supply_u128)
-            .ok_or(ErrorCode::ArithmeticOverflow)?
-            .checked_add(rounding_protection)
-            .ok_or(ErrorCode::ArithmeticOverflow)?
-            .checked_div(total_assets_u128)
-            .ok_or(ErrorCode::ZeroDivision)?;
+    /// Withdraws `amount`, reverting if more than `max_shares` would be burned
+    fn withdraw_with_max_shares(&mut self, amount: u64, max_shares: u64) -> Result<()> {
+        require_gt!(amount, 0, ErrorCode::ZeroAmount);
+        require!(
+            self.lp_vault.can_withdraw(),
+            ErrorCode::VaultWithdrawalsPaused
+        );
+        self.lp_vault.vest(Clock::get()?.unix_timestamp)?;
+        self.crystallize_fees()?;
+
+        let shares_burn_u64 = self
+            .lp_vault
+            .preview_withdraw(amount, self.shares_mint.supply)?;
 
-        let shares_burn_u64 =
-            u64::try_from(shares_burn_amount).map_err(|_| ErrorCode::U64Overflow)?;
+        require_gte!(max_shares, shares_burn_u64, ErrorCode::SlippageExceeded);
 
         self.burn_shares_from_user(shares_burn_u64)?;
 
-        self.lp_vault.total_assets = self
-            .lp_vault
-            .total_assets
-            .checked_sub(amount)
-            .ok_or(ErrorCode::ArithmeticUnderflow)?;
-
-        self.transfer_token_from_vault_to_owner(amount)?;
+        let exit_fee = self.process_withdrawal(amount)?;
+        self.reserve_before_withdrawal(amount - exit_fee)?;
+        self.transfer_token_from_vault_to_owner(amount - exit_fee)?;
+        self.checkpoint_share_price()?;
 
         emit!(Withdraw {
             vault: self.lp_vault.shares_mint,
             sender: self.owner.key(),
             owner: self.owner_asset_account.owner.key(),
             receiver: self.owner_asset_account.key(),
-            assets: amount,
+            assets: amount - exit_fee,
             shares: shares_burn_u64,
         });
 
@@ -0,0 +1,180 @@
+use {
+    super::{
+        checkpoint_share_price, crystallize_fees_if_charged, process_withdrawal,
+        reserve_before_withdrawal,
+    },
+    crate::{error::ErrorCode, events::Withdraw, lp_vault_signer_seeds, Depositor, LpVault},
+    anchor_lang::prelude::*,
+    anchor_spl::{
+        token_2022::Token2022,
+        token_interface::{self, Burn, Mint, TokenAccount, TokenInterface, TransferChecked},
+    },
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct WithdrawTo<'info> {
+    /// The key of the user that owns the shares
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        associated_token::mint = shares_mint,
+        associated_token::authority = owner,
+        associated_token::token_program = shares_token_program,
+    )]
+    /// The Owner's token account that stores share tokens
+    pub owner_shares_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = asset_mint,
+        token::token_program = asset_token_program,
+    )]
+    /// The token account that receives the assets, owned by any wallet
+    pub receiver_asset_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        has_one = vault,
+        has_one = shares_mint,
+    )]
+    pub lp_vault: Box<Account<'info, LpVault>>,
+
+    #[account(mut)]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub asset_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut)]
+    pub shares_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// CHECK: The LP Vault's withdrawal queue, required once the vault has one.
+    /// Withdrawals are then only paid from what it leaves.
+    #[account(
+        mut,
+        seeds = [b"withdrawal_queue", lp_vault.key().as_ref()],
+        bump,
+    )]
+    pub withdrawal_queue: Option<UncheckedAccount<'info>>,
+
+    /// Required once the withdrawal queue exists
+    #[account(mut)]
+    pub queue_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// Tracks the owner's net deposits and last deposit time, required while the vault has a
+    /// cooldown
+    #[account(
+        mut,
+        seeds = [b"depositor", lp_vault.key().as_ref(), owner.key().as_ref()],
+        bump = depositor.bump,
+    )]
+    pub depositor: Option<Box<Account<'info, Depositor>>>,
+
+    /// Receives the vault's accrued fees before the share price is used, required while the vault
+    /// charges fees
+    #[account(
+        mut,
+        address = lp_vault.fee_shares_account,
+    )]
+    pub fee_shares_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// CHECK: The LP Vault's share price history, required once the vault has one. The share
+    /// price after the operation is then recorded in it.
+    #[account(
+        mut,
+        seeds = [b"share_price_history", lp_vault.key().as_ref()],
+        bump,
+    )]
+    pub share_price_history: Option<UncheckedAccount<'info>>,
+
+    pub asset_token_program: Interface<'info, TokenInterface>,
+    pub shares_token_program: Program<'info, Token2022>,
+}
+
+impl<'info> WithdrawTo<'info> {
+    fn burn_shares_from_owner(&self, amount: u64) -> Result<()> {
+        let cpi_accounts = Burn {
+            mint: self.shares_mint.to_account_info(),
+            from: self.owner_shares_account.to_account_info(),
+            authority: self.owner.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(self.shares_token_program.to_account_info(), cpi_accounts);
+        token_interface::burn(cpi_ctx, amount)
+    }
+
+    fn transfer_token_from_vault_to_receiver(&self, amount: u64) -> Result<()> {
+        let cpi_accounts = TransferChecked {
+            from: self.vault.to_account_info(),
+            mint: self.asset_mint.to_account_info(),
+            to: self.receiver_asset_account.to_account_info(),
+            authority: self.lp_vault.to_account_info(),
+        };
+        let cpi_ctx = CpiContext {
+            program: self.asset_token_program.to_account_info(),
+            accounts: cpi_accounts,
+            remaining_accounts: Vec::new(),
+            signer_seeds: &[lp_vault_signer_seeds!(self.lp_vault)],
+        };
+        token_interface::transfer_checked(cpi_ctx, amount, self.asset_mint.decimals)
+    }
+
+    /// Burns the owner's shares for `amount` and sends the assets to the receiver, reverting if
+    /// more than `max_shares` would be burned
+    pub fn withdraw_to(&mut self, amount: u64, max_shares: u64) -> Result<()> {
+        require_gt!(amount, 0, ErrorCode::ZeroAmount);
+        require!(
+            self.lp_vault.can_withdraw(),
+            ErrorCode::VaultWithdrawalsPaused
+        );
+        self.lp_vault.vest(Clock::get()?.unix_timestamp)?;
+        crystallize_fees_if_charged(
+            &mut self.lp_vault,
+            &mut self.shares_mint,
+            self.fee_shares_account.as_deref(),
+            &self.shares_token_program,
+        )?;
+
+        let shares_burn_u64 = self
+            .lp_vault
+            .preview_withdraw(amount, self.shares_mint.supply)?;
+
+        require_gte!(max_shares, shares_burn_u64, ErrorCode::SlippageExceeded);
+
+        self.burn_shares_from_owner(shares_burn_u64)?;
+
+        let exit_fee = process_withdrawal(
+            &mut self.lp_vault,
+            self.depositor
+                .as_deref_mut()
+                .map(|depositor| &mut **depositor),
+            amount,
+        )?;
+        reserve_before_withdrawal(
+            self.withdrawal_queue.as_deref(),
+            self.queue_vault.as_deref_mut(),
+            &mut self.lp_vault,
+            &mut self.vault,
+            &self.asset_mint,
+            &self.asset_token_program,
+            amount - exit_fee,
+        )?;
+        self.transfer_token_from_vault_to_receiver(amount - exit_fee)?;
+        checkpoint_share_price(
+            self.share_price_history.as_deref(),
+            &self.lp_vault,
+            Some(&mut self.shares_mint),
+        )?;
+
+        emit!(Withdraw {
+            vault: self.lp_vault.shares_mint,
+            sender: self.owner.key(),
+            owner: self.owner.key(),
+            receiver: self.receiver_asset_account.key(),
+            assets: amount - exit_fee,
+            shares: shares_burn_u64,
+        });
+
+        Ok(())
+    }
+}
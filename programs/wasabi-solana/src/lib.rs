@@ -12,6 +12,11 @@ pub use constants::*;
 pub use instructions::*;
 pub use state::*;
 
+use {
+    spl_discriminator::SplDiscriminate,
+    spl_transfer_hook_interface::instruction::ExecuteInstruction,
+};
+
 declare_id!("spicyTHtbmarmUxwFSHYpA8G4uP2nRNq38RReMpoZ9c");
 
 #[program]
@@ -48,6 +53,26 @@ pub mod wasabi_solana {
         ctx.accounts.set_lp_state(allow_lp)
     }
 
+    pub fn set_lp_vault_status(ctx: Context<SetLpVaultStatus>, statuses: u16) -> Result<()> {
+        ctx.accounts.set_lp_vault_status(statuses)
+    }
+
+    pub fn set_pool_status(ctx: Context<SetPoolStatus>, statuses: u16) -> Result<()> {
+        ctx.accounts.set_pool_status(statuses)
+    }
+
+    pub fn set_mint_policy(ctx: Context<SetMintPolicy>, rejected_features: u16) -> Result<()> {
+        ctx.accounts.set_mint_policy(rejected_features, &ctx.bumps)
+    }
+
+    pub fn allow_mint(ctx: Context<AllowMint>) -> Result<()> {
+        ctx.accounts.allow_mint(&ctx.bumps)
+    }
+
+    pub fn remove_allowed_mint(ctx: Context<RemoveAllowedMint>) -> Result<()> {
+        ctx.accounts.remove_allowed_mint()
+    }
+
     pub fn set_fee_wallet(ctx: Context<SetFeeWallet>, fee_wallet: Pubkey) -> Result<()> {
         ctx.accounts.set_fee_wallet(&fee_wallet)
     }
@@ -59,6 +84,18 @@ pub mod wasabi_solana {
         ctx.accounts.set_liquidation_wallet(&liquidation_wallet)
     }
 
+    pub fn set_max_exit_order_execution_fee(
+        ctx: Context<SetMaxExitOrderExecutionFee>,
+        max_execution_fee: u64,
+    ) -> Result<()> {
+        ctx.accounts
+            .set_max_exit_order_execution_fee(max_execution_fee)
+    }
+
+    pub fn migrate_global_settings(ctx: Context<MigrateGlobalSettings>) -> Result<()> {
+        ctx.accounts.migrate_global_settings()
+    }
+
     pub fn set_max_apy(ctx: Context<SetMaxApy>, max_apy: u64) -> Result<()> {
         ctx.accounts.set_max_apy(max_apy)
     }
@@ -82,11 +119,85 @@ pub mod wasabi_solana {
         ctx.accounts.remove_permission()
     }
 
+    #[cfg(feature = "metaplex")]
     #[access_control(InitLpVault::validate(&ctx))]
     pub fn init_lp_vault(ctx: Context<InitLpVault>, args: InitLpVaultArgs) -> Result<()> {
         ctx.accounts.init_lp_vault(&args, &ctx.bumps)
     }
 
+    #[access_control(InitLpVaultWithTokenMetadata::validate(&ctx))]
+    pub fn init_lp_vault_with_token_metadata(
+        ctx: Context<InitLpVaultWithTokenMetadata>,
+        args: InitLpVaultArgs,
+    ) -> Result<()> {
+        ctx.accounts
+            .init_lp_vault_with_token_metadata(&args, &ctx.bumps)
+    }
+
+    #[access_control(InitAllowlistedLpVault::validate(&ctx))]
+    pub fn init_allowlisted_lp_vault(
+        ctx: Context<InitAllowlistedLpVault>,
+        args: InitLpVaultArgs,
+    ) -> Result<()> {
+        ctx.accounts.init_allowlisted_lp_vault(&args, &ctx.bumps)
+    }
+
+    #[access_control(SetVaultAllowlist::validate(&ctx))]
+    pub fn set_vault_allowlist(
+        ctx: Context<SetVaultAllowlist>,
+        allowlist_enabled: bool,
+    ) -> Result<()> {
+        ctx.accounts.set_vault_allowlist(allowlist_enabled)
+    }
+
+    #[access_control(AllowlistWallet::validate(&ctx))]
+    pub fn allowlist_wallet(ctx: Context<AllowlistWallet>) -> Result<()> {
+        ctx.accounts.allowlist_wallet(&ctx.bumps)
+    }
+
+    #[access_control(RemoveAllowlistedWallet::validate(&ctx))]
+    pub fn remove_allowlisted_wallet(ctx: Context<RemoveAllowlistedWallet>) -> Result<()> {
+        ctx.accounts.remove_allowlisted_wallet()
+    }
+
+    #[instruction(discriminator = ExecuteInstruction::SPL_DISCRIMINATOR_SLICE)]
+    pub fn transfer_hook(ctx: Context<TransferHook>, amount: u64) -> Result<()> {
+        ctx.accounts.transfer_hook(amount)
+    }
+
+    #[access_control(DecommissionLpVault::validate(&ctx))]
+    pub fn decommission_lp_vault(ctx: Context<DecommissionLpVault>) -> Result<()> {
+        ctx.accounts.decommission_lp_vault()
+    }
+
+    #[access_control(CloseLpVault::validate(&ctx))]
+    pub fn close_lp_vault(ctx: Context<CloseLpVault>) -> Result<()> {
+        ctx.accounts.close_lp_vault()
+    }
+
+    #[access_control(InitSuccessorLpVault::validate(&ctx, version))]
+    pub fn init_successor_lp_vault(
+        ctx: Context<InitSuccessorLpVault>,
+        version: u8,
+        args: InitLpVaultArgs,
+    ) -> Result<()> {
+        ctx.accounts.init_successor_lp_vault(&args, &ctx.bumps)
+    }
+
+    #[access_control(InitAllowlistedSuccessorLpVault::validate(&ctx, version))]
+    pub fn init_allowlisted_successor_lp_vault(
+        ctx: Context<InitAllowlistedSuccessorLpVault>,
+        version: u8,
+        args: InitLpVaultArgs,
+    ) -> Result<()> {
+        ctx.accounts
+            .init_allowlisted_successor_lp_vault(&args, &ctx.bumps)
+    }
+
+    pub fn migrate_lp_vault(ctx: Context<MigrateLpVault>) -> Result<()> {
+        ctx.accounts.migrate_lp_vault()
+    }
+
     #[access_control(UpdateVaultMaxBorrow::validate(&ctx))]
     pub fn update_lp_vault_max_borrow(
         ctx: Context<UpdateVaultMaxBorrow>,
@@ -95,6 +206,60 @@ pub mod wasabi_solana {
         ctx.accounts.update_lp_vault_max_borrow(max_borrow)
     }
 
+    #[access_control(SetVaultDepositCaps::validate(&ctx))]
+    pub fn set_vault_deposit_caps(
+        ctx: Context<SetVaultDepositCaps>,
+        deposit_cap: u64,
+        wallet_deposit_cap: u64,
+    ) -> Result<()> {
+        ctx.accounts
+            .set_vault_deposit_caps(deposit_cap, wallet_deposit_cap)
+    }
+
+    #[access_control(SetVaultMaxUtilization::validate(&ctx, max_utilization_bps))]
+    pub fn set_vault_max_utilization(
+        ctx: Context<SetVaultMaxUtilization>,
+        max_utilization_bps: u16,
+    ) -> Result<()> {
+        ctx.accounts.set_vault_max_utilization(max_utilization_bps)
+    }
+
+    #[access_control(SetVaultFees::validate(&ctx, performance_fee_bps, management_fee_bps))]
+    pub fn set_vault_fees(
+        ctx: Context<SetVaultFees>,
+        performance_fee_bps: u16,
+        management_fee_bps: u16,
+    ) -> Result<()> {
+        ctx.accounts
+            .set_vault_fees(performance_fee_bps, management_fee_bps)
+    }
+
+    #[access_control(SetVaultCooldown::validate(&ctx, lp_cooldown, exit_fee_bps))]
+    pub fn set_vault_cooldown(
+        ctx: Context<SetVaultCooldown>,
+        lp_cooldown: i64,
+        exit_fee_bps: u16,
+    ) -> Result<()> {
+        ctx.accounts.set_vault_cooldown(lp_cooldown, exit_fee_bps)
+    }
+
+    #[access_control(SetVaultVestingPeriod::validate(&ctx, vesting_period))]
+    pub fn set_vault_vesting_period(
+        ctx: Context<SetVaultVestingPeriod>,
+        vesting_period: i64,
+    ) -> Result<()> {
+        ctx.accounts.set_vault_vesting_period(vesting_period)
+    }
+
+    #[cfg(feature = "metaplex")]
+    #[access_control(UpdateSharesMetadata::validate(&ctx))]
+    pub fn update_shares_metadata(
+        ctx: Context<UpdateSharesMetadata>,
+        args: UpdateSharesMetadataArgs,
+    ) -> Result<()> {
+        ctx.accounts.update_shares_metadata(args)
+    }
+
     #[access_control(InitLongPool::validate(&ctx))]
     pub fn init_long_pool(ctx: Context<InitLongPool>) -> Result<()> {
         ctx.accounts.init_long_pool(&ctx.bumps)
@@ -105,6 +270,10 @@ pub mod wasabi_solana {
         ctx.accounts.init_short_pool(&ctx.bumps)
     }
 
+    pub fn migrate_base_pool(ctx: Context<MigrateBasePool>) -> Result<()> {
+        ctx.accounts.migrate_base_pool()
+    }
+
     pub fn init_or_update_take_profit_order(
         ctx: Context<InitOrUpdateTakeProfitOrder>,
         maker_amount: u64,
@@ -114,6 +283,28 @@ pub mod wasabi_solana {
             .init_or_update_take_profit_order(maker_amount, taker_amount)
     }
 
+    pub fn init_or_update_take_profit_order_with_fee(
+        ctx: Context<InitOrUpdateTakeProfitOrder>,
+        maker_amount: u64,
+        taker_amount: u64,
+        execution_fee: u64,
+    ) -> Result<()> {
+        ctx.accounts.init_or_update_take_profit_order_with_fee(
+            maker_amount,
+            taker_amount,
+            execution_fee,
+        )
+    }
+
+    pub fn init_or_update_take_profit_order_at_price(
+        ctx: Context<InitOrUpdateTakeProfitOrder>,
+        price: ExitOrderPrice,
+        execution_fee: u64,
+    ) -> Result<()> {
+        ctx.accounts
+            .init_or_update_take_profit_order_at_price(price, execution_fee)
+    }
+
     pub fn close_take_profit_order(ctx: Context<CloseTakeProfitOrder>) -> Result<()> {
         ctx.accounts.close_take_profit_order()
     }
@@ -127,10 +318,63 @@ pub mod wasabi_solana {
             .init_or_update_stop_loss_order(maker_amount, taker_amount)
     }
 
+    pub fn init_or_update_stop_loss_order_with_fee(
+        ctx: Context<InitOrUpdateStopLossOrder>,
+        maker_amount: u64,
+        taker_amount: u64,
+        execution_fee: u64,
+    ) -> Result<()> {
+        ctx.accounts.init_or_update_stop_loss_order_with_fee(
+            maker_amount,
+            taker_amount,
+            execution_fee,
+        )
+    }
+
+    pub fn init_or_update_stop_loss_order_at_price(
+        ctx: Context<InitOrUpdateStopLossOrder>,
+        price: ExitOrderPrice,
+        execution_fee: u64,
+    ) -> Result<()> {
+        ctx.accounts
+            .init_or_update_stop_loss_order_at_price(price, execution_fee)
+    }
+
     pub fn close_stop_loss_order(ctx: Context<CloseStopLossOrder>) -> Result<()> {
         ctx.accounts.close_stop_loss_order()
     }
 
+    pub fn batch_update_exit_orders<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BatchUpdateExitOrders<'info>>,
+        ops: Vec<ExitOrderOp>,
+    ) -> Result<()> {
+        ctx.accounts
+            .batch_update_exit_orders(ctx.remaining_accounts, &ops)
+    }
+
+    pub fn migrate_exit_order(
+        ctx: Context<MigrateExitOrder>,
+        order_type: ExitOrderType,
+    ) -> Result<()> {
+        ctx.accounts.migrate_exit_order(order_type)
+    }
+
+    #[access_control(InitLimitOpenOrder::validate(&ctx, &args))]
+    pub fn init_limit_open_order(
+        ctx: Context<InitLimitOpenOrder>,
+        args: InitLimitOpenOrderArgs,
+    ) -> Result<()> {
+        ctx.accounts.init_limit_open_order(&args, &ctx.bumps)
+    }
+
+    pub fn cancel_limit_open_order(ctx: Context<CancelLimitOpenOrder>) -> Result<()> {
+        ctx.accounts.cancel_limit_open_order()
+    }
+
+    pub fn init_depositor(ctx: Context<InitDepositor>) -> Result<()> {
+        ctx.accounts.init_depositor(&ctx.bumps)
+    }
+
     #[access_control(DepositOrWithdraw::validate(&ctx, amount))]
     pub fn deposit(ctx: Context<DepositOrWithdraw>, amount: u64) -> Result<()> {
         ctx.accounts.deposit(amount)
@@ -144,11 +388,158 @@ pub mod wasabi_solana {
         ctx.accounts.redeem(shares_amount)
     }
 
+    #[access_control(DepositOrWithdraw::validate(&ctx, amount))]
+    pub fn deposit_with_min_shares(
+        ctx: Context<DepositOrWithdraw>,
+        amount: u64,
+        min_shares: u64,
+    ) -> Result<()> {
+        ctx.accounts.deposit_with_min_shares(amount, min_shares)
+    }
+
+    pub fn withdraw_with_max_shares(
+        ctx: Context<DepositOrWithdraw>,
+        amount: u64,
+        max_shares: u64,
+    ) -> Result<()> {
+        ctx.accounts.withdraw_with_max_shares(amount, max_shares)
+    }
+
+    #[access_control(DepositFor::validate(&ctx, amount))]
+    pub fn deposit_for(ctx: Context<DepositFor>, amount: u64, min_shares: u64) -> Result<()> {
+        ctx.accounts.deposit_for(amount, min_shares, &ctx.bumps)
+    }
+
+    pub fn withdraw_to(ctx: Context<WithdrawTo>, amount: u64, max_shares: u64) -> Result<()> {
+        ctx.accounts.withdraw_to(amount, max_shares)
+    }
+
+    #[access_control(MigrateLpShares::validate(&ctx, shares_amount))]
+    pub fn migrate_lp_shares(
+        ctx: Context<MigrateLpShares>,
+        shares_amount: u64,
+        min_shares: u64,
+    ) -> Result<()> {
+        ctx.accounts.migrate_lp_shares(shares_amount, min_shares)
+    }
+
+    #[access_control(DepositOrWithdrawNative::validate_deposit(&ctx, amount))]
+    pub fn deposit_native(
+        ctx: Context<DepositOrWithdrawNative>,
+        amount: u64,
+        min_shares: u64,
+    ) -> Result<()> {
+        ctx.accounts.deposit_native(amount, min_shares)
+    }
+
+    #[access_control(DepositOrWithdrawNative::validate(&ctx))]
+    pub fn withdraw_native(
+        ctx: Context<DepositOrWithdrawNative>,
+        amount: u64,
+        max_shares: u64,
+    ) -> Result<()> {
+        ctx.accounts.withdraw_native(amount, max_shares)
+    }
+
+    pub fn redeem_with_min_assets(
+        ctx: Context<DepositOrWithdraw>,
+        shares_amount: u64,
+        min_assets: u64,
+    ) -> Result<()> {
+        ctx.accounts
+            .redeem_with_min_assets(shares_amount, min_assets)
+    }
+
+    #[access_control(InitWithdrawalQueue::validate(&ctx))]
+    pub fn init_withdrawal_queue(ctx: Context<InitWithdrawalQueue>) -> Result<()> {
+        ctx.accounts.init_withdrawal_queue(&ctx.bumps)
+    }
+
+    #[access_control(RequestWithdrawal::validate(&ctx, shares_amount))]
+    pub fn request_withdrawal(ctx: Context<RequestWithdrawal>, shares_amount: u64) -> Result<()> {
+        ctx.accounts.request_withdrawal(shares_amount, &ctx.bumps)
+    }
+
+    pub fn fill_withdrawal_queue(ctx: Context<FillWithdrawalQueue>) -> Result<()> {
+        ctx.accounts.fill_withdrawal_queue()
+    }
+
+    pub fn claim_withdrawal(ctx: Context<ClaimWithdrawal>) -> Result<()> {
+        ctx.accounts.claim_withdrawal()
+    }
+
+    pub fn crystallize_vault_fees(ctx: Context<CrystallizeVaultFees>) -> Result<()> {
+        ctx.accounts.crystallize_vault_fees()
+    }
+
     #[access_control(Donate::validate(&ctx, amount))]
     pub fn donate(ctx: Context<Donate>, amount: u64) -> Result<()> {
         ctx.accounts.donate(amount)
     }
 
+    pub fn preview_deposit(ctx: Context<VaultView>, assets: u64) -> Result<u64> {
+        ctx.accounts.preview_deposit(assets)
+    }
+
+    pub fn preview_mint(ctx: Context<VaultView>, shares: u64) -> Result<u64> {
+        ctx.accounts.preview_mint(shares)
+    }
+
+    pub fn preview_withdraw(ctx: Context<VaultView>, assets: u64) -> Result<u64> {
+        ctx.accounts.preview_withdraw(assets)
+    }
+
+    pub fn preview_redeem(ctx: Context<VaultView>, shares: u64) -> Result<u64> {
+        ctx.accounts.preview_redeem(shares)
+    }
+
+    pub fn convert_to_shares(ctx: Context<VaultView>, assets: u64) -> Result<u64> {
+        ctx.accounts.convert_to_shares(assets)
+    }
+
+    pub fn convert_to_assets(ctx: Context<VaultView>, shares: u64) -> Result<u64> {
+        ctx.accounts.convert_to_assets(shares)
+    }
+
+    pub fn max_withdraw(ctx: Context<MaxWithdrawView>) -> Result<u64> {
+        ctx.accounts.max_withdraw()
+    }
+
+    #[access_control(InitSharePriceHistory::validate(&ctx))]
+    pub fn init_share_price_history(ctx: Context<InitSharePriceHistory>) -> Result<()> {
+        ctx.accounts.init_share_price_history(&ctx.bumps)
+    }
+
+    pub fn share_price_twap(ctx: Context<SharePriceView>, window: u32) -> Result<u64> {
+        ctx.accounts.share_price_twap(window)
+    }
+
+    #[access_control(InitRewardPool::validate(&ctx))]
+    pub fn init_reward_pool(ctx: Context<InitRewardPool>) -> Result<()> {
+        ctx.accounts.init_reward_pool(&ctx.bumps)
+    }
+
+    #[access_control(TopUpRewardPool::validate(&ctx))]
+    pub fn top_up_reward_pool(
+        ctx: Context<TopUpRewardPool>,
+        amount: u64,
+        emission_rate: u64,
+    ) -> Result<()> {
+        ctx.accounts.top_up_reward_pool(amount, emission_rate)
+    }
+
+    pub fn stake_shares(ctx: Context<StakeShares>, amount: u64) -> Result<()> {
+        ctx.accounts.stake_shares(amount, &ctx.bumps)
+    }
+
+    pub fn unstake_shares(ctx: Context<UnstakeShares>, amount: u64) -> Result<()> {
+        ctx.accounts.unstake_shares(amount)
+    }
+
+    pub fn claim_rewards(ctx: Context<ClaimRewards>) -> Result<()> {
+        ctx.accounts.claim_rewards()
+    }
+
     #[access_control(OpenLongPositionSetup::validate(&ctx, expiration))]
     pub fn open_long_position_setup(
         ctx: Context<OpenLongPositionSetup>,
@@ -169,6 +560,26 @@ pub mod wasabi_solana {
         )
     }
 
+    #[access_control(OpenLongPositionSetup::validate(&ctx, expiration))]
+    pub fn open_long_position_setup_native(
+        ctx: Context<OpenLongPositionSetup>,
+        nonce: u16,
+        min_target_amount: u64,
+        down_payment: u64,
+        principal: u64,
+        fee: u64,
+        expiration: i64,
+    ) -> Result<()> {
+        ctx.accounts.open_long_position_setup_native(
+            nonce,
+            min_target_amount,
+            down_payment,
+            principal,
+            fee,
+            expiration,
+        )
+    }
+
     pub fn open_long_position_cleanup(ctx: Context<OpenLongPositionCleanup>) -> Result<()> {
         ctx.accounts.open_long_position_cleanup()
     }
@@ -193,6 +604,12 @@ pub mod wasabi_solana {
         ctx.accounts.close_long_position_cleanup()
     }
 
+    pub fn close_long_position_cleanup_native(
+        ctx: Context<CloseLongPositionCleanupNative>,
+    ) -> Result<()> {
+        ctx.accounts.close_long_position_cleanup_native()
+    }
+
     #[access_control(OpenShortPositionSetup::validate(&ctx, expiration))]
     pub fn open_short_position_setup(
         ctx: Context<OpenShortPositionSetup>,
@@ -217,6 +634,21 @@ pub mod wasabi_solana {
         ctx.accounts.open_short_position_cleanup()
     }
 
+    #[access_control(ExecuteLimitOpenOrderSetup::validate(&ctx, fee))]
+    pub fn execute_limit_open_order_setup(
+        ctx: Context<ExecuteLimitOpenOrderSetup>,
+        principal: u64,
+        fee: u64,
+    ) -> Result<()> {
+        ctx.accounts.execute_limit_open_order_setup(principal, fee)
+    }
+
+    pub fn execute_limit_open_order_cleanup(
+        ctx: Context<ExecuteLimitOpenOrderCleanup>,
+    ) -> Result<()> {
+        ctx.accounts.execute_limit_open_order_cleanup()
+    }
+
     #[access_control(CloseShortPositionSetup::validate(&ctx, expiration))]
     pub fn close_short_position_setup(
         ctx: Context<CloseShortPositionSetup>,
@@ -237,6 +669,12 @@ pub mod wasabi_solana {
         ctx.accounts.close_short_position_cleanup()
     }
 
+    pub fn close_short_position_cleanup_native(
+        ctx: Context<CloseShortPositionCleanupNative>,
+    ) -> Result<()> {
+        ctx.accounts.close_short_position_cleanup_native()
+    }
+
     #[access_control(LiquidatePositionSetup::validate(&ctx, expiration))]
     pub fn liquidate_position_setup(
         ctx: Context<LiquidatePositionSetup>,
@@ -257,7 +695,7 @@ pub mod wasabi_solana {
         ctx.accounts.liquidate_position_cleanup()
     }
 
-    #[access_control(TakeProfitSetup::validate(&ctx, expiration))]
+    #[access_control(TakeProfitSetup::validate(&ctx, execution_fee, expiration))]
     pub fn take_profit_setup(
         ctx: Context<TakeProfitSetup>,
         min_target_amount: u64,
@@ -273,7 +711,7 @@ pub mod wasabi_solana {
         ctx.accounts.take_profit_cleanup()
     }
 
-    #[access_control(StopLossSetup::validate(&ctx, expiration))]
+    #[access_control(StopLossSetup::validate(&ctx, execution_fee, expiration))]
     pub fn stop_loss_setup(
         ctx: Context<StopLossSetup>,
         min_target_amount: u64,
@@ -1,7 +1,12 @@
 #[macro_export]
 macro_rules! lp_vault_signer_seeds {
     ($lp_vault:expr) => {
-        &[b"lp_vault", $lp_vault.asset.as_ref(), &[$lp_vault.bump]]
+        &[
+            b"lp_vault",
+            $lp_vault.asset.as_ref(),
+            $lp_vault.version_seed(),
+            &[$lp_vault.bump],
+        ]
     };
 }
 
@@ -28,3 +33,39 @@ macro_rules! short_pool_signer_seeds {
         ]
     };
 }
+
+#[macro_export]
+macro_rules! limit_open_order_signer_seeds {
+    ($order:expr) => {
+        &[
+            b"limit_open_order",
+            $order.trader.as_ref(),
+            $order.pool.as_ref(),
+            &$order.nonce.to_le_bytes(),
+            &[$order.bump],
+        ]
+    };
+}
+
+#[macro_export]
+macro_rules! withdrawal_queue_signer_seeds {
+    ($queue:expr) => {
+        &[
+            b"withdrawal_queue",
+            $queue.lp_vault.as_ref(),
+            &[$queue.bump],
+        ]
+    };
+}
+
+#[macro_export]
+macro_rules! reward_pool_signer_seeds {
+    ($reward_pool:expr) => {
+        &[
+            b"reward_pool",
+            $reward_pool.lp_vault.as_ref(),
+            $reward_pool.reward_mint.as_ref(),
+            &[$reward_pool.bump],
+        ]
+    };
+}
@@ -0,0 +1,12 @@
+use anchor_lang::prelude::*;
+
+/// A wallet approved by a vault manager to hold shares of an allowlisted LP Vault
+#[account]
+pub struct AllowlistedWallet {
+    /// The LP Vault the wallet is approved for
+    pub lp_vault: Pubkey,
+    /// The approved wallet
+    pub wallet: Pubkey,
+    /// The bump seed for this PDA
+    pub bump: u8,
+}
@@ -1,4 +1,17 @@
-use anchor_lang::prelude::*;
+use {
+    crate::{error::ErrorCode, utils::deserialize_legacy_account},
+    anchor_lang::prelude::*,
+};
+
+/// `BasePool.statuses` bit rejecting new positions, including the execution of limit open orders.
+/// Limit open orders can still be placed and are executed once opens resume.
+pub const POOL_OPENS_PAUSED: u16 = 0b0000000000000001;
+/// `BasePool.statuses` bit for winding a pool down. On top of rejecting new positions, limit open
+/// orders can no longer be placed. Positions can still be closed, liquidated or given exit
+/// orders, and resting limit open orders cancelled.
+pub const POOL_REDUCE_ONLY: u16 = 0b0000000000000010;
+/// All `BasePool.statuses` bits
+pub const POOL_STATUSES: u16 = POOL_OPENS_PAUSED | POOL_REDUCE_ONLY;
 
 // NOTE: Nice to have for indexing (vault addresses)
 #[account]
@@ -15,10 +28,102 @@ pub struct BasePool {
     pub is_long_pool: bool,
     /// The bump seed for this PDA
     pub bump: u8,
+    /// Bit mapping of paused operations for this pool only
+    pub statuses: u16,
+}
+
+impl BasePool {
+    /// Returns true if opening new positions is paused
+    pub fn is_opens_paused(&self) -> bool {
+        self.statuses & POOL_OPENS_PAUSED == POOL_OPENS_PAUSED
+    }
+
+    /// Returns true if the pool only allows reducing exposure
+    pub fn is_reduce_only(&self) -> bool {
+        self.statuses & POOL_REDUCE_ONLY == POOL_REDUCE_ONLY
+    }
+
+    /// Ensures a new position may be opened in this pool
+    pub fn validate_can_open(&self) -> Result<()> {
+        require!(!self.is_reduce_only(), ErrorCode::PoolReduceOnly);
+        require!(!self.is_opens_paused(), ErrorCode::PoolOpensPaused);
+        Ok(())
+    }
+}
+/// The `BasePool` layout pools were created with before `statuses` was added. Such pools are
+/// migrated with `migrate_base_pool`.
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct BasePoolV0 {
+    pub collateral: Pubkey,
+    pub collateral_vault: Pubkey,
+    pub currency: Pubkey,
+    pub currency_vault: Pubkey,
+    pub is_long_pool: bool,
+    pub bump: u8,
 }
+
+impl BasePoolV0 {
+    /// Length of the account, including the discriminator
+    pub const LEN: usize = 8 + 32 * 4 + 1 + 1;
+
+    pub fn try_from_account_data(data: &[u8]) -> Result<Self> {
+        deserialize_legacy_account(data, BasePool::DISCRIMINATOR, Self::LEN)
+    }
+
+    /// The pool in the current layout, with no paused operations
+    pub fn migrate(self) -> BasePool {
+        BasePool {
+            collateral: self.collateral,
+            collateral_vault: self.collateral_vault,
+            currency: self.currency,
+            currency_vault: self.currency_vault,
+            is_long_pool: self.is_long_pool,
+            bump: self.bump,
+            statuses: 0,
+        }
+    }
+}
+
 // The long pool needs to borrow SOL to buy WIF. Collateral is held in WIF, so the long pool needs
 //  a WIF token account to store that collateral.
 // Need to make sure there is an LP Vault for the base currency
 
 // The short pool needs to borrow WIF to buy SOL. Collateral is held in SOL (base currency), so
 //  the short pool needs a wSOL token account to store the collateral.
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_migrate_v0_pool() {
+        let legacy = BasePoolV0 {
+            collateral: Pubkey::new_unique(),
+            collateral_vault: Pubkey::new_unique(),
+            currency: Pubkey::new_unique(),
+            currency_vault: Pubkey::new_unique(),
+            is_long_pool: true,
+            bump: 253,
+        };
+        let mut data = BasePool::DISCRIMINATOR.to_vec();
+        legacy.serialize(&mut data).unwrap();
+        assert_eq!(data.len(), BasePoolV0::LEN);
+
+        // The baseline-sized account can't be loaded with the current layout
+        assert!(BasePool::try_deserialize(&mut &data[..]).is_err());
+
+        let pool = BasePoolV0::try_from_account_data(&data).unwrap().migrate();
+        assert_eq!(pool.collateral, legacy.collateral);
+        assert_eq!(pool.collateral_vault, legacy.collateral_vault);
+        assert_eq!(pool.currency, legacy.currency);
+        assert_eq!(pool.currency_vault, legacy.currency_vault);
+        assert!(pool.is_long_pool);
+        assert_eq!(pool.bump, 253);
+        assert!(pool.validate_can_open().is_ok());
+
+        let mut migrated = Vec::new();
+        pool.try_serialize(&mut migrated).unwrap();
+        assert!(BasePool::try_deserialize(&mut &migrated[..]).is_ok());
+        assert!(BasePoolV0::try_from_account_data(&migrated).is_err());
+    }
+}
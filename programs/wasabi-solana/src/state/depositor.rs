@@ -0,0 +1,117 @@
+use {crate::error::ErrorCode, anchor_lang::prelude::*};
+
+/// Tracks how much and when a wallet has deposited into an LP Vault, used to enforce the
+/// per-wallet cap and the LP cooldown
+#[account]
+pub struct Depositor {
+    /// The wallet this record belongs to
+    pub owner: Pubkey,
+    /// The LP Vault deposited into
+    pub lp_vault: Pubkey,
+    /// Net amount of assets deposited: deposits minus withdrawals, floored at zero
+    pub deposited: u64,
+    /// The bump seed for this PDA
+    pub bump: u8,
+    /// The start of this wallet's cooldown. Deposits by the wallet restart it, while deposits made
+    /// for it by other wallets move it forward in proportion to their size.
+    pub last_deposit_timestamp: i64,
+}
+
+impl Depositor {
+    /// Records `amount` against the wallet cap and moves the cooldown forward. It restarts at `now`
+    /// if `restarts_cooldown`, otherwise its start moves towards `now` weighted by `amount` against
+    /// the wallet's net deposits, so small deposits for another wallet can't keep it from
+    /// withdrawing yet the deposited assets still wait out most of the cooldown.
+    pub fn record_deposit(
+        &mut self,
+        amount: u64,
+        wallet_deposit_cap: u64,
+        now: i64,
+        restarts_cooldown: bool,
+    ) -> Result<()> {
+        let deposited = self
+            .deposited
+            .checked_add(amount)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        if wallet_deposit_cap > 0 {
+            require_gte!(
+                wallet_deposit_cap,
+                deposited,
+                ErrorCode::WalletDepositCapExceeded
+            );
+        }
+        self.last_deposit_timestamp = if restarts_cooldown || self.deposited == 0 {
+            now
+        } else {
+            let elapsed = now.saturating_sub(self.last_deposit_timestamp) as i128;
+            let delay = elapsed
+                .checked_mul(amount as i128)
+                .ok_or(ErrorCode::ArithmeticOverflow)?
+                / deposited as i128;
+            self.last_deposit_timestamp
+                .checked_add(delay as i64)
+                .ok_or(ErrorCode::ArithmeticOverflow)?
+        };
+        self.deposited = deposited;
+        Ok(())
+    }
+
+    pub fn record_withdrawal(&mut self, amount: u64) {
+        self.deposited = self.deposited.saturating_sub(amount);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wallet_deposit_cap() {
+        let mut depositor = Depositor {
+            owner: Pubkey::default(),
+            lp_vault: Pubkey::default(),
+            deposited: 0,
+            bump: 0,
+            last_deposit_timestamp: 0,
+        };
+        depositor.record_deposit(600, 1_000, 10, true).unwrap();
+        assert_eq!(depositor.last_deposit_timestamp, 10);
+        assert!(depositor.record_deposit(401, 1_000, 20, true).is_err());
+        assert_eq!(depositor.deposited, 600);
+        assert_eq!(depositor.last_deposit_timestamp, 10);
+
+        depositor.record_withdrawal(800);
+        assert_eq!(depositor.deposited, 0);
+        depositor.record_deposit(1_000, 1_000, 30, true).unwrap();
+
+        // A cap of zero disables the per-wallet limit
+        depositor
+            .record_deposit(u64::MAX - 1_000, 0, 40, true)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_deposit_for_another_wallet_moves_cooldown() {
+        let mut depositor = Depositor {
+            owner: Pubkey::default(),
+            lp_vault: Pubkey::default(),
+            deposited: 0,
+            bump: 0,
+            last_deposit_timestamp: 0,
+        };
+
+        // The first deposit starts the cooldown even when made by another wallet
+        depositor.record_deposit(300, 0, 100, false).unwrap();
+        assert_eq!(depositor.last_deposit_timestamp, 100);
+
+        // Later deposits by other wallets count towards the cap and move the cooldown forward
+        // by their share of the net deposits
+        depositor.record_deposit(100, 0, 200, false).unwrap();
+        assert_eq!(depositor.deposited, 400);
+        assert_eq!(depositor.last_deposit_timestamp, 125);
+
+        // Deposits by the wallet itself restart it
+        depositor.record_deposit(100, 0, 300, true).unwrap();
+        assert_eq!(depositor.last_deposit_timestamp, 300);
+    }
+}
@@ -0,0 +1,376 @@
+use {
+    crate::{error::ErrorCode, utils::deserialize_legacy_account},
+    anchor_lang::prelude::*,
+};
+
+/// The decimals used for the normalized price of orders placed with raw amounts
+pub const DEFAULT_PRICE_DECIMALS: u8 = 9;
+/// The most decimals a price is given when it would otherwise round to zero
+pub const MAX_PRICE_DECIMALS: u8 = 18;
+
+/// The side of the position an exit order belongs to
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OrderSide {
+    #[default]
+    Long,
+    Short,
+}
+
+impl OrderSide {
+    pub fn new(is_long: bool) -> Self {
+        if is_long {
+            OrderSide::Long
+        } else {
+            OrderSide::Short
+        }
+    }
+}
+
+/// The type of an exit order, matching the `order_type` emitted in events
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExitOrderType {
+    TakeProfit = 0,
+    StopLoss = 1,
+}
+
+impl ExitOrderType {
+    /// The seed of the order's PDA, followed by the position's key
+    pub fn seed(&self) -> &'static [u8] {
+        match self {
+            ExitOrderType::TakeProfit => b"take_profit_order",
+            ExitOrderType::StopLoss => b"stop_loss_order",
+        }
+    }
+}
+
+/// The price of one whole `collateral` token quoted in whole `currency` tokens,
+/// scaled by `10^decimals` (i.e. `price: 150_500, decimals: 3` is 150.5 currency per collateral).
+/// Take profit orders trigger at or above the price, stop loss orders at or below it,
+/// regardless of the side of the position.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ExitOrderPrice {
+    pub price: u64,
+    pub decimals: u8,
+}
+
+impl ExitOrderPrice {
+    /// Converts the price to the amount of `currency` expected for `maker_amount` of
+    /// `collateral`, rounding up so the order never executes below the requested price.
+    pub fn to_taker_amount(
+        &self,
+        maker_amount: u64,
+        collateral_decimals: u8,
+        currency_decimals: u8,
+    ) -> Result<u64> {
+        // taker = maker * price * 10^currency_decimals / (10^collateral_decimals * 10^decimals)
+        let numerator = (maker_amount as u128)
+            .checked_mul(self.price as u128)
+            .ok_or(ErrorCode::ArithmeticOverflow)?
+            .checked_mul(pow10(currency_decimals)?)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        let denominator = pow10(collateral_decimals)?
+            .checked_mul(pow10(self.decimals)?)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+
+        numerator
+            .div_ceil(denominator)
+            .try_into()
+            .map_err(|_| ErrorCode::U64Overflow.into())
+    }
+
+    /// The normalized price implied by a raw `maker_amount` / `taker_amount` pair. Take profit
+    /// prices are rounded up and stop loss prices down, so the order never executes at a worse
+    /// price than the amounts describe.
+    /// A stop loss price that rounds down to zero is rejected, since it could never trigger.
+    pub fn from_amounts(
+        order_type: ExitOrderType,
+        maker_amount: u64,
+        taker_amount: u64,
+        collateral_decimals: u8,
+        currency_decimals: u8,
+        decimals: u8,
+    ) -> Result<Self> {
+        // price = taker * 10^collateral_decimals * 10^decimals / (maker * 10^currency_decimals)
+        let numerator = (taker_amount as u128)
+            .checked_mul(pow10(collateral_decimals)?)
+            .ok_or(ErrorCode::ArithmeticOverflow)?
+            .checked_mul(pow10(decimals)?)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        let denominator = (maker_amount as u128)
+            .checked_mul(pow10(currency_decimals)?)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        require_gt!(denominator, 0, ErrorCode::ZeroDivision);
+
+        let price = match order_type {
+            ExitOrderType::TakeProfit => numerator.div_ceil(denominator),
+            ExitOrderType::StopLoss => numerator / denominator,
+        }
+        .try_into()
+        .map_err(|_| ErrorCode::U64Overflow)?;
+        require_gt!(price, 0, ErrorCode::ZeroPrice);
+
+        Ok(Self { price, decimals })
+    }
+
+    /// Same as `from_amounts`, but prices that would round to zero get more decimals than
+    /// `decimals`, up to `MAX_PRICE_DECIMALS`. Used for orders that can't be placed again, like
+    /// orders migrated from the original layout.
+    pub fn from_amounts_with_min_decimals(
+        order_type: ExitOrderType,
+        maker_amount: u64,
+        taker_amount: u64,
+        collateral_decimals: u8,
+        currency_decimals: u8,
+        decimals: u8,
+    ) -> Result<Self> {
+        let mut decimals = decimals;
+        loop {
+            match Self::from_amounts(
+                order_type,
+                maker_amount,
+                taker_amount,
+                collateral_decimals,
+                currency_decimals,
+                decimals,
+            ) {
+                Err(err) if err == ErrorCode::ZeroPrice.into() && decimals < MAX_PRICE_DECIMALS => {
+                    decimals += 1
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+/// The take profit and stop loss order layout orders were created with before the execution fee,
+/// price and side were added. Such orders are migrated with `migrate_exit_order`.
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct ExitOrderV0 {
+    pub position: Pubkey,
+    pub maker_amount: u64,
+    pub taker_amount: u64,
+}
+
+impl ExitOrderV0 {
+    /// Length of the account, including the discriminator
+    pub const LEN: usize = 8 + 32 + 8 + 8;
+
+    /// Parses an order account of the type with `discriminator` still in the original layout
+    pub fn try_from_account_data(data: &[u8], discriminator: &[u8]) -> Result<Self> {
+        deserialize_legacy_account(data, discriminator, Self::LEN)
+    }
+}
+
+fn pow10(exp: u8) -> Result<u128> {
+    Ok(10u128
+        .checked_pow(exp as u32)
+        .ok_or(ErrorCode::ArithmeticOverflow)?)
+}
+
+/// Shared execution check for take profit and stop loss orders.
+///
+/// The executed price is `actual_taker_amount` of `currency` for `actual_maker_amount` of
+/// `collateral`, normalized with the mint decimals and compared against the order `price`.
+/// A take profit order requires the executed price to be at or above the order price, a stop
+/// loss order at or below it.
+pub fn validate_exit_order_execution(
+    order_type: ExitOrderType,
+    price: &ExitOrderPrice,
+    actual_maker_amount: u64,
+    actual_taker_amount: u64,
+    collateral_decimals: u8,
+    currency_decimals: u8,
+) -> Result<()> {
+    // actual_taker / 10^currency_decimals / (actual_maker / 10^collateral_decimals)
+    //      vs  price / 10^decimals
+    let executed = (actual_taker_amount as u128)
+        .checked_mul(pow10(collateral_decimals)?)
+        .ok_or(ErrorCode::ArithmeticOverflow)?
+        .checked_mul(pow10(price.decimals)?)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+    let target = (price.price as u128)
+        .checked_mul(actual_maker_amount as u128)
+        .ok_or(ErrorCode::ArithmeticOverflow)?
+        .checked_mul(pow10(currency_decimals)?)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+
+    match order_type {
+        ExitOrderType::TakeProfit => {
+            require_gte!(executed, target, ErrorCode::PriceTargetNotReached)
+        }
+        ExitOrderType::StopLoss => {
+            require_gte!(target, executed, ErrorCode::PriceTargetNotReached)
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::TakeProfitOrder};
+
+    #[test]
+    fn test_load_v0_exit_order() {
+        let legacy = ExitOrderV0 {
+            position: Pubkey::new_unique(),
+            maker_amount: 1_000,
+            taker_amount: 2_000,
+        };
+        let mut data = TakeProfitOrder::DISCRIMINATOR.to_vec();
+        legacy.serialize(&mut data).unwrap();
+        assert_eq!(data.len(), ExitOrderV0::LEN);
+
+        // The baseline-sized account can't be loaded with the current layout
+        assert!(TakeProfitOrder::try_deserialize(&mut &data[..]).is_err());
+
+        let order =
+            ExitOrderV0::try_from_account_data(&data, TakeProfitOrder::DISCRIMINATOR).unwrap();
+        assert_eq!(order.position, legacy.position);
+        assert_eq!(order.maker_amount, 1_000);
+        assert_eq!(order.taker_amount, 2_000);
+
+        // The discriminator must match the order type being migrated
+        assert!(
+            ExitOrderV0::try_from_account_data(&data, crate::StopLossOrder::DISCRIMINATOR).is_err()
+        );
+    }
+
+    #[test]
+    fn test_price_to_taker_amount() {
+        // 150.5 USDC (6 decimals) per SOL (9 decimals)
+        let price = ExitOrderPrice {
+            price: 150_500,
+            decimals: 3,
+        };
+        assert_eq!(
+            price.to_taker_amount(2_000_000_000, 9, 6).unwrap(),
+            301_000_000
+        );
+        // Rounds up
+        assert_eq!(price.to_taker_amount(1, 9, 6).unwrap(), 1);
+
+        let from = ExitOrderPrice::from_amounts(
+            ExitOrderType::TakeProfit,
+            2_000_000_000,
+            301_000_000,
+            9,
+            6,
+            3,
+        )
+        .unwrap();
+        assert_eq!(from, price);
+
+        // 2/3 currency per collateral: take profit rounds up, stop loss down
+        let tp = ExitOrderPrice::from_amounts(ExitOrderType::TakeProfit, 3, 2, 6, 6, 3).unwrap();
+        assert_eq!(tp.price, 667);
+        let sl = ExitOrderPrice::from_amounts(ExitOrderType::StopLoss, 3, 2, 6, 6, 3).unwrap();
+        assert_eq!(sl.price, 666);
+    }
+
+    #[test]
+    fn test_price_from_amounts_low_priced_pair() {
+        // A memecoin (6 decimals) trading around 0.000000002 SOL (9 decimals)
+        let maker_amount = 1_000_000_000_000; // 1M collateral tokens
+        let taker_amount = 2_000_000; // 0.002 SOL
+        let tp = ExitOrderPrice::from_amounts(
+            ExitOrderType::TakeProfit,
+            maker_amount,
+            taker_amount,
+            6,
+            9,
+            DEFAULT_PRICE_DECIMALS,
+        )
+        .unwrap();
+        assert_eq!(tp.price, 2);
+
+        // Halving the stop loss price leaves less than one unit at the default decimals
+        assert_eq!(
+            ExitOrderPrice::from_amounts(
+                ExitOrderType::StopLoss,
+                maker_amount * 2,
+                taker_amount / 2,
+                6,
+                9,
+                DEFAULT_PRICE_DECIMALS,
+            )
+            .unwrap_err(),
+            ErrorCode::ZeroPrice.into()
+        );
+        // The same order is accepted with a finer price scale
+        let sl = ExitOrderPrice::from_amounts(
+            ExitOrderType::StopLoss,
+            maker_amount * 2,
+            taker_amount / 2,
+            6,
+            9,
+            12,
+        )
+        .unwrap();
+        assert_eq!(sl.price, 500);
+        assert!(validate_exit_order_execution(
+            ExitOrderType::StopLoss,
+            &sl,
+            maker_amount * 2,
+            taker_amount / 2,
+            6,
+            9
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn test_price_from_amounts_with_min_decimals() {
+        // 0.0000005 SOL (9 decimals) per collateral token (6 decimals) needs 7 decimals
+        let price = ExitOrderPrice::from_amounts_with_min_decimals(
+            ExitOrderType::StopLoss,
+            2_000_000_000_000,
+            1_000_000,
+            6,
+            9,
+            DEFAULT_PRICE_DECIMALS,
+        )
+        .unwrap();
+        assert_eq!(price.price, 5);
+        assert_eq!(price.decimals, DEFAULT_PRICE_DECIMALS + 1);
+
+        // Prices that don't round to zero keep the requested decimals
+        let price =
+            ExitOrderPrice::from_amounts_with_min_decimals(ExitOrderType::StopLoss, 3, 2, 6, 6, 3)
+                .unwrap();
+        assert_eq!(price.price, 666);
+        assert_eq!(price.decimals, 3);
+
+        // A zero taker amount has no price at any scale
+        assert!(ExitOrderPrice::from_amounts_with_min_decimals(
+            ExitOrderType::StopLoss,
+            3,
+            0,
+            6,
+            6,
+            3,
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_validate_exit_order_execution() {
+        // Order price is 2 currency per collateral
+        let tp = ExitOrderType::TakeProfit;
+        let sl = ExitOrderType::StopLoss;
+        let price = ExitOrderPrice {
+            price: 2_000,
+            decimals: 3,
+        };
+        assert!(validate_exit_order_execution(tp, &price, 50, 100, 6, 6).is_ok());
+        assert!(validate_exit_order_execution(tp, &price, 50, 101, 6, 6).is_ok());
+        assert!(validate_exit_order_execution(tp, &price, 50, 99, 6, 6).is_err());
+        assert!(validate_exit_order_execution(sl, &price, 50, 100, 6, 6).is_ok());
+        assert!(validate_exit_order_execution(sl, &price, 50, 99, 6, 6).is_ok());
+        assert!(validate_exit_order_execution(sl, &price, 50, 101, 6, 6).is_err());
+
+        // 2 USDC (6 decimals) per SOL (9 decimals): 1 SOL sold for 2 USDC
+        assert!(validate_exit_order_execution(tp, &price, 1_000_000_000, 2_000_000, 9, 6).is_ok());
+        assert!(validate_exit_order_execution(tp, &price, 1_000_000_000, 1_999_999, 9, 6).is_err());
+    }
+}
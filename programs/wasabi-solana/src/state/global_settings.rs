@@ -1,4 +1,4 @@
-use anchor_lang::prelude::*;
+use {crate::utils::deserialize_legacy_account, anchor_lang::prelude::*};
 
 const TRADING_ENABLED: u16 = 0b0000000000000001;
 const LPING_ENABLED: u16 = 0b0000000000000010;
@@ -10,6 +10,38 @@ pub struct GlobalSettings {
     pub liquidation_wallet: Pubkey,
     /// Bit mapping of enabled features. Status allow disabling trading, lping, etc.
     pub statuses: u16,
+    /// The largest execution fee, in lamports, a trader can escrow on an exit order
+    pub max_exit_order_execution_fee: u64,
+}
+
+/// The `GlobalSettings` layout the program was deployed with, before the exit order execution
+/// fee cap was added. Migrated with `migrate_global_settings`.
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct GlobalSettingsV0 {
+    pub super_admin: Pubkey,
+    pub fee_wallet: Pubkey,
+    pub liquidation_wallet: Pubkey,
+    pub statuses: u16,
+}
+
+impl GlobalSettingsV0 {
+    /// Length of the account, including the discriminator
+    pub const LEN: usize = 8 + 32 * 3 + 2;
+
+    pub fn try_from_account_data(data: &[u8]) -> Result<Self> {
+        deserialize_legacy_account(data, GlobalSettings::DISCRIMINATOR, Self::LEN)
+    }
+
+    /// The settings in the current layout, with exit order execution fees uncapped
+    pub fn migrate(self) -> GlobalSettings {
+        GlobalSettings {
+            super_admin: self.super_admin,
+            fee_wallet: self.fee_wallet,
+            liquidation_wallet: self.liquidation_wallet,
+            statuses: self.statuses,
+            max_exit_order_execution_fee: u64::MAX,
+        }
+    }
 }
 
 impl GlobalSettings {
@@ -58,3 +90,38 @@ impl GlobalSettings {
         self.statuses
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_migrate_v0_global_settings() {
+        let legacy = GlobalSettingsV0 {
+            super_admin: Pubkey::new_unique(),
+            fee_wallet: Pubkey::new_unique(),
+            liquidation_wallet: Pubkey::new_unique(),
+            statuses: TRADING_ENABLED | LPING_ENABLED,
+        };
+        let mut data = GlobalSettings::DISCRIMINATOR.to_vec();
+        legacy.serialize(&mut data).unwrap();
+        assert_eq!(data.len(), GlobalSettingsV0::LEN);
+
+        // The baseline-sized account can't be loaded with the current layout
+        assert!(GlobalSettings::try_deserialize(&mut &data[..]).is_err());
+
+        let settings = GlobalSettingsV0::try_from_account_data(&data)
+            .unwrap()
+            .migrate();
+        assert_eq!(settings.super_admin, legacy.super_admin);
+        assert_eq!(settings.fee_wallet, legacy.fee_wallet);
+        assert_eq!(settings.liquidation_wallet, legacy.liquidation_wallet);
+        assert!(settings.can_trade() && settings.can_lp());
+        assert_eq!(settings.max_exit_order_execution_fee, u64::MAX);
+
+        let mut migrated = Vec::new();
+        settings.try_serialize(&mut migrated).unwrap();
+        assert!(GlobalSettings::try_deserialize(&mut &migrated[..]).is_ok());
+        assert!(GlobalSettingsV0::try_from_account_data(&migrated).is_err());
+    }
+}
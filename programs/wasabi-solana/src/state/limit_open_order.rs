@@ -0,0 +1,112 @@
+use {
+    crate::{debt_controller::LEVERAGE_DENOMINATOR, error::ErrorCode},
+    anchor_lang::prelude::*,
+};
+
+/// A resting order to open a position, executed by a keeper once the limit price is reachable.
+/// The down payment and fee are escrowed in `escrow` until the order is executed or cancelled.
+#[account]
+pub struct LimitOpenOrder {
+    /// Wallet that placed the order and will own the opened position
+    pub trader: Pubkey,
+    /// The long or short pool the position will be opened in
+    pub pool: Pubkey,
+    /// The LP Vault that the position will borrow from
+    pub lp_vault: Pubkey,
+    /// Token account owned by this order that holds the down payment and fee
+    pub escrow: Pubkey,
+    /// Nonce used to derive the address of the opened position
+    pub nonce: u16,
+    /// The down payment (is in `currency` for long, `collateral` for short positions)
+    pub down_payment: u64,
+    /// The maximum open fee paid to the fee wallet on execution (same mint as `down_payment`).
+    /// Orders are only executed while it covers the open fee the protocol charges at the time,
+    /// and the difference is refunded to the trader.
+    pub fee: u64,
+    /// Requested leverage, scaled by `LEVERAGE_DENOMINATOR` (i.e. 300 == 3x)
+    pub leverage: u64,
+    /// Limit price: the amount of `currency` swapped for at least `taker_amount` of collateral
+    pub maker_amount: u64,
+    /// Limit price: the minimum amount of `collateral` received for every `maker_amount` swapped
+    pub taker_amount: u64,
+    /// `unix_timestamp` after which the order can no longer be executed
+    pub expiration: i64,
+    /// The bump seed for this PDA
+    pub bump: u8,
+}
+
+impl LimitOpenOrder {
+    /// The principal implied by the down payment and the requested leverage.
+    /// For shorts this is denominated in collateral, i.e. the cap on the collateral received.
+    pub fn compute_principal(&self) -> Result<u64> {
+        // principal = down_payment * (leverage - LEVERAGE_DENOMINATOR) / LEVERAGE_DENOMINATOR
+        let principal: u64 = (self.down_payment as u128)
+            .checked_mul(
+                self.leverage
+                    .checked_sub(LEVERAGE_DENOMINATOR)
+                    .ok_or(ErrorCode::ArithmeticUnderflow)? as u128,
+            )
+            .ok_or(ErrorCode::ArithmeticOverflow)?
+            .checked_div(LEVERAGE_DENOMINATOR as u128)
+            .ok_or(ErrorCode::ZeroDivision)?
+            .try_into()
+            .map_err(|_| ErrorCode::U64Overflow)?;
+        Ok(principal)
+    }
+
+    /// Returns an error unless `collateral_received / currency_spent` is at least the limit price
+    pub fn validate_execution_price(
+        &self,
+        currency_spent: u64,
+        collateral_received: u64,
+    ) -> Result<()> {
+        // collateral_received / currency_spent >= taker_amount / maker_amount
+        let lhs = (collateral_received as u128)
+            .checked_mul(self.maker_amount as u128)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        let rhs = (currency_spent as u128)
+            .checked_mul(self.taker_amount as u128)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+
+        require_gte!(lhs, rhs, ErrorCode::PriceTargetNotReached);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn order(down_payment: u64, leverage: u64) -> LimitOpenOrder {
+        LimitOpenOrder {
+            trader: Pubkey::default(),
+            pool: Pubkey::default(),
+            lp_vault: Pubkey::default(),
+            escrow: Pubkey::default(),
+            nonce: 0,
+            down_payment,
+            fee: 0,
+            leverage,
+            maker_amount: 1_000,
+            taker_amount: 2_000,
+            expiration: 0,
+            bump: 0,
+        }
+    }
+
+    #[test]
+    fn test_compute_principal() {
+        assert_eq!(order(1_000, 300).compute_principal().unwrap(), 2_000);
+        assert_eq!(order(1_000, 150).compute_principal().unwrap(), 500);
+        assert!(order(1_000, 50).compute_principal().is_err());
+    }
+
+    #[test]
+    fn test_validate_execution_price() {
+        let order = order(1_000, 300);
+        assert!(order.validate_execution_price(3_000, 6_000).is_ok());
+        assert!(order.validate_execution_price(3_000, 6_001).is_ok());
+        assert!(order.validate_execution_price(3_000, 5_999).is_err());
+    }
+}
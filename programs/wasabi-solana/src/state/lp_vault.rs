@@ -1,4 +1,48 @@
-use anchor_lang::prelude::*;
+use {
+    crate::{error::ErrorCode, utils::deserialize_legacy_account},
+    anchor_lang::prelude::*,
+};
+
+/// Virtual shares and assets added to both sides of every conversion. They behave like shares
+/// locked in the vault forever, so an attacker inflating the share price through donations loses
+/// most of the donation to them, which makes the first-depositor inflation attack unprofitable.
+/// Both offsets are equal so an empty vault still mints shares 1:1.
+pub const VIRTUAL_SHARES: u64 = 1_000;
+pub const VIRTUAL_ASSETS: u64 = 1_000;
+
+/// The smallest deposit accepted while the vault has no shares outstanding
+pub const MIN_FIRST_DEPOSIT: u64 = 1_000;
+
+/// Share prices are quoted as the assets per `SHARE_PRICE_SCALE` shares
+pub const SHARE_PRICE_SCALE: u64 = 1_000_000_000;
+
+/// Basis points denominator used by `max_utilization_bps`
+pub const UTILIZATION_BPS_DENOMINATOR: u16 = 10_000;
+
+/// `LpVault.statuses` bit rejecting deposits
+pub const VAULT_DEPOSITS_PAUSED: u16 = 0b0000000000000001;
+/// `LpVault.statuses` bit rejecting withdrawals and redemptions
+pub const VAULT_WITHDRAWALS_PAUSED: u16 = 0b0000000000000010;
+/// `LpVault.statuses` bit rejecting new borrows by positions and strategies
+pub const VAULT_BORROWS_PAUSED: u16 = 0b0000000000000100;
+/// Basis points denominator used by the vault's fees
+pub const FEE_BPS_DENOMINATOR: u16 = 10_000;
+/// Upper bound for `performance_fee_bps`
+pub const MAX_PERFORMANCE_FEE_BPS: u16 = 5_000;
+/// Upper bound for `management_fee_bps`
+pub const MAX_MANAGEMENT_FEE_BPS: u16 = 1_000;
+/// Upper bound for `vesting_period`, 30 days
+pub const MAX_VESTING_PERIOD: i64 = 2_592_000;
+/// Upper bound for `lp_cooldown`, 7 days
+pub const MAX_LP_COOLDOWN: i64 = 604_800;
+/// Upper bound for `exit_fee_bps`
+pub const MAX_EXIT_FEE_BPS: u16 = 1_000;
+
+const ONE_YEAR_IN_SECONDS: u64 = 31_536_000;
+
+/// All `LpVault.statuses` bits
+pub const VAULT_STATUSES: u16 =
+    VAULT_DEPOSITS_PAUSED | VAULT_WITHDRAWALS_PAUSED | VAULT_BORROWS_PAUSED;
 
 #[account]
 pub struct LpVault {
@@ -16,4 +60,806 @@ pub struct LpVault {
     pub max_borrow: u64,
     /// Total amount currently borrowed from the vault that is to be paid back by the admin
     pub total_borrowed: u64,
+    /// Maximum `total_assets` deposits may bring the vault to
+    pub deposit_cap: u64,
+    /// Maximum net amount a single wallet may deposit, 0 if uncapped
+    pub wallet_deposit_cap: u64,
+    /// Maximum share of `total_assets` that may be lent out to trading positions, in bps
+    pub max_utilization_bps: u16,
+    /// Bit mapping of paused operations for this vault only
+    pub statuses: u16,
+    /// The shares token account receiving the vault's fees
+    pub fee_shares_account: Pubkey,
+    /// Share of realised interest taken as a fee, in bps
+    pub performance_fee_bps: u16,
+    /// Annual fee on `total_assets`, in bps
+    pub management_fee_bps: u16,
+    /// Performance fees accrued in assets since fees were last crystallised
+    pub pending_performance_fee: u64,
+    /// The last time fees were crystallised
+    pub last_fee_timestamp: i64,
+    /// Donations and strategy yield not yet streamed into `total_assets`
+    pub unvested_assets: u64,
+    /// Seconds over which donations and strategy yield vest, 0 to add them instantly
+    pub vesting_period: i64,
+    /// The time `unvested_assets` will be fully vested
+    pub vesting_end: i64,
+    /// The last time assets were vested
+    pub last_vesting_timestamp: i64,
+    /// Seconds after a deposit during which the depositor's withdrawals are restricted, 0 if
+    /// disabled. The cooldown is tracked on the `Depositor` of the wallet holding the shares,
+    /// which starts it when created and moves it forward on every deposit into the wallet.
+    pub lp_cooldown: i64,
+    /// Fee kept by the vault on withdrawals during the cooldown, in bps. Withdrawals during the
+    /// cooldown are rejected if 0.
+    pub exit_fee_bps: u16,
+    /// True if only wallets with an `AllowlistedWallet` may deposit or receive shares
+    pub allowlist_enabled: bool,
+    /// Version of the vault for its asset. Version 0 is the original vault, seeded by the asset
+    /// only; later versions also seed the version.
+    pub version: u8,
+    /// True once the vault is winding down. It no longer accepts deposits or lends, and is closed
+    /// once every LP has redeemed or migrated.
+    pub decommissioned: bool,
+    /// The next version of this vault LPs may migrate into, the default pubkey if none
+    pub successor: Pubkey,
+    /// True once the vault's `WithdrawalQueue` exists. Instructions moving the vault's tokens
+    /// then require it, so inflows are reserved for queued withdrawals.
+    pub has_withdrawal_queue: bool,
+    /// True once the vault's `SharePriceHistory` exists. Instructions changing the share price
+    /// then require it, so every change is recorded.
+    pub has_share_price_history: bool,
+    /// Tokens moved from `vault` into the withdrawal queue's reserve. They still count towards
+    /// `total_assets` until the queued requests are filled, but aren't lent out.
+    pub queue_reserved_assets: u64,
+}
+
+/// Fees crystallised into shares by `LpVault::crystallize_fees`
+pub struct FeeAccrual {
+    pub performance_fee: u64,
+    pub management_fee: u64,
+    pub shares: u64,
+}
+
+/// The `LpVault` layout vaults were created with before caps, fees, vesting, cooldowns, the
+/// allowlist and versioning were added. Such vaults are migrated with `migrate_lp_vault`.
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct LpVaultV0 {
+    pub bump: u8,
+    pub asset: Pubkey,
+    pub vault: Pubkey,
+    pub shares_mint: Pubkey,
+    pub total_assets: u64,
+    pub max_borrow: u64,
+    pub total_borrowed: u64,
+}
+
+impl LpVaultV0 {
+    /// Length of the account, including the discriminator
+    pub const LEN: usize = 8 + 1 + 32 * 3 + 8 * 3;
+
+    pub fn try_from_account_data(data: &[u8]) -> Result<Self> {
+        deserialize_legacy_account(data, LpVault::DISCRIMINATOR, Self::LEN)
+    }
+
+    /// The vault in the current layout, with the added fields set like a new vault's
+    pub fn migrate(self, now: i64) -> LpVault {
+        LpVault {
+            bump: self.bump,
+            asset: self.asset,
+            vault: self.vault,
+            shares_mint: self.shares_mint,
+            total_assets: self.total_assets,
+            max_borrow: self.max_borrow,
+            total_borrowed: self.total_borrowed,
+            deposit_cap: u64::MAX,
+            wallet_deposit_cap: 0,
+            max_utilization_bps: UTILIZATION_BPS_DENOMINATOR,
+            statuses: 0,
+            fee_shares_account: Pubkey::default(),
+            performance_fee_bps: 0,
+            management_fee_bps: 0,
+            pending_performance_fee: 0,
+            last_fee_timestamp: now,
+            unvested_assets: 0,
+            vesting_period: 0,
+            vesting_end: now,
+            last_vesting_timestamp: now,
+            lp_cooldown: 0,
+            exit_fee_bps: 0,
+            allowlist_enabled: false,
+            version: 0,
+            decommissioned: false,
+            successor: Pubkey::default(),
+            has_withdrawal_queue: false,
+            has_share_price_history: false,
+            queue_reserved_assets: 0,
+        }
+    }
+}
+
+impl LpVault {
+    /// A new, empty vault with no caps, fees or paused operations
+    pub fn new(bump: u8, asset: Pubkey, vault: Pubkey, shares_mint: Pubkey, now: i64) -> Self {
+        Self {
+            bump,
+            asset,
+            vault,
+            shares_mint,
+            total_assets: 0,
+            max_borrow: 0,
+            total_borrowed: 0,
+            deposit_cap: u64::MAX,
+            wallet_deposit_cap: 0,
+            max_utilization_bps: UTILIZATION_BPS_DENOMINATOR,
+            statuses: 0,
+            fee_shares_account: Pubkey::default(),
+            performance_fee_bps: 0,
+            management_fee_bps: 0,
+            pending_performance_fee: 0,
+            last_fee_timestamp: now,
+            unvested_assets: 0,
+            vesting_period: 0,
+            vesting_end: now,
+            last_vesting_timestamp: now,
+            lp_cooldown: 0,
+            exit_fee_bps: 0,
+            allowlist_enabled: false,
+            version: 0,
+            decommissioned: false,
+            successor: Pubkey::default(),
+            has_withdrawal_queue: false,
+            has_share_price_history: false,
+            queue_reserved_assets: 0,
+        }
+    }
+
+    /// A new, empty successor of this vault for the same asset, carrying over its caps, fee rates,
+    /// vesting period, cooldown and allowlist. The fee shares account belongs to this vault's
+    /// shares mint and can't carry over, so while the fee rates are non-zero the successor takes
+    /// no deposits or migrations until `set_vault_fees` names its own.
+    pub fn new_successor(
+        &self,
+        bump: u8,
+        vault: Pubkey,
+        shares_mint: Pubkey,
+        now: i64,
+    ) -> Result<Self> {
+        Ok(Self {
+            max_borrow: self.max_borrow,
+            deposit_cap: self.deposit_cap,
+            wallet_deposit_cap: self.wallet_deposit_cap,
+            max_utilization_bps: self.max_utilization_bps,
+            performance_fee_bps: self.performance_fee_bps,
+            management_fee_bps: self.management_fee_bps,
+            vesting_period: self.vesting_period,
+            lp_cooldown: self.lp_cooldown,
+            exit_fee_bps: self.exit_fee_bps,
+            allowlist_enabled: self.allowlist_enabled,
+            version: self
+                .version
+                .checked_add(1)
+                .ok_or(ErrorCode::ArithmeticOverflow)?,
+            ..Self::new(bump, self.asset, vault, shares_mint, now)
+        })
+    }
+
+    /// The version seed of the vault's PDA, empty for the original vault
+    pub fn version_seed(&self) -> &[u8] {
+        if self.version == 0 {
+            &[]
+        } else {
+            std::slice::from_ref(&self.version)
+        }
+    }
+
+    /// Returns true if the vault accepts deposits
+    pub fn can_deposit(&self) -> bool {
+        self.statuses & VAULT_DEPOSITS_PAUSED == 0 && !self.decommissioned
+    }
+
+    /// Returns true if the vault allows withdrawals
+    pub fn can_withdraw(&self) -> bool {
+        self.statuses & VAULT_WITHDRAWALS_PAUSED == 0
+    }
+
+    /// Returns true if the vault lends to positions and strategies
+    pub fn can_borrow(&self) -> bool {
+        self.statuses & VAULT_BORROWS_PAUSED == 0 && !self.decommissioned
+    }
+
+    fn virtual_shares(shares_supply: u64) -> u128 {
+        shares_supply as u128 + VIRTUAL_SHARES as u128
+    }
+
+    fn virtual_assets(&self) -> u128 {
+        self.total_assets as u128 + VIRTUAL_ASSETS as u128
+    }
+
+    /// The amount of shares `assets` is worth, rounded down
+    pub fn convert_to_shares(&self, assets: u64, shares_supply: u64) -> Result<u64> {
+        Ok((assets as u128)
+            .checked_mul(Self::virtual_shares(shares_supply))
+            .ok_or(ErrorCode::ArithmeticOverflow)?
+            .checked_div(self.virtual_assets())
+            .ok_or(ErrorCode::ZeroDivision)?
+            .try_into()
+            .map_err(|_| ErrorCode::U64Overflow)?)
+    }
+
+    /// The amount of assets `shares` is worth, rounded down
+    pub fn convert_to_assets(&self, shares: u64, shares_supply: u64) -> Result<u64> {
+        Ok((shares as u128)
+            .checked_mul(self.virtual_assets())
+            .ok_or(ErrorCode::ArithmeticOverflow)?
+            .checked_div(Self::virtual_shares(shares_supply))
+            .ok_or(ErrorCode::ZeroDivision)?
+            .try_into()
+            .map_err(|_| ErrorCode::U64Overflow)?)
+    }
+
+    /// The assets per `SHARE_PRICE_SCALE` shares
+    pub fn share_price(&self, shares_supply: u64) -> Result<u64> {
+        self.convert_to_assets(SHARE_PRICE_SCALE, shares_supply)
+    }
+
+    /// The amount of shares minted by depositing `assets`
+    pub fn preview_deposit(&self, assets: u64, shares_supply: u64) -> Result<u64> {
+        self.convert_to_shares(assets, shares_supply)
+    }
+
+    /// The amount of assets that must be deposited to mint `shares`, rounded up
+    pub fn preview_mint(&self, shares: u64, shares_supply: u64) -> Result<u64> {
+        Ok((shares as u128)
+            .checked_mul(self.virtual_assets())
+            .ok_or(ErrorCode::ArithmeticOverflow)?
+            .div_ceil(Self::virtual_shares(shares_supply))
+            .try_into()
+            .map_err(|_| ErrorCode::U64Overflow)?)
+    }
+
+    /// The amount of shares burned by withdrawing `assets`
+    pub fn preview_withdraw(&self, assets: u64, shares_supply: u64) -> Result<u64> {
+        let assets_u128 = assets as u128;
+
+        // Calculate proportional rounding protection
+        // Uses 0.1% (1/1000) of withdrawal amount as protection, minimum of 1
+        let rounding_protection = std::cmp::max(1, assets_u128 / 1000);
+
+        Ok(assets_u128
+            .checked_mul(Self::virtual_shares(shares_supply))
+            .ok_or(ErrorCode::ArithmeticOverflow)?
+            .checked_add(rounding_protection)
+            .ok_or(ErrorCode::ArithmeticOverflow)?
+            .checked_div(self.virtual_assets())
+            .ok_or(ErrorCode::ZeroDivision)?
+            .try_into()
+            .map_err(|_| ErrorCode::U64Overflow)?)
+    }
+
+    /// The amount of assets received by redeeming `shares`
+    pub fn preview_redeem(&self, shares: u64, shares_supply: u64) -> Result<u64> {
+        self.convert_to_assets(shares, shares_supply)
+    }
+
+    /// Accrues the performance fee on `interest` realised by the vault
+    pub fn accrue_performance_fee(&mut self, interest: u64) -> Result<()> {
+        let fee = (interest as u128)
+            .checked_mul(self.performance_fee_bps as u128)
+            .ok_or(ErrorCode::ArithmeticOverflow)?
+            / FEE_BPS_DENOMINATOR as u128;
+        self.pending_performance_fee = self
+            .pending_performance_fee
+            .checked_add(fee as u64)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        Ok(())
+    }
+
+    /// The management fee accrued on `total_assets` since fees were last crystallised
+    pub fn compute_management_fee(&self, now: i64) -> Result<u64> {
+        let elapsed = now.saturating_sub(self.last_fee_timestamp).max(0) as u128;
+        let fee = (self.total_assets as u128)
+            .checked_mul(self.management_fee_bps as u128)
+            .ok_or(ErrorCode::ArithmeticOverflow)?
+            .checked_mul(elapsed)
+            .ok_or(ErrorCode::ArithmeticOverflow)?
+            / (FEE_BPS_DENOMINATOR as u128 * ONE_YEAR_IN_SECONDS as u128);
+        Ok(fee.try_into().map_err(|_| ErrorCode::U64Overflow)?)
+    }
+
+    /// True if the vault charges fees or has performance fees left to crystallise. Its fees must
+    /// then be crystallised before the share price is used.
+    pub fn charges_fees(&self) -> bool {
+        self.performance_fee_bps > 0
+            || self.management_fee_bps > 0
+            || self.pending_performance_fee > 0
+    }
+
+    /// The amount of shares `crystallize_fees` would mint to the fee account at `now`, such that
+    /// the new shares are worth the accrued fees once minted
+    pub fn pending_fee_shares(&self, shares_supply: u64, now: i64) -> Result<u64> {
+        let fee = self
+            .pending_performance_fee
+            .checked_add(self.compute_management_fee(now)?)
+            .ok_or(ErrorCode::ArithmeticOverflow)?
+            .min(self.total_assets);
+
+        // shares / (supply + shares) = fee / total_assets
+        Ok((fee as u128)
+            .checked_mul(Self::virtual_shares(shares_supply))
+            .ok_or(ErrorCode::ArithmeticOverflow)?
+            .checked_div(self.virtual_assets() - fee as u128)
+            .ok_or(ErrorCode::ZeroDivision)?
+            .try_into()
+            .map_err(|_| ErrorCode::U64Overflow)?)
+    }
+
+    /// Converts the accrued fees into the amount of shares to mint to the fee account. Resets the
+    /// accrual.
+    pub fn crystallize_fees(&mut self, shares_supply: u64, now: i64) -> Result<FeeAccrual> {
+        let management_fee = self.compute_management_fee(now)?;
+        let performance_fee = self.pending_performance_fee;
+        let shares = self.pending_fee_shares(shares_supply, now)?;
+
+        self.pending_performance_fee = 0;
+        self.last_fee_timestamp = now;
+
+        Ok(FeeAccrual {
+            performance_fee,
+            management_fee,
+            shares,
+        })
+    }
+
+    /// The part of `unvested_assets` that has vested since assets were last vested
+    pub fn vested_assets(&self, now: i64) -> Result<u64> {
+        if now >= self.vesting_end {
+            return Ok(self.unvested_assets);
+        }
+        let elapsed = now.saturating_sub(self.last_vesting_timestamp).max(0) as u128;
+        let remaining = self.vesting_end.saturating_sub(self.last_vesting_timestamp) as u128;
+        Ok((self.unvested_assets as u128)
+            .checked_mul(elapsed)
+            .ok_or(ErrorCode::ArithmeticOverflow)?
+            .checked_div(remaining)
+            .ok_or(ErrorCode::ZeroDivision)?
+            .try_into()
+            .map_err(|_| ErrorCode::U64Overflow)?)
+    }
+
+    /// Streams the vested part of `unvested_assets` into `total_assets`. Must be called before
+    /// shares are priced.
+    pub fn vest(&mut self, now: i64) -> Result<()> {
+        let vested = self.vested_assets(now)?;
+        self.total_assets = self
+            .total_assets
+            .checked_add(vested)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        self.unvested_assets -= vested;
+        self.last_vesting_timestamp = now;
+        Ok(())
+    }
+
+    /// Adds donations or strategy yield to the vault. They vest linearly over `vesting_period`,
+    /// restarting the vesting of any assets still unvested.
+    pub fn add_unvested_assets(&mut self, amount: u64, now: i64) -> Result<()> {
+        if self.vesting_period == 0 {
+            self.total_assets = self
+                .total_assets
+                .checked_add(amount)
+                .ok_or(ErrorCode::ArithmeticOverflow)?;
+            return Ok(());
+        }
+        self.vest(now)?;
+        self.unvested_assets = self
+            .unvested_assets
+            .checked_add(amount)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        self.vesting_end = now
+            .checked_add(self.vesting_period)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        Ok(())
+    }
+
+    /// The exit fee on withdrawing `assets` for a depositor who last deposited at
+    /// `last_deposit_timestamp`, rounded up. Fails if the cooldown hasn't ended and the vault
+    /// charges no exit fee.
+    pub fn exit_fee(&self, assets: u64, last_deposit_timestamp: i64, now: i64) -> Result<u64> {
+        Self::fee_on(assets, self.exit_fee_rate(last_deposit_timestamp, now)?)
+    }
+
+    /// The exit fee rate in bps for a depositor who last deposited at `last_deposit_timestamp`.
+    /// Fails if the cooldown hasn't ended and the vault charges no exit fee.
+    pub fn exit_fee_rate(&self, last_deposit_timestamp: i64, now: i64) -> Result<u16> {
+        if now >= last_deposit_timestamp.saturating_add(self.lp_cooldown) {
+            return Ok(0);
+        }
+        require!(self.exit_fee_bps > 0, ErrorCode::LpCooldownActive);
+        Ok(self.exit_fee_bps)
+    }
+
+    /// `fee_bps` of `assets`, rounded up
+    pub fn fee_on(assets: u64, fee_bps: u16) -> Result<u64> {
+        Ok((assets as u128)
+            .checked_mul(fee_bps as u128)
+            .ok_or(ErrorCode::ArithmeticOverflow)?
+            .div_ceil(FEE_BPS_DENOMINATOR as u128)
+            .try_into()
+            .map_err(|_| ErrorCode::U64Overflow)?)
+    }
+
+    /// Ensures the admin's outstanding strategy borrows stay within `max_borrow`
+    pub fn validate_strategy_borrow(&self, amount: u64) -> Result<()> {
+        let total_borrowed = self
+            .total_borrowed
+            .checked_add(amount)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        require_gte!(
+            self.max_borrow,
+            total_borrowed,
+            ErrorCode::MaxBorrowExceeded
+        );
+        Ok(())
+    }
+
+    /// The assets the vault accounts for but holds neither in its `vault_balance` unborrowed
+    /// tokens nor in the withdrawal queue's reserve, i.e. the principal lent out to positions and
+    /// strategies
+    pub fn lent_out(&self, vault_balance: u64) -> u64 {
+        self.total_assets
+            .saturating_add(self.unvested_assets)
+            .saturating_sub(vault_balance)
+            .saturating_sub(self.queue_reserved_assets)
+    }
+
+    /// Ensures lending `amount` out of the vault's `vault_balance` unborrowed tokens keeps the
+    /// utilization within `max_utilization_bps`, leaving a buffer for LP withdrawals
+    pub fn validate_utilization(&self, vault_balance: u64, amount: u64) -> Result<()> {
+        let remaining = vault_balance
+            .checked_sub(amount)
+            .ok_or(ErrorCode::InsufficientAvailablePrincipal)?;
+        // Tokens reserved for the withdrawal queue are no longer in the vault but aren't borrowed
+        let borrowed = self
+            .total_assets
+            .saturating_sub(remaining)
+            .saturating_sub(self.queue_reserved_assets) as u128;
+        let max_borrowed = (self.total_assets as u128)
+            .checked_mul(self.max_utilization_bps as u128)
+            .ok_or(ErrorCode::ArithmeticOverflow)?
+            / UTILIZATION_BPS_DENOMINATOR as u128;
+        require_gte!(max_borrowed, borrowed, ErrorCode::MaxUtilizationExceeded);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lp_vault(total_assets: u64) -> LpVault {
+        let default = Pubkey::default();
+        LpVault {
+            total_assets,
+            ..LpVault::new(0, default, default, default, 0)
+        }
+    }
+
+    #[test]
+    fn test_previews_on_empty_vault() {
+        let vault = lp_vault(0);
+        assert_eq!(vault.preview_deposit(1_000, 0).unwrap(), 1_000);
+        assert_eq!(vault.preview_mint(1_000, 0).unwrap(), 1_000);
+        assert_eq!(vault.preview_redeem(0, 0).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_previews_round_in_favor_of_vault() {
+        // 2_000 shares backed by 4_000 assets, i.e. 3_000 virtual shares and 5_000 virtual assets
+        let vault = lp_vault(4_000);
+        assert_eq!(vault.convert_to_shares(4, 2_000).unwrap(), 2);
+        assert_eq!(vault.convert_to_assets(1, 2_000).unwrap(), 1);
+        assert_eq!(vault.preview_mint(1, 2_000).unwrap(), 2);
+        assert_eq!(vault.preview_redeem(3, 2_000).unwrap(), 5);
+        // (5 * 3_000 + 1) / 5_000
+        assert_eq!(vault.preview_withdraw(5, 2_000).unwrap(), 3);
+        // (5_000 * 3_000 + 5) / 5_000
+        assert_eq!(vault.preview_withdraw(5_000, 2_000).unwrap(), 3_000);
+    }
+
+    #[test]
+    fn test_inflation_attack_is_unprofitable() {
+        // The attacker makes the smallest first deposit, then donates to inflate the share price
+        let mut vault = lp_vault(0);
+        let attacker_deposit = MIN_FIRST_DEPOSIT;
+        let attacker_shares = vault.preview_deposit(attacker_deposit, 0).unwrap();
+        let mut supply = attacker_shares;
+        vault.total_assets = attacker_deposit;
+
+        for donation in [1_000u64, 1_000_000, 1_000_000_000, 1_000_000_000_000] {
+            let mut vault = lp_vault(vault.total_assets + donation);
+
+            // The victim's deposit is worth just under one share, the worst case for rounding
+            let victim_deposit = vault.preview_mint(1, supply).unwrap() - 1;
+            let victim_shares = vault.preview_deposit(victim_deposit, supply).unwrap();
+            vault.total_assets += victim_deposit;
+            let supply = supply + victim_shares;
+
+            let attacker_assets = vault.preview_redeem(attacker_shares, supply).unwrap();
+            assert!(
+                attacker_assets < attacker_deposit + donation,
+                "attacker profits with a donation of {donation}"
+            );
+        }
+
+        // Honest deposits into an untouched vault round trip without losing value
+        vault.total_assets = attacker_deposit;
+        let shares = vault.preview_deposit(5_000, supply).unwrap();
+        vault.total_assets += 5_000;
+        supply += shares;
+        assert_eq!(vault.preview_redeem(shares, supply).unwrap(), 5_000);
+    }
+
+    #[test]
+    fn test_validate_utilization() {
+        let mut vault = lp_vault(1_000);
+        vault.max_utilization_bps = 8_000;
+
+        // 300 already lent out, so 500 more reaches the 80% cap (800 of 1000)
+        assert!(vault.validate_utilization(700, 500).is_ok());
+        assert!(vault.validate_utilization(700, 501).is_err());
+        assert!(vault.validate_utilization(700, 701).is_err());
+
+        vault.max_utilization_bps = UTILIZATION_BPS_DENOMINATOR;
+        assert!(vault.validate_utilization(700, 700).is_ok());
+
+        // 200 of the 300 missing from the vault are reserved for queued withdrawals, so only 100
+        // is lent out
+        vault.max_utilization_bps = 8_000;
+        vault.queue_reserved_assets = 200;
+        assert!(vault.validate_utilization(700, 700).is_ok());
+        vault.queue_reserved_assets = 100;
+        assert!(vault.validate_utilization(700, 600).is_ok());
+        assert!(vault.validate_utilization(700, 601).is_err());
+    }
+
+    #[test]
+    fn test_crystallize_fees() {
+        let mut vault = lp_vault(99_000);
+        vault.performance_fee_bps = 1_000;
+        vault.management_fee_bps = 200;
+
+        // 10% of 10_000 interest
+        vault.accrue_performance_fee(10_000).unwrap();
+        assert_eq!(vault.pending_performance_fee, 1_000);
+
+        // 2% a year on 99_000 assets, accrued over half a year
+        let now = (ONE_YEAR_IN_SECONDS / 2) as i64;
+        assert_eq!(vault.compute_management_fee(now).unwrap(), 990);
+
+        let supply = 99_000;
+        let pending_shares = vault.pending_fee_shares(supply, now).unwrap();
+        assert_eq!(vault.pending_performance_fee, 1_000);
+
+        let accrual = vault.crystallize_fees(supply, now).unwrap();
+        assert_eq!(accrual.shares, pending_shares);
+        assert_eq!(accrual.performance_fee, 1_000);
+        assert_eq!(accrual.management_fee, 990);
+        assert_eq!(vault.pending_performance_fee, 0);
+        assert_eq!(vault.last_fee_timestamp, now);
+
+        // The minted shares are worth the fees, rounded down
+        let supply = supply + accrual.shares;
+        let value = vault.convert_to_assets(accrual.shares, supply).unwrap();
+        assert!((1_988..=1_990).contains(&value));
+
+        // Nothing more accrues without time passing
+        assert_eq!(vault.crystallize_fees(supply, now).unwrap().shares, 0);
+    }
+
+    #[test]
+    fn test_charges_fees() {
+        let mut vault = lp_vault(1_000);
+        assert!(!vault.charges_fees());
+
+        vault.management_fee_bps = 100;
+        assert!(vault.charges_fees());
+
+        // Performance fees accrued before the fees were turned off are still owed
+        vault.management_fee_bps = 0;
+        vault.pending_performance_fee = 1;
+        assert!(vault.charges_fees());
+    }
+
+    #[test]
+    fn test_validate_strategy_borrow() {
+        let mut vault = lp_vault(1_000);
+        vault.max_borrow = 500;
+        vault.total_borrowed = 200;
+        assert!(vault.validate_strategy_borrow(300).is_ok());
+        assert!(vault.validate_strategy_borrow(301).is_err());
+    }
+
+    #[test]
+    fn test_vesting() {
+        let mut vault = lp_vault(10_000);
+
+        // Without a vesting period assets are added instantly
+        vault.add_unvested_assets(1_000, 0).unwrap();
+        assert_eq!(vault.total_assets, 11_000);
+        assert_eq!(vault.unvested_assets, 0);
+
+        vault.vesting_period = 100;
+        vault.add_unvested_assets(1_000, 0).unwrap();
+        assert_eq!(vault.total_assets, 11_000);
+        assert_eq!(vault.vested_assets(25).unwrap(), 250);
+
+        vault.vest(50).unwrap();
+        assert_eq!(vault.total_assets, 11_500);
+        assert_eq!(vault.unvested_assets, 500);
+
+        // New yield restarts the window for everything still unvested
+        vault.add_unvested_assets(1_500, 60).unwrap();
+        assert_eq!(vault.total_assets, 11_600);
+        assert_eq!(vault.unvested_assets, 1_900);
+        assert_eq!(vault.vesting_end, 160);
+        assert_eq!(vault.vested_assets(110).unwrap(), 950);
+
+        vault.vest(1_000).unwrap();
+        assert_eq!(vault.total_assets, 13_500);
+        assert_eq!(vault.unvested_assets, 0);
+    }
+
+    #[test]
+    fn test_exit_fee() {
+        let mut vault = lp_vault(10_000);
+
+        // No cooldown
+        assert_eq!(vault.exit_fee(1_000, 100, 100).unwrap(), 0);
+
+        vault.lp_cooldown = 3_600;
+        assert!(vault.exit_fee(1_000, 100, 3_699).is_err());
+        assert_eq!(vault.exit_fee(1_000, 100, 3_700).unwrap(), 0);
+
+        vault.exit_fee_bps = 50;
+        assert_eq!(vault.exit_fee(1_000, 100, 3_699).unwrap(), 5);
+        assert_eq!(vault.exit_fee(1_001, 100, 3_699).unwrap(), 6);
+        assert_eq!(vault.exit_fee(1_000, 100, 3_700).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_new_successor() {
+        let mut vault = lp_vault(1_000);
+        vault.deposit_cap = 5_000;
+        vault.wallet_deposit_cap = 500;
+        vault.performance_fee_bps = 1_000;
+        vault.lp_cooldown = 3_600;
+        vault.exit_fee_bps = 50;
+        vault.allowlist_enabled = true;
+        vault.fee_shares_account = Pubkey::new_unique();
+        vault.decommissioned = true;
+
+        let successor = vault
+            .new_successor(1, Pubkey::new_unique(), Pubkey::new_unique(), 100)
+            .unwrap();
+        assert_eq!(successor.version, 1);
+        assert_eq!(successor.asset, vault.asset);
+        assert_eq!(successor.total_assets, 0);
+        assert_eq!(successor.deposit_cap, 5_000);
+        assert_eq!(successor.wallet_deposit_cap, 500);
+        assert_eq!(successor.performance_fee_bps, 1_000);
+        assert_eq!(successor.lp_cooldown, 3_600);
+        assert_eq!(successor.exit_fee_bps, 50);
+        assert!(successor.allowlist_enabled);
+        assert!(!successor.decommissioned);
+        assert_eq!(successor.fee_shares_account, Pubkey::default());
+        // Fees are charged but there is no fee shares account yet, so deposits can't be priced
+        assert!(successor.charges_fees());
+
+        vault.version = u8::MAX;
+        assert!(vault
+            .new_successor(1, Pubkey::new_unique(), Pubkey::new_unique(), 100)
+            .is_err());
+    }
+
+    #[test]
+    fn test_version_seed() {
+        let mut vault = lp_vault(0);
+        let asset = Pubkey::new_unique();
+        vault.asset = asset;
+
+        // The original vault keeps the address seeded by the asset only
+        let (legacy, bump) =
+            Pubkey::find_program_address(&[b"lp_vault", asset.as_ref()], &crate::ID);
+        vault.bump = bump;
+        assert_eq!(
+            Pubkey::create_program_address(crate::lp_vault_signer_seeds!(vault), &crate::ID)
+                .unwrap(),
+            legacy
+        );
+
+        let (successor, bump) =
+            Pubkey::find_program_address(&[b"lp_vault", asset.as_ref(), &[1]], &crate::ID);
+        vault.version = 1;
+        vault.bump = bump;
+        assert_ne!(successor, legacy);
+        assert_eq!(
+            Pubkey::create_program_address(crate::lp_vault_signer_seeds!(vault), &crate::ID)
+                .unwrap(),
+            successor
+        );
+    }
+
+    #[test]
+    fn test_decommissioned_vault() {
+        let mut vault = lp_vault(0);
+        vault.decommissioned = true;
+        assert!(!vault.can_deposit());
+        assert!(!vault.can_borrow());
+        assert!(vault.can_withdraw());
+    }
+
+    #[test]
+    fn test_lent_out() {
+        let mut vault = lp_vault(1_000);
+        vault.unvested_assets = 100;
+        assert_eq!(vault.lent_out(1_100), 0);
+        // Rounding dust and direct transfers stay in the vault
+        assert_eq!(vault.lent_out(1_105), 0);
+        // 300 principal is still with open positions
+        assert_eq!(vault.lent_out(800), 300);
+        // Tokens reserved for queued withdrawals aren't lent out
+        vault.queue_reserved_assets = 300;
+        assert_eq!(vault.lent_out(800), 0);
+    }
+
+    #[test]
+    fn test_migrate_v0_vault() {
+        let legacy = LpVaultV0 {
+            bump: 254,
+            asset: Pubkey::new_unique(),
+            vault: Pubkey::new_unique(),
+            shares_mint: Pubkey::new_unique(),
+            total_assets: 1_000,
+            max_borrow: 500,
+            total_borrowed: 200,
+        };
+        let mut data = LpVault::DISCRIMINATOR.to_vec();
+        legacy.serialize(&mut data).unwrap();
+        assert_eq!(data.len(), LpVaultV0::LEN);
+
+        // The baseline-sized account can't be loaded with the current layout
+        assert!(LpVault::try_deserialize(&mut &data[..]).is_err());
+
+        let vault = LpVaultV0::try_from_account_data(&data)
+            .unwrap()
+            .migrate(100);
+        assert_eq!(vault.bump, 254);
+        assert_eq!(vault.asset, legacy.asset);
+        assert_eq!(vault.vault, legacy.vault);
+        assert_eq!(vault.shares_mint, legacy.shares_mint);
+        assert_eq!(vault.total_assets, 1_000);
+        assert_eq!(vault.max_borrow, 500);
+        assert_eq!(vault.total_borrowed, 200);
+        // The added fields don't restrict the vault
+        assert_eq!(vault.deposit_cap, u64::MAX);
+        assert_eq!(vault.wallet_deposit_cap, 0);
+        assert_eq!(vault.max_utilization_bps, UTILIZATION_BPS_DENOMINATOR);
+        assert!(vault.can_deposit() && vault.can_withdraw() && vault.can_borrow());
+        assert_eq!(vault.performance_fee_bps, 0);
+        assert_eq!(vault.management_fee_bps, 0);
+        assert_eq!(vault.last_fee_timestamp, 100);
+        assert_eq!(vault.vesting_period, 0);
+        assert_eq!(vault.last_vesting_timestamp, 100);
+        assert_eq!(vault.lp_cooldown, 0);
+        assert!(!vault.allowlist_enabled);
+        assert_eq!(vault.version_seed(), &[] as &[u8]);
+        assert!(!vault.decommissioned);
+
+        let mut migrated = Vec::new();
+        vault.try_serialize(&mut migrated).unwrap();
+        assert!(LpVault::try_deserialize(&mut &migrated[..]).is_ok());
+
+        // Migrated vaults are rejected
+        assert!(LpVaultV0::try_from_account_data(&migrated).is_err());
+    }
 }
@@ -0,0 +1,180 @@
+use {
+    crate::error::ErrorCode,
+    anchor_lang::prelude::*,
+    anchor_spl::token_2022::spl_token_2022::{
+        extension::{
+            default_account_state::DefaultAccountState, mint_close_authority::MintCloseAuthority,
+            non_transferable::NonTransferable, permanent_delegate::PermanentDelegate,
+            transfer_hook::TransferHook, BaseStateWithExtensions, StateWithExtensions,
+        },
+        state::{AccountState, Mint},
+    },
+};
+
+pub const MINT_FREEZE_AUTHORITY: u16 = 0b0000000000000001;
+pub const MINT_PERMANENT_DELEGATE: u16 = 0b0000000000000010;
+pub const MINT_TRANSFER_HOOK: u16 = 0b0000000000000100;
+pub const MINT_NON_TRANSFERABLE: u16 = 0b0000000000001000;
+pub const MINT_DEFAULT_FROZEN: u16 = 0b0000000000010000;
+pub const MINT_CLOSE_AUTHORITY: u16 = 0b0000000000100000;
+pub const MINT_FEATURES: u16 = MINT_FREEZE_AUTHORITY
+    | MINT_PERMANENT_DELEGATE
+    | MINT_TRANSFER_HOOK
+    | MINT_NON_TRANSFERABLE
+    | MINT_DEFAULT_FROZEN
+    | MINT_CLOSE_AUTHORITY;
+
+/// Mint features that are rejected when an LP Vault or pool is initialized. Without a policy
+/// account every feature is rejected.
+#[account]
+pub struct MintPolicy {
+    /// Bit mapping of the rejected mint features
+    pub rejected_features: u16,
+    /// The bump seed for this PDA
+    pub bump: u8,
+}
+
+impl Default for MintPolicy {
+    fn default() -> Self {
+        Self {
+            rejected_features: MINT_FEATURES,
+            bump: 0,
+        }
+    }
+}
+
+impl MintPolicy {
+    /// Fails if the mint has any of the rejected features
+    pub fn validate_mint(&self, mint: &AccountInfo) -> Result<()> {
+        require!(
+            mint_features(mint)? & self.rejected_features == 0,
+            ErrorCode::MintNotAllowed
+        );
+        Ok(())
+    }
+}
+
+/// A mint approved by the super admin that is exempt from the `MintPolicy`
+#[account]
+pub struct AllowedMint {
+    pub mint: Pubkey,
+    /// The bump seed for this PDA
+    pub bump: u8,
+}
+
+/// Validates `mint` against the policy, unless it is on the allowlist
+pub fn validate_mint(
+    mint: &AccountInfo,
+    mint_policy: Option<&MintPolicy>,
+    allowed_mint: Option<&AllowedMint>,
+) -> Result<()> {
+    if allowed_mint.is_some() {
+        return Ok(());
+    }
+    match mint_policy {
+        Some(mint_policy) => mint_policy.validate_mint(mint),
+        None => MintPolicy::default().validate_mint(mint),
+    }
+}
+
+/// Returns the bit mapping of the risky features of a legacy or Token-2022 mint
+pub fn mint_features(mint: &AccountInfo) -> Result<u16> {
+    let data = mint.try_borrow_data()?;
+    features_of(&data)
+}
+
+fn features_of(data: &[u8]) -> Result<u16> {
+    let mint = StateWithExtensions::<Mint>::unpack(data)?;
+    let mut features = 0;
+
+    if mint.base.freeze_authority.is_some() {
+        features |= MINT_FREEZE_AUTHORITY;
+    }
+    if let Ok(extension) = mint.get_extension::<PermanentDelegate>() {
+        if Option::<Pubkey>::from(extension.delegate).is_some() {
+            features |= MINT_PERMANENT_DELEGATE;
+        }
+    }
+    if let Ok(extension) = mint.get_extension::<TransferHook>() {
+        if Option::<Pubkey>::from(extension.program_id).is_some() {
+            features |= MINT_TRANSFER_HOOK;
+        }
+    }
+    if mint.get_extension::<NonTransferable>().is_ok() {
+        features |= MINT_NON_TRANSFERABLE;
+    }
+    if let Ok(extension) = mint.get_extension::<DefaultAccountState>() {
+        if extension.state == AccountState::Frozen as u8 {
+            features |= MINT_DEFAULT_FROZEN;
+        }
+    }
+    if let Ok(extension) = mint.get_extension::<MintCloseAuthority>() {
+        if Option::<Pubkey>::from(extension.close_authority).is_some() {
+            features |= MINT_CLOSE_AUTHORITY;
+        }
+    }
+
+    Ok(features)
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        anchor_lang::solana_program::{program_option::COption, program_pack::Pack},
+    };
+
+    fn legacy_mint(freeze_authority: COption<Pubkey>) -> Vec<u8> {
+        let mut data = vec![0; Mint::LEN];
+        Mint {
+            mint_authority: COption::Some(Pubkey::new_unique()),
+            supply: 0,
+            decimals: 6,
+            is_initialized: true,
+            freeze_authority,
+        }
+        .pack_into_slice(&mut data);
+        data
+    }
+
+    #[test]
+    fn test_legacy_mint_features() {
+        assert_eq!(features_of(&legacy_mint(COption::None)).unwrap(), 0);
+        assert_eq!(
+            features_of(&legacy_mint(COption::Some(Pubkey::new_unique()))).unwrap(),
+            MINT_FREEZE_AUTHORITY
+        );
+    }
+
+    #[test]
+    fn test_validate_mint() {
+        let key = Pubkey::new_unique();
+        let owner = anchor_spl::token::ID;
+        let mut lamports = 0;
+        let mut data = legacy_mint(COption::Some(Pubkey::new_unique()));
+        let mint = AccountInfo::new(
+            &key,
+            false,
+            false,
+            &mut lamports,
+            &mut data,
+            &owner,
+            false,
+            0,
+        );
+
+        // Rejected by the default policy
+        assert!(validate_mint(&mint, None, None).is_err());
+
+        // Accepted by a policy that allows freeze authorities
+        let policy = MintPolicy {
+            rejected_features: MINT_FEATURES & !MINT_FREEZE_AUTHORITY,
+            bump: 0,
+        };
+        assert!(validate_mint(&mint, Some(&policy), None).is_ok());
+
+        // Accepted when allowlisted
+        let allowed_mint = AllowedMint { mint: key, bump: 0 };
+        assert!(validate_mint(&mint, None, Some(&allowed_mint)).is_ok());
+    }
+}
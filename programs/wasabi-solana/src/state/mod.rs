@@ -1,25 +1,41 @@
+pub mod allowlisted_wallet;
 pub mod base_pool;
 pub mod close_position_request;
 pub mod debt_controller;
+pub mod depositor;
+pub mod exit_order;
 pub mod global_settings;
+pub mod limit_open_order;
 pub mod lp_vault;
+pub mod mint_policy;
 pub mod open_position_request;
 pub mod permission;
 pub mod position;
+pub mod reward_pool;
+pub mod share_price_history;
 pub mod stop_loss_order;
 pub mod strategy;
 pub mod strategy_request;
 pub mod take_profit_order;
+pub mod withdrawal_queue;
 
+pub use allowlisted_wallet::*;
 pub use base_pool::*;
 pub use close_position_request::*;
 pub use debt_controller::*;
+pub use depositor::*;
+pub use exit_order::*;
 pub use global_settings::*;
+pub use limit_open_order::*;
 pub use lp_vault::*;
+pub use mint_policy::*;
 pub use open_position_request::*;
 pub use permission::*;
 pub use position::*;
+pub use reward_pool::*;
+pub use share_price_history::*;
 pub use stop_loss_order::*;
 pub use strategy::*;
 pub use strategy_request::*;
 pub use take_profit_order::*;
+pub use withdrawal_queue::*;
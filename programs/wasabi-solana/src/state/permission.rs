@@ -12,6 +12,7 @@ pub const LIQUIDATE_PERMISSION: u8 = 0b00000010;
 pub const COSIGN_PERMISSION: u8 = 0b00000100;
 pub const INIT_POOL_PERMISSION: u8 = 0b00001000;
 pub const VAULT_BORROW_PERMISSION: u8 = 0b00010000;
+pub const VAULT_MANAGER_PERMISSION: u8 = 0b00100000;
 
 #[account]
 pub struct Permission {
@@ -49,4 +50,10 @@ impl Permission {
         self.permissions_map & VAULT_BORROW_PERMISSION == VAULT_BORROW_PERMISSION
             || self.is_super_authority
     }
+
+    /// True if the authority can manage the wallets allowed into permissioned vaults
+    pub fn can_manage_vaults(&self) -> bool {
+        self.permissions_map & VAULT_MANAGER_PERMISSION == VAULT_MANAGER_PERMISSION
+            || self.is_super_authority
+    }
 }
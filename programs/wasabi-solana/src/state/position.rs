@@ -26,6 +26,48 @@ pub struct Position {
 }
 
 impl Position {
+    /// Validates an exit order's `maker_amount` / `taker_amount` against the position.
+    /// `interest` is the interest currently owed on the principal.
+    pub fn validate_exit_order(
+        &self,
+        is_long: bool,
+        maker_amount: u64,
+        taker_amount: u64,
+        interest: u64,
+    ) -> Result<()> {
+        require_gt!(maker_amount, 0, ErrorCode::ZeroAmount);
+        require_gt!(taker_amount, 0, ErrorCode::ZeroAmount);
+
+        let debt = self
+            .principal
+            .checked_add(interest)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+
+        if is_long {
+            // All the collateral is sold on close, so an order can't sell more than the position
+            require_gte!(
+                self.collateral_amount,
+                maker_amount,
+                ErrorCode::InvalidExitOrder
+            );
+            // The currency received (`taker_amount`) must at least repay principal and interest
+            require_gte!(taker_amount, debt, ErrorCode::BadDebt);
+        } else {
+            // At the order price (maker_amount / taker_amount) the collateral must be able to buy
+            // back the principal and interest:
+            //      debt * maker_amount / taker_amount <= collateral_amount
+            let debt_in_collateral = (debt as u128)
+                .checked_mul(maker_amount as u128)
+                .ok_or(ErrorCode::ArithmeticOverflow)?;
+            let collateral = (self.collateral_amount as u128)
+                .checked_mul(taker_amount as u128)
+                .ok_or(ErrorCode::ArithmeticOverflow)?;
+            require_gte!(collateral, debt_in_collateral, ErrorCode::BadDebt);
+        }
+
+        Ok(())
+    }
+
     pub fn compute_close_fee(&self, net_value: u64, is_long: bool) -> Result<u64> {
         let net_value_u128 = net_value as u128;
         let fees_to_be_paid_u128 = self.fees_to_be_paid as u128;
@@ -96,3 +138,52 @@ impl Position {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn position(principal: u64, collateral_amount: u64) -> Position {
+        Position {
+            trader: Pubkey::default(),
+            currency: Pubkey::default(),
+            collateral: Pubkey::default(),
+            last_funding_timestamp: 0,
+            down_payment: 1_000,
+            principal,
+            collateral_amount,
+            fees_to_be_paid: 0,
+            collateral_vault: Pubkey::default(),
+            lp_vault: Pubkey::default(),
+        }
+    }
+
+    #[test]
+    fn test_validate_long_exit_order() {
+        let position = position(2_000, 3_000);
+        assert!(position.validate_exit_order(true, 3_000, 2_010, 10).is_ok());
+        // Zero amounts
+        assert!(position.validate_exit_order(true, 0, 2_010, 10).is_err());
+        assert!(position.validate_exit_order(true, 3_000, 0, 10).is_err());
+        // Sells more than the collateral held
+        assert!(position
+            .validate_exit_order(true, 3_001, 2_010, 10)
+            .is_err());
+        // Doesn't cover principal + interest
+        assert!(position
+            .validate_exit_order(true, 3_000, 2_009, 10)
+            .is_err());
+    }
+
+    #[test]
+    fn test_validate_short_exit_order() {
+        // Borrowed 2_000 currency, holding 3_000 collateral
+        let position = position(2_000, 3_000);
+        // 1 collateral per currency: 2_010 debt costs 2_010 collateral
+        assert!(position.validate_exit_order(false, 1, 1, 10).is_ok());
+        // 3 collateral per 2 currency: 2_010 debt costs 3_015 collateral
+        assert!(position.validate_exit_order(false, 3, 2, 10).is_err());
+        // 3 collateral per 2 currency: 2_000 debt costs 3_000 collateral
+        assert!(position.validate_exit_order(false, 3, 2, 0).is_ok());
+    }
+}
@@ -0,0 +1,212 @@
+use {crate::error::ErrorCode, anchor_lang::prelude::*};
+
+/// Precision of `RewardPool::reward_per_share`
+pub const REWARD_PER_SHARE_SCALE: u128 = 1_000_000_000_000;
+
+/// Emits a reward token to the LPs staking their shares of an LP Vault.
+///
+/// Rewards are tracked with a reward-per-share accumulator: `reward_per_share` grows by the
+/// rewards emitted per staked share, and each `RewardStake` is owed the growth since it last
+/// settled. Emissions stop while nothing is staked or once the funded rewards run out.
+#[account]
+pub struct RewardPool {
+    /// The LP Vault whose shares are staked
+    pub lp_vault: Pubkey,
+    /// The mint of the staked shares
+    pub shares_mint: Pubkey,
+    /// The mint of the reward token
+    pub reward_mint: Pubkey,
+    /// Token account owned by this pool that holds the funded, unclaimed rewards
+    pub reward_vault: Pubkey,
+    /// Token account owned by this pool that holds the staked shares
+    pub stake_vault: Pubkey,
+    /// Reward tokens emitted per second
+    pub emission_rate: u64,
+    /// Funded rewards that have not been emitted yet
+    pub remaining_rewards: u64,
+    /// Total shares staked
+    pub total_staked: u64,
+    /// Rewards emitted per staked share, scaled by `REWARD_PER_SHARE_SCALE`
+    pub reward_per_share: u128,
+    /// The last time rewards were emitted
+    pub last_update_timestamp: i64,
+    /// The bump seed for this PDA
+    pub bump: u8,
+}
+
+impl RewardPool {
+    /// Emits the rewards accrued since the last update
+    pub fn update(&mut self, now: i64) -> Result<()> {
+        let elapsed = now.saturating_sub(self.last_update_timestamp).max(0) as u128;
+        self.last_update_timestamp = now;
+        if self.total_staked == 0 {
+            return Ok(());
+        }
+
+        let emitted = (self.emission_rate as u128)
+            .checked_mul(elapsed)
+            .ok_or(ErrorCode::ArithmeticOverflow)?
+            .min(self.remaining_rewards as u128);
+        self.reward_per_share = self
+            .reward_per_share
+            .checked_add(
+                emitted
+                    .checked_mul(REWARD_PER_SHARE_SCALE)
+                    .ok_or(ErrorCode::ArithmeticOverflow)?
+                    / self.total_staked as u128,
+            )
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        // `emitted` is at most `remaining_rewards`
+        self.remaining_rewards -= emitted as u64;
+
+        Ok(())
+    }
+
+    /// The rewards accrued by `staked` shares since `reward_per_share` was at `reward_debt`
+    fn accrued(&self, staked: u64) -> Result<u128> {
+        Ok((staked as u128)
+            .checked_mul(self.reward_per_share)
+            .ok_or(ErrorCode::ArithmeticOverflow)?
+            / REWARD_PER_SHARE_SCALE)
+    }
+}
+
+/// An LP's stake in a `RewardPool`
+#[account]
+pub struct RewardStake {
+    /// The wallet that staked the shares and receives the rewards
+    pub owner: Pubkey,
+    /// The pool staked into
+    pub reward_pool: Pubkey,
+    /// Shares staked
+    pub staked: u64,
+    /// The accrued rewards of `staked` shares that were settled already
+    pub reward_debt: u128,
+    /// Rewards settled but not claimed yet
+    pub pending_rewards: u64,
+    /// The bump seed for this PDA
+    pub bump: u8,
+}
+
+impl RewardStake {
+    /// Moves the rewards accrued since the last settlement to `pending_rewards`. The pool must be
+    /// updated first.
+    pub fn settle(&mut self, reward_pool: &RewardPool) -> Result<()> {
+        let accrued = reward_pool
+            .accrued(self.staked)?
+            .checked_sub(self.reward_debt)
+            .ok_or(ErrorCode::ArithmeticUnderflow)?;
+        self.pending_rewards = self
+            .pending_rewards
+            .checked_add(accrued.try_into().map_err(|_| ErrorCode::U64Overflow)?)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        self.reward_debt = reward_pool.accrued(self.staked)?;
+        Ok(())
+    }
+
+    /// Settles the stake, then adds `amount` shares to it
+    pub fn stake(&mut self, reward_pool: &mut RewardPool, amount: u64) -> Result<()> {
+        self.settle(reward_pool)?;
+        self.staked = self
+            .staked
+            .checked_add(amount)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        reward_pool.total_staked = reward_pool
+            .total_staked
+            .checked_add(amount)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        self.reward_debt = reward_pool.accrued(self.staked)?;
+        Ok(())
+    }
+
+    /// Settles the stake, then removes `amount` shares from it
+    pub fn unstake(&mut self, reward_pool: &mut RewardPool, amount: u64) -> Result<()> {
+        self.settle(reward_pool)?;
+        require_gte!(self.staked, amount, ErrorCode::InsufficientStake);
+        self.staked -= amount;
+        reward_pool.total_staked = reward_pool
+            .total_staked
+            .checked_sub(amount)
+            .ok_or(ErrorCode::ArithmeticUnderflow)?;
+        self.reward_debt = reward_pool.accrued(self.staked)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reward_pool(emission_rate: u64, remaining_rewards: u64) -> RewardPool {
+        let default = Pubkey::default();
+        RewardPool {
+            lp_vault: default,
+            shares_mint: default,
+            reward_mint: default,
+            reward_vault: default,
+            stake_vault: default,
+            emission_rate,
+            remaining_rewards,
+            total_staked: 0,
+            reward_per_share: 0,
+            last_update_timestamp: 0,
+            bump: 0,
+        }
+    }
+
+    fn reward_stake() -> RewardStake {
+        RewardStake {
+            owner: Pubkey::default(),
+            reward_pool: Pubkey::default(),
+            staked: 0,
+            reward_debt: 0,
+            pending_rewards: 0,
+            bump: 0,
+        }
+    }
+
+    #[test]
+    fn test_rewards_split_by_stake() {
+        let mut pool = reward_pool(100, 1_000_000);
+        let mut alice = reward_stake();
+        let mut bob = reward_stake();
+
+        // Nothing is emitted while nothing is staked
+        pool.update(10).unwrap();
+        alice.stake(&mut pool, 1_000).unwrap();
+        assert_eq!(pool.remaining_rewards, 1_000_000);
+
+        // Alice earns everything for 10 seconds, then shares with Bob 1:3 for 20 seconds
+        pool.update(20).unwrap();
+        bob.stake(&mut pool, 3_000).unwrap();
+        pool.update(40).unwrap();
+        alice.settle(&pool).unwrap();
+        bob.settle(&pool).unwrap();
+        assert_eq!(alice.pending_rewards, 1_000 + 500);
+        assert_eq!(bob.pending_rewards, 1_500);
+        assert_eq!(pool.remaining_rewards, 1_000_000 - 3_000);
+
+        // Settling twice doesn't pay twice
+        alice.settle(&pool).unwrap();
+        assert_eq!(alice.pending_rewards, 1_500);
+
+        bob.unstake(&mut pool, 3_000).unwrap();
+        assert!(bob.unstake(&mut pool, 1).is_err());
+        pool.update(50).unwrap();
+        alice.settle(&pool).unwrap();
+        assert_eq!(alice.pending_rewards, 2_500);
+    }
+
+    #[test]
+    fn test_emissions_capped_by_funding() {
+        let mut pool = reward_pool(100, 250);
+        let mut alice = reward_stake();
+        alice.stake(&mut pool, 7).unwrap();
+
+        pool.update(10).unwrap();
+        alice.settle(&pool).unwrap();
+        assert_eq!(pool.remaining_rewards, 0);
+        // Rounded down by the accumulator
+        assert!((249..=250).contains(&alice.pending_rewards));
+    }
+}
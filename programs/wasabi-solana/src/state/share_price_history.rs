@@ -0,0 +1,165 @@
+use {
+    crate::{error::ErrorCode, LpVault},
+    anchor_lang::prelude::*,
+};
+
+/// Number of checkpoints kept by a `SharePriceHistory`
+pub const SHARE_PRICE_CHECKPOINTS: usize = 32;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct SharePriceCheckpoint {
+    pub slot: u64,
+    pub timestamp: i64,
+    /// Assets per `SHARE_PRICE_SCALE` shares
+    pub share_price: u64,
+}
+
+/// Ring buffer of an LP Vault's share price, recorded when the vault's assets or shares change.
+/// Integrators can read a time weighted average price through `share_price_twap`.
+#[account]
+pub struct SharePriceHistory {
+    /// The LP Vault whose share price is recorded
+    pub lp_vault: Pubkey,
+    /// Index of the most recent checkpoint
+    pub head: u16,
+    /// Number of checkpoints recorded, up to `SHARE_PRICE_CHECKPOINTS`
+    pub len: u16,
+    /// The bump seed for this PDA
+    pub bump: u8,
+    pub checkpoints: [SharePriceCheckpoint; SHARE_PRICE_CHECKPOINTS],
+}
+
+impl SharePriceHistory {
+    pub fn new(lp_vault: Pubkey, bump: u8) -> Self {
+        Self {
+            lp_vault,
+            head: 0,
+            len: 0,
+            bump,
+            checkpoints: [SharePriceCheckpoint::default(); SHARE_PRICE_CHECKPOINTS],
+        }
+    }
+
+    /// The checkpoint recorded `age` checkpoints before the most recent one
+    fn get(&self, age: usize) -> &SharePriceCheckpoint {
+        let index = (self.head as usize + SHARE_PRICE_CHECKPOINTS - age) % SHARE_PRICE_CHECKPOINTS;
+        &self.checkpoints[index]
+    }
+
+    pub fn latest(&self) -> Option<&SharePriceCheckpoint> {
+        (self.len > 0).then(|| self.get(0))
+    }
+
+    /// Records `share_price`. Only the first checkpoint of a slot is kept, so a single slot can't
+    /// evict the history and later operations in the slot can't choose its price.
+    pub fn record(&mut self, share_price: u64, slot: u64, timestamp: i64) {
+        let checkpoint = SharePriceCheckpoint {
+            slot,
+            timestamp,
+            share_price,
+        };
+        match self.latest() {
+            Some(latest) if latest.slot == slot => return,
+            Some(_) => {
+                self.head = ((self.head as usize + 1) % SHARE_PRICE_CHECKPOINTS) as u16;
+                self.len = self
+                    .len
+                    .saturating_add(1)
+                    .min(SHARE_PRICE_CHECKPOINTS as u16);
+            }
+            None => self.len = 1,
+        }
+        self.checkpoints[self.head as usize] = checkpoint;
+    }
+
+    /// Records the LP Vault's current share price, counting only vested assets
+    pub fn checkpoint(&mut self, lp_vault: &LpVault, shares_supply: u64) -> Result<()> {
+        let clock = Clock::get()?;
+        let mut lp_vault = lp_vault.clone();
+        lp_vault.vest(clock.unix_timestamp)?;
+        self.record(
+            lp_vault.share_price(shares_supply)?,
+            clock.slot,
+            clock.unix_timestamp,
+        );
+        Ok(())
+    }
+
+    /// Time weighted average share price over the `window` seconds before `now`. Each checkpoint's
+    /// price holds until the next checkpoint, and the most recent one holds until `now`.
+    pub fn twap(&self, window: u32, now: i64) -> Result<u64> {
+        require_gt!(window, 0, ErrorCode::InvalidTwapWindow);
+        let window_start = now
+            .checked_sub(window as i64)
+            .ok_or(ErrorCode::ArithmeticUnderflow)?;
+
+        let mut weighted_sum: u128 = 0;
+        let mut end = now;
+        for age in 0..self.len as usize {
+            let checkpoint = self.get(age);
+            let start = checkpoint.timestamp.max(window_start);
+            if end > start {
+                weighted_sum = weighted_sum
+                    .checked_add(checkpoint.share_price as u128 * (end - start) as u128)
+                    .ok_or(ErrorCode::ArithmeticOverflow)?;
+            }
+            if checkpoint.timestamp <= window_start {
+                return Ok((weighted_sum / window as u128) as u64);
+            }
+            end = checkpoint.timestamp;
+        }
+
+        err!(ErrorCode::InsufficientPriceHistory)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_keeps_first_checkpoint_in_slot() {
+        let mut history = SharePriceHistory::new(Pubkey::default(), 0);
+        assert!(history.latest().is_none());
+
+        history.record(100, 1, 10);
+        history.record(110, 1, 10);
+        assert_eq!(history.len, 1);
+        assert_eq!(history.latest().unwrap().share_price, 100);
+
+        history.record(120, 2, 11);
+        assert_eq!(history.len, 2);
+        assert_eq!(history.latest().unwrap().share_price, 120);
+    }
+
+    #[test]
+    fn test_record_wraps_around() {
+        let mut history = SharePriceHistory::new(Pubkey::default(), 0);
+        for i in 0..SHARE_PRICE_CHECKPOINTS as u64 + 5 {
+            history.record(i, i, i as i64);
+        }
+        assert_eq!(history.len as usize, SHARE_PRICE_CHECKPOINTS);
+        assert_eq!(
+            history.latest().unwrap().share_price,
+            SHARE_PRICE_CHECKPOINTS as u64 + 4
+        );
+        assert_eq!(history.get(SHARE_PRICE_CHECKPOINTS - 1).share_price, 5);
+    }
+
+    #[test]
+    fn test_twap() {
+        let mut history = SharePriceHistory::new(Pubkey::default(), 0);
+        history.record(100, 1, 0);
+        history.record(200, 2, 60);
+
+        // 100 for 60s then 200 for 60s
+        assert_eq!(history.twap(120, 120).unwrap(), 150);
+        // Only the most recent checkpoint is within the window
+        assert_eq!(history.twap(30, 120).unwrap(), 200);
+        // 100 for 30s then 200 for 60s
+        assert_eq!(history.twap(90, 120).unwrap(), 166);
+
+        assert!(history.twap(121, 120).is_err());
+        assert!(history.twap(0, 120).is_err());
+    }
+}
@@ -1,4 +1,7 @@
-use anchor_lang::prelude::*;
+use {
+    crate::{ExitOrderPrice, OrderSide},
+    anchor_lang::prelude::*,
+};
 
 #[account]
 pub struct StopLossOrder {
@@ -8,4 +11,12 @@ pub struct StopLossOrder {
     pub maker_amount: u64,
     /// The amount that will be bought to close the position (is in `position.currency`)
     pub taker_amount: u64,
+    /// The maximum execution fee (in lamports) the executor can be paid when filling the order.
+    /// It is prefunded by the trader and held by this account on top of its rent.
+    pub execution_fee: u64,
+    /// The normalized price the order executes at, i.e. `taker_amount / maker_amount` adjusted
+    /// for mint decimals
+    pub price: ExitOrderPrice,
+    /// The side of the position, which determines how the executed amounts are measured
+    pub side: OrderSide,
 }
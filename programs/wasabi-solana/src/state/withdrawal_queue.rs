@@ -0,0 +1,222 @@
+use {crate::error::ErrorCode, anchor_lang::prelude::*};
+
+/// FIFO queue of withdrawals that couldn't be served from the LP Vault's unborrowed tokens.
+///
+/// Requested shares are escrowed rather than burned, so they keep sharing the vault's gains and
+/// losses until their request is filled at the share price of the time. Repayments are reserved
+/// in `queue_vault` up to the value of the pending requests so they can't be lent out again, and
+/// requests are filled one at a time in the order they were made.
+#[account]
+pub struct WithdrawalQueue {
+    /// The LP Vault this queue belongs to
+    pub lp_vault: Pubkey,
+    /// Token account owned by this queue that holds the reserved and the filled, unclaimed assets
+    pub queue_vault: Pubkey,
+    /// Shares token account owned by this queue that escrows the shares of pending requests
+    pub shares_escrow: Pubkey,
+    /// Amount of shares escrowed by pending requests
+    pub pending_shares: u64,
+    /// Value of the pending requests when they were made, capping the reserve
+    pub pending_assets: u64,
+    /// Amount of assets in `queue_vault` reserved for pending requests
+    pub reserved_assets: u64,
+    /// Amount of assets in `queue_vault` owed to filled requests
+    pub unclaimed_assets: u64,
+    /// The id of the next withdrawal request
+    pub next_request_id: u64,
+    /// The id of the next withdrawal request to fill
+    pub next_fill_id: u64,
+    /// The bump seed for this PDA
+    pub bump: u8,
+}
+
+impl WithdrawalQueue {
+    /// Adds `request` to the back of the queue
+    pub fn enqueue(&mut self, request: &WithdrawalRequest) -> Result<()> {
+        require_eq!(
+            request.id,
+            self.next_request_id,
+            ErrorCode::WithdrawalRequestNotNext
+        );
+        self.pending_shares = self
+            .pending_shares
+            .checked_add(request.shares)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        self.pending_assets = self
+            .pending_assets
+            .checked_add(request.requested_assets)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        self.next_request_id = self
+            .next_request_id
+            .checked_add(1)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        Ok(())
+    }
+
+    /// The amount of up to `available` assets the pending requests still need reserved
+    pub fn reservable(&self, available: u64) -> u64 {
+        std::cmp::min(
+            self.pending_assets.saturating_sub(self.reserved_assets),
+            available,
+        )
+    }
+
+    /// Reserves up to `available` assets for pending requests, returning the amount reserved
+    pub fn reserve(&mut self, available: u64) -> Result<u64> {
+        let amount = self.reservable(available);
+        self.reserved_assets = self
+            .reserved_assets
+            .checked_add(amount)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        Ok(amount)
+    }
+
+    /// Fills the request at the front of the queue with `assets`. Returns the amount taken from
+    /// the reserve and the amount of reserve released because it exceeds the value of the
+    /// requests still pending.
+    pub fn fill(&mut self, request: &mut WithdrawalRequest, assets: u64) -> Result<(u64, u64)> {
+        require_eq!(
+            request.id,
+            self.next_fill_id,
+            ErrorCode::WithdrawalRequestNotNext
+        );
+        self.pending_shares = self
+            .pending_shares
+            .checked_sub(request.shares)
+            .ok_or(ErrorCode::ArithmeticUnderflow)?;
+        self.pending_assets = self
+            .pending_assets
+            .checked_sub(request.requested_assets)
+            .ok_or(ErrorCode::ArithmeticUnderflow)?;
+        self.unclaimed_assets = self
+            .unclaimed_assets
+            .checked_add(assets)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        self.next_fill_id = self
+            .next_fill_id
+            .checked_add(1)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        request.assets = assets;
+
+        let from_reserve = std::cmp::min(self.reserved_assets, assets);
+        self.reserved_assets -= from_reserve;
+        let released = self.reserved_assets.saturating_sub(self.pending_assets);
+        self.reserved_assets -= released;
+
+        Ok((from_reserve, released))
+    }
+
+    /// True once `request` has been filled
+    pub fn is_filled(&self, request: &WithdrawalRequest) -> bool {
+        request.id < self.next_fill_id
+    }
+}
+
+/// A withdrawal waiting in the `WithdrawalQueue`. The owner's shares are escrowed when the
+/// request is made and redeemed when it is filled.
+#[account]
+pub struct WithdrawalRequest {
+    /// Wallet that made the request and receives the assets
+    pub owner: Pubkey,
+    /// The LP Vault the shares are redeemed from
+    pub lp_vault: Pubkey,
+    /// Sequential id of the request within the queue
+    pub id: u64,
+    /// The amount of shares escrowed
+    pub shares: u64,
+    /// The value of the shares when the request was made
+    pub requested_assets: u64,
+    /// The exit fee charged when filled, in bps, as of when the request was made
+    pub exit_fee_bps: u16,
+    /// The amount of assets owed, set once filled
+    pub assets: u64,
+    /// The bump seed for this PDA
+    pub bump: u8,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(id: u64, requested_assets: u64) -> WithdrawalRequest {
+        WithdrawalRequest {
+            owner: Pubkey::default(),
+            lp_vault: Pubkey::default(),
+            id,
+            shares: requested_assets,
+            requested_assets,
+            exit_fee_bps: 0,
+            assets: 0,
+            bump: 0,
+        }
+    }
+
+    fn queue() -> WithdrawalQueue {
+        WithdrawalQueue {
+            lp_vault: Pubkey::default(),
+            queue_vault: Pubkey::default(),
+            shares_escrow: Pubkey::default(),
+            pending_shares: 0,
+            pending_assets: 0,
+            reserved_assets: 0,
+            unclaimed_assets: 0,
+            next_request_id: 0,
+            next_fill_id: 0,
+            bump: 0,
+        }
+    }
+
+    #[test]
+    fn test_fill_in_fifo_order() {
+        let mut queue = queue();
+        let mut first = request(0, 100);
+        let mut second = request(1, 200);
+        queue.enqueue(&first).unwrap();
+        queue.enqueue(&second).unwrap();
+        assert_eq!(queue.pending_shares, 300);
+
+        // Requests can only be filled from the front of the queue
+        assert_eq!(
+            queue.fill(&mut second, 200).unwrap_err(),
+            ErrorCode::WithdrawalRequestNotNext.into()
+        );
+
+        // Reserves are capped by the value of the pending requests
+        assert_eq!(queue.reservable(1_000), 300);
+        assert_eq!(queue.reserve(150).unwrap(), 150);
+        assert_eq!(queue.reservable(1_000), 150);
+        assert_eq!(queue.reserve(1_000).unwrap(), 150);
+        assert_eq!(queue.reserve(1_000).unwrap(), 0);
+
+        assert_eq!(queue.fill(&mut first, 100).unwrap(), (100, 0));
+        assert!(queue.is_filled(&first));
+        assert!(!queue.is_filled(&second));
+        assert_eq!(first.assets, 100);
+
+        assert_eq!(queue.fill(&mut second, 200).unwrap(), (200, 0));
+        assert!(queue.is_filled(&second));
+        assert_eq!(queue.pending_shares, 0);
+        assert_eq!(queue.reserved_assets, 0);
+        assert_eq!(queue.unclaimed_assets, 300);
+    }
+
+    #[test]
+    fn test_fill_at_fill_time_value() {
+        let mut queue = queue();
+        let mut first = request(0, 100);
+        let mut second = request(1, 200);
+        queue.enqueue(&first).unwrap();
+        queue.enqueue(&second).unwrap();
+        assert_eq!(queue.reserve(300).unwrap(), 300);
+
+        // The vault took a loss, so the first request is worth less than when it was made and
+        // the reserve left over beyond the second request is released
+        assert_eq!(queue.fill(&mut first, 80).unwrap(), (80, 20));
+        assert_eq!(queue.reserved_assets, 200);
+
+        // The vault gained, so the second request takes more than was reserved
+        assert_eq!(queue.fill(&mut second, 220).unwrap(), (200, 0));
+        assert_eq!(queue.reserved_assets, 0);
+        assert_eq!(queue.unclaimed_assets, 300);
+    }
+}
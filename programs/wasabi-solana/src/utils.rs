@@ -1,6 +1,26 @@
 use {
     crate::{error::ErrorCode, CloseStopLossOrder, CloseTakeProfitOrder},
-    anchor_lang::{prelude::*, solana_program::sysvar},
+    anchor_lang::{
+        prelude::*,
+        solana_program::{program_pack::Pack, sysvar},
+        system_program,
+    },
+    anchor_spl::{
+        token::spl_token::native_mint,
+        token_2022::{
+            spl_token_2022::{
+                extension::{
+                    transfer_fee::TransferFeeAmount, BaseStateWithExtensions, StateWithExtensions,
+                },
+                state::Account as SplTokenAccount,
+            },
+            Token2022,
+        },
+        token_2022_extensions::{harvest_withheld_tokens_to_mint, HarvestWithheldTokensToMint},
+        token_interface::{
+            self, CloseAccount, InitializeAccount3, SyncNative, TokenAccount, TransferChecked,
+        },
+    },
 };
 
 pub fn get_function_hash(namespace: &str, name: &str) -> [u8; 8] {
@@ -20,13 +40,219 @@ fn check_function_hash(hash: &[u8]) -> bool {
     }
 }
 
+/// Moves lamports out of an account owned by this program, e.g. an escrowed execution fee
+pub fn transfer_lamports_from_pda(from: &AccountInfo, to: &AccountInfo, amount: u64) -> Result<()> {
+    **from.try_borrow_mut_lamports()? = from
+        .lamports()
+        .checked_sub(amount)
+        .ok_or(ErrorCode::ArithmeticUnderflow)?;
+    **to.try_borrow_mut_lamports()? = to
+        .lamports()
+        .checked_add(amount)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+    Ok(())
+}
+
+/// Reloads `account` and returns how much it received since `balance_before`. Mints with the
+/// Token-2022 transfer fee extension withhold part of each transfer in the destination, so this
+/// can be less than the amount sent.
+pub fn balance_increase(
+    account: &mut InterfaceAccount<TokenAccount>,
+    balance_before: u64,
+) -> Result<u64> {
+    account.reload()?;
+    Ok(account
+        .amount
+        .checked_sub(balance_before)
+        .ok_or(ErrorCode::ArithmeticUnderflow)?)
+}
+
+/// Returns the Token-2022 transfer fees withheld in `account`
+pub fn withheld_transfer_fees(account: &AccountInfo) -> Result<u64> {
+    if *account.owner != Token2022::id() {
+        return Ok(0);
+    }
+    let data = account.try_borrow_data()?;
+    let state = StateWithExtensions::<SplTokenAccount>::unpack(&data)?;
+    Ok(state
+        .get_extension::<TransferFeeAmount>()
+        .map_or(0, |fee| u64::from(fee.withheld_amount)))
+}
+
+/// Moves the transfer fees withheld in `account` to its mint. Token-2022 accounts can't be
+/// closed while they hold withheld fees.
+pub fn harvest_withheld_transfer_fees<'info>(
+    token_program: AccountInfo<'info>,
+    mint: AccountInfo<'info>,
+    account: AccountInfo<'info>,
+) -> Result<()> {
+    if withheld_transfer_fees(&account)? == 0 {
+        return Ok(());
+    }
+    let cpi_accounts = HarvestWithheldTokensToMint {
+        token_program_id: token_program.clone(),
+        mint,
+    };
+    let cpi_ctx = CpiContext::new(token_program, cpi_accounts);
+    harvest_withheld_tokens_to_mint(cpi_ctx, vec![account])
+}
+
+/// Wraps `amount` lamports from `owner` into its wSOL `account`
+pub fn wrap_sol<'info>(
+    owner: AccountInfo<'info>,
+    account: AccountInfo<'info>,
+    token_program: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    let cpi_accounts = system_program::Transfer {
+        from: owner,
+        to: account.clone(),
+    };
+    let cpi_ctx = CpiContext::new(system_program, cpi_accounts);
+    system_program::transfer(cpi_ctx, amount)?;
+
+    let cpi_ctx = CpiContext::new(token_program, SyncNative { account });
+    token_interface::sync_native(cpi_ctx)
+}
+
+/// Unwraps `amount` of the owner's wSOL `account` back to the owner as SOL, leaving the rest of
+/// its balance wrapped. The tokens are moved to `temporary_account`, the program's
+/// `native_unwrap` PDA for the owner, which is created as a wSOL account and closed to the owner.
+pub fn unwrap_sol<'info>(
+    owner: AccountInfo<'info>,
+    account: AccountInfo<'info>,
+    temporary_account: AccountInfo<'info>,
+    mint: AccountInfo<'info>,
+    token_program: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+
+    let owner_key = owner.key();
+    let (expected, bump) =
+        Pubkey::find_program_address(&[b"native_unwrap", owner_key.as_ref()], &crate::ID);
+    require_keys_eq!(expected, temporary_account.key(), ErrorCode::InvalidPubkey);
+    let signer_seeds: &[&[&[u8]]] = &[&[b"native_unwrap", owner_key.as_ref(), &[bump]]];
+
+    // Mirrors Anchor's `init`, which also works if lamports were sent to the address beforehand
+    let space = SplTokenAccount::LEN;
+    let rent = Rent::get()?.minimum_balance(space);
+    if temporary_account.lamports() == 0 {
+        let cpi_ctx = CpiContext::new_with_signer(
+            system_program.clone(),
+            system_program::CreateAccount {
+                from: owner.clone(),
+                to: temporary_account.clone(),
+            },
+            signer_seeds,
+        );
+        system_program::create_account(cpi_ctx, rent, space as u64, token_program.key)?;
+    } else {
+        let shortfall = rent.saturating_sub(temporary_account.lamports());
+        if shortfall > 0 {
+            let cpi_ctx = CpiContext::new(
+                system_program.clone(),
+                system_program::Transfer {
+                    from: owner.clone(),
+                    to: temporary_account.clone(),
+                },
+            );
+            system_program::transfer(cpi_ctx, shortfall)?;
+        }
+        let cpi_ctx = CpiContext::new_with_signer(
+            system_program.clone(),
+            system_program::Allocate {
+                account_to_allocate: temporary_account.clone(),
+            },
+            signer_seeds,
+        );
+        system_program::allocate(cpi_ctx, space as u64)?;
+        let cpi_ctx = CpiContext::new_with_signer(
+            system_program,
+            system_program::Assign {
+                account_to_assign: temporary_account.clone(),
+            },
+            signer_seeds,
+        );
+        system_program::assign(cpi_ctx, token_program.key)?;
+    }
+
+    let cpi_accounts = InitializeAccount3 {
+        account: temporary_account.clone(),
+        mint: mint.clone(),
+        authority: owner.clone(),
+    };
+    let cpi_ctx = CpiContext::new(token_program.clone(), cpi_accounts);
+    token_interface::initialize_account3(cpi_ctx)?;
+
+    let cpi_accounts = TransferChecked {
+        from: account,
+        mint,
+        to: temporary_account.clone(),
+        authority: owner.clone(),
+    };
+    let cpi_ctx = CpiContext::new(token_program.clone(), cpi_accounts);
+    token_interface::transfer_checked(cpi_ctx, amount, native_mint::DECIMALS)?;
+
+    let cpi_accounts = CloseAccount {
+        account: temporary_account,
+        destination: owner.clone(),
+        authority: owner,
+    };
+    let cpi_ctx = CpiContext::new(token_program, cpi_accounts);
+    token_interface::close_account(cpi_ctx)
+}
+
+/// Deserializes an account created with an older layout of the `discriminator` account type,
+/// which is `len` bytes long including the discriminator
+pub fn deserialize_legacy_account<T: AnchorDeserialize>(
+    data: &[u8],
+    discriminator: &[u8],
+    len: usize,
+) -> Result<T> {
+    require!(
+        data.len() == len && data.starts_with(discriminator),
+        anchor_lang::error::ErrorCode::AccountDidNotDeserialize
+    );
+    Ok(T::deserialize(&mut &data[discriminator.len()..])?)
+}
+
+/// Grows `info` to `space` bytes, funding the extra rent from `payer`, and writes `account` to
+/// it. Used to migrate accounts created with an older, shorter layout.
+pub fn migrate_account<'info, T: AccountSerialize>(
+    info: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    account: &T,
+    space: usize,
+) -> Result<()> {
+    let lamports = Rent::get()?
+        .minimum_balance(space)
+        .saturating_sub(info.lamports());
+    if lamports > 0 {
+        let cpi_accounts = system_program::Transfer {
+            from: payer.clone(),
+            to: info.clone(),
+        };
+        let cpi_ctx = CpiContext::new(system_program.clone(), cpi_accounts);
+        system_program::transfer(cpi_ctx, lamports)?;
+    }
+
+    info.realloc(space, true)?;
+    account.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])
+}
+
 pub fn get_shares_mint_address(lp_vault: &Pubkey, mint: &Pubkey) -> Pubkey {
     Pubkey::find_program_address(&[lp_vault.as_ref(), mint.as_ref()], &crate::ID).0
 }
 
 pub fn setup_transaction_introspection_validation(
     sysvar_info: &AccountInfo,
-    clean_up_ix_hash: [u8; 8],
+    clean_up_ix_hashes: &[[u8; 8]],
     is_position_setup: bool,
 ) -> Result<()> {
     let current_index = sysvar::instructions::load_current_index_checked(sysvar_info)? as usize;
@@ -59,7 +285,10 @@ pub fn setup_transaction_introspection_validation(
             let ixn_unwrapped = ixn.unwrap();
             if crate::ID == ixn_unwrapped.program_id {
                 // Check that there is a cleanup instruction
-                if ixn_unwrapped.data[0..8] == clean_up_ix_hash {
+                if clean_up_ix_hashes
+                    .iter()
+                    .any(|hash| ixn_unwrapped.data[0..8] == *hash)
+                {
                     has_cleanup_ix = true;
                 }
             }
@@ -22,6 +22,7 @@ describe("wasabi-solana", () => {
                 canInitVaults:       true,
                 canLiquidate:        true,
                 canBorrowFromVaults: true,
+                canManageVaults: true,
                 canInitPools:        true,
                 status:              {active: {}},
             })
@@ -17,6 +17,7 @@ describe("InitOrUpdatePermission", () => {
                 canLiquidate:        true, // 2
                 canInitPools:        true, // 8
                 canBorrowFromVaults: true,
+                canManageVaults: true,
                 status:              {active: {}}
             })
             .accounts({
@@ -29,6 +30,6 @@ describe("InitOrUpdatePermission", () => {
         assert.ok(!permissionAfter.isSuperAuthority);
         assert.equal(permissionAfter.authority.toString(), newAuthority.toString());
         assert.equal(JSON.stringify(permissionAfter.status), JSON.stringify({active: {}}));
-        assert.equal(permissionAfter.permissionsMap, 31);
+        assert.equal(permissionAfter.permissionsMap, 63);
     });
 });
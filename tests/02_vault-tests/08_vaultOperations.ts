@@ -2,6 +2,26 @@ import {
   validateDeposit,
   validateWithdraw,
   validateDonate,
+  validateQueuedWithdrawal,
+  validateQueueReservations,
+  depositWithInsufficientShares,
+  validatePreviews,
+  firstDepositBelowMinimum,
+  depositOverVaultCap,
+  depositOverWalletCap,
+  operationsWhileVaultPaused,
+  validateSetVaultFees,
+  validateUpdateSharesMetadata,
+  validateTokenMetadataVault,
+  validateMintPolicy,
+  validateDepositForAndWithdrawTo,
+  validateSharePriceHistory,
+  validateVestedDonate,
+  withdrawDuringCooldown,
+  validateVaultAllowlist,
+  validateShareTransferHook,
+  validateLiquidityMining,
+  validateDecommissionAndMigrate,
 } from "./validateVault";
 import { VaultContext } from "./vaultContext";
 
@@ -12,6 +32,10 @@ describe("Vault Operations", () => {
       ctx = await new VaultContext().generate();
     });
 
+    it("should reject a first deposit below the minimum", async () => {
+      await firstDepositBelowMinimum(ctx);
+    });
+
     it("should have a successful initial deposit", async () => {
       await validateDeposit(ctx, BigInt(1_000_000));
     });
@@ -27,6 +51,10 @@ describe("Vault Operations", () => {
     it("should maintain correct share ratio even with tiny deposits", async () => {
       await validateDeposit(ctx, BigInt(1));
     });
+
+    it("should fail when fewer than the minimum shares are minted", async () => {
+      await depositWithInsufficientShares(ctx, BigInt(1_000));
+    });
   });
 
   describe("Withdraw", () => {
@@ -35,9 +63,129 @@ describe("Vault Operations", () => {
     });
   });
 
+  describe("Previews", () => {
+    it("should quote the shares minted and burned", async () => {
+      await validatePreviews(ctx, BigInt(250_000));
+    });
+  });
+
+  describe("Withdrawal Queue", () => {
+    before(async () => {
+      await ctx.initWithdrawalQueue();
+    });
+
+    it("should fill and claim a queued withdrawal", async () => {
+      await validateQueuedWithdrawal(ctx, BigInt(100_000));
+    });
+
+    it("should reserve deposits and direct withdrawals for queued withdrawals", async () => {
+      await validateQueueReservations(ctx, BigInt(100_000));
+    });
+  });
+
+  describe("Deposit Caps", () => {
+    it("should reject deposits over the vault cap", async () => {
+      await depositOverVaultCap(ctx);
+    });
+
+    it("should reject deposits over the per-wallet cap", async () => {
+      await depositOverWalletCap(ctx);
+    });
+  });
+
+  describe("Vault Status", () => {
+    it("should reject paused operations only", async () => {
+      await operationsWhileVaultPaused(ctx);
+    });
+  });
+
+  describe("Fees", () => {
+    it("should set and crystallize vault fees", async () => {
+      await validateSetVaultFees(ctx);
+    });
+  });
+
+  describe("Shares Metadata", () => {
+    it("should update the shares metadata", async () => {
+      await validateUpdateSharesMetadata(ctx);
+    });
+  });
+
   describe("Donate", () => {
     it("Should allow donation of assets", async () => {
       await validateDonate(ctx, BigInt(1_000_000));
     });
   });
+
+  describe("On Behalf Of", () => {
+    it("should deposit for and withdraw to another wallet", async () => {
+      await validateDepositForAndWithdrawTo(ctx, BigInt(1_000_000));
+    });
+  });
+
+  describe("Share Price History", () => {
+    it("should checkpoint the share price and return its TWAP", async () => {
+      await validateSharePriceHistory(ctx, BigInt(1_000_000));
+    });
+  });
+
+  describe("Token Metadata", () => {
+    let tokenMetadataCtx: VaultContext;
+    before(async () => {
+      tokenMetadataCtx = await new VaultContext().generate(true);
+    });
+
+    it("should store the shares metadata on the mint", async () => {
+      await validateTokenMetadataVault(tokenMetadataCtx);
+    });
+
+    it("should accept deposits", async () => {
+      await validateDeposit(tokenMetadataCtx, BigInt(1_000_000));
+    });
+  });
+
+  describe("Mint Policy", () => {
+    it("should reject risky mints unless they are allowlisted", async () => {
+      await validateMintPolicy(ctx);
+    });
+  });
+
+  describe("Vesting", () => {
+    it("should vest donations into total assets over the vesting period", async () => {
+      await validateVestedDonate(ctx, BigInt(1_000_000));
+    });
+  });
+
+  describe("LP Cooldown", () => {
+    it("should reject or charge withdrawals before the cooldown ends", async () => {
+      await withdrawDuringCooldown(ctx);
+    });
+  });
+
+  describe("Allowlist", () => {
+    it("should only accept deposits from allowlisted wallets", async () => {
+      await validateVaultAllowlist(ctx);
+    });
+
+    it("should only transfer shares to allowlisted wallets", async () => {
+      await validateShareTransferHook(ctx);
+    });
+  });
+
+  describe("Liquidity Mining", () => {
+    it("should stream rewards to staked shares", async () => {
+      await validateLiquidityMining(ctx);
+    });
+  });
+
+  describe("Decommissioning", () => {
+    let decommissionCtx: VaultContext;
+    before(async () => {
+      decommissionCtx = await new VaultContext().generate();
+    });
+
+    it("should wind the vault down and migrate LPs into its successor", async () => {
+      await validateDecommissionAndMigrate(decommissionCtx, BigInt(1_000_000));
+    });
+  });
 });
@@ -0,0 +1,36 @@
+import {
+  validateTransferFeeClaimWithdrawal,
+  validateTransferFeeCloseLpVault,
+  validateTransferFeeDeposit,
+  validateTransferFeeDonate,
+} from "./validateVault";
+import {VaultContext} from "./vaultContext";
+import {TOKEN_2022_PROGRAM_ID} from "@solana/spl-token";
+
+describe("Transfer Fee Vault", () => {
+  let ctx: VaultContext;
+  before(async () => {
+    ctx = await new VaultContext(TOKEN_2022_PROGRAM_ID).generate(true);
+  });
+
+  it("should mint shares for the amount received net of the transfer fee", async () => {
+    await validateTransferFeeDeposit(ctx, BigInt(1_000_000));
+  });
+
+  it("should keep the share price on a second deposit", async () => {
+    await validateTransferFeeDeposit(ctx, BigInt(2_500_000));
+  });
+
+  it("should credit donations net of the transfer fee", async () => {
+    await validateTransferFeeDonate(ctx, BigInt(1_000_000));
+  });
+
+  it("should owe queued withdrawals what the queue received net of the transfer fee", async () => {
+    await ctx.initWithdrawalQueue();
+    await validateTransferFeeClaimWithdrawal(ctx, BigInt(500_000));
+  });
+
+  it("should harvest the withheld transfer fees when closing the vault", async () => {
+    await validateTransferFeeCloseLpVault(ctx);
+  });
+});
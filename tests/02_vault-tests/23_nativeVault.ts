@@ -0,0 +1,17 @@
+import {validateNativeDeposit, validateNativeWithdraw} from "./validateVault";
+import {NativeVaultContext} from "./nativeVaultContext";
+
+describe("Native SOL Vault", () => {
+  let ctx: NativeVaultContext;
+  before(async () => {
+    ctx = await new NativeVaultContext().generate();
+  });
+
+  it("should wrap SOL on deposit", async () => {
+    await validateNativeDeposit(ctx, BigInt(1_000_000_000));
+  });
+
+  it("should unwrap SOL on withdraw", async () => {
+    await validateNativeWithdraw(ctx, BigInt(500_000_000));
+  });
+});
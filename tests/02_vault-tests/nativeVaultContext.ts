@@ -0,0 +1,144 @@
+import {
+    createAssociatedTokenAccountIdempotentInstruction,
+    createSyncNativeInstruction,
+    getAssociatedTokenAddressSync,
+    NATIVE_MINT,
+    TOKEN_2022_PROGRAM_ID,
+    TOKEN_PROGRAM_ID,
+} from "@solana/spl-token";
+import {BN, Program, web3, workspace} from "@coral-xyz/anchor";
+import {WasabiSolana} from "../../target/types/wasabi_solana";
+import {defaultInitLpVaultArgs} from "../utils";
+import {superAdminProgram, superAdminPermission, WASABI_PROGRAM_ID} from "../hooks/rootHook";
+
+/**
+ * LP Vault whose asset is wSOL, used through the native SOL instructions
+ */
+export class NativeVaultContext {
+    constructor(
+        readonly program = workspace.WasabiSolana as Program<WasabiSolana>,
+        readonly lpVault = web3.PublicKey.findProgramAddressSync(
+            [Buffer.from("lp_vault"), NATIVE_MINT.toBuffer()],
+            WASABI_PROGRAM_ID
+        )[0],
+        readonly vault = getAssociatedTokenAddressSync(NATIVE_MINT, lpVault, true, TOKEN_PROGRAM_ID),
+        readonly ownerWsolAta = getAssociatedTokenAddressSync(
+            NATIVE_MINT,
+            program.provider.publicKey,
+            false,
+            TOKEN_PROGRAM_ID,
+        ),
+        readonly nativeUnwrapAccount = web3.PublicKey.findProgramAddressSync(
+            [Buffer.from("native_unwrap"), program.provider.publicKey.toBuffer()],
+            WASABI_PROGRAM_ID
+        )[0],
+        readonly sharesMint = web3.PublicKey.findProgramAddressSync(
+            [lpVault.toBuffer(), NATIVE_MINT.toBuffer()],
+            WASABI_PROGRAM_ID
+        )[0],
+        readonly ownerSharesAta = getAssociatedTokenAddressSync(
+            sharesMint,
+            program.provider.publicKey,
+            false,
+            TOKEN_2022_PROGRAM_ID,
+        ),
+    ) {
+    }
+
+    async generate() {
+        // The wSOL LP Vault is unique, so it may already exist
+        if (await this.program.provider.connection.getAccountInfo(this.lpVault)) {
+            return this;
+        }
+
+        await superAdminProgram.methods
+            .initLpVaultWithTokenMetadata(defaultInitLpVaultArgs)
+            .accountsPartial({
+                payer:             superAdminProgram.provider.publicKey,
+                authority:         superAdminProgram.provider.publicKey,
+                vault:             this.vault,
+                lpVault:           this.lpVault,
+                permission:        superAdminPermission,
+                assetMint:         NATIVE_MINT,
+                assetTokenProgram: TOKEN_PROGRAM_ID,
+            })
+            .preInstructions([
+                createAssociatedTokenAccountIdempotentInstruction(
+                    superAdminProgram.provider.publicKey,
+                    this.vault,
+                    this.lpVault,
+                    NATIVE_MINT,
+                    TOKEN_PROGRAM_ID,
+                ),
+            ])
+            .rpc();
+
+        return this;
+    }
+
+    getNativeAccounts() {
+        return {
+            depositOrWithdraw: {
+                owner:              this.program.provider.publicKey,
+                ownerAssetAccount:  this.ownerWsolAta,
+                ownerSharesAccount: this.ownerSharesAta,
+                lpVault:            this.lpVault,
+                vault:              this.vault,
+                assetMint:          NATIVE_MINT,
+                sharesMint:         this.sharesMint,
+                assetTokenProgram:  TOKEN_PROGRAM_ID,
+                sharesTokenProgram: TOKEN_2022_PROGRAM_ID,
+            },
+            nativeUnwrapAccount: this.nativeUnwrapAccount,
+        };
+    }
+
+    /** The owner's wSOL account must exist before the instruction */
+    createWsolAtaIx() {
+        return createAssociatedTokenAccountIdempotentInstruction(
+            this.program.provider.publicKey,
+            this.ownerWsolAta,
+            this.program.provider.publicKey,
+            NATIVE_MINT,
+            TOKEN_PROGRAM_ID,
+        );
+    }
+
+    /** Wraps `lamports` into the owner's wSOL account, outside of the native instructions */
+    async wrapSol(lamports: bigint) {
+        await this.program.provider.sendAndConfirm(new web3.Transaction().add(
+            this.createWsolAtaIx(),
+            web3.SystemProgram.transfer({
+                fromPubkey: this.program.provider.publicKey,
+                toPubkey:   this.ownerWsolAta,
+                lamports:   lamports,
+            }),
+            createSyncNativeInstruction(this.ownerWsolAta, TOKEN_PROGRAM_ID),
+        ));
+    }
+
+    async depositNative(lamports: bigint, minShares = BigInt(0)) {
+        return await this.program.methods
+            .depositNative(new BN(lamports.toString()), new BN(minShares.toString()))
+            .accountsPartial(this.getNativeAccounts())
+            .preInstructions([
+                this.createWsolAtaIx(),
+                createAssociatedTokenAccountIdempotentInstruction(
+                    this.program.provider.publicKey,
+                    this.ownerSharesAta,
+                    this.program.provider.publicKey,
+                    this.sharesMint,
+                    TOKEN_2022_PROGRAM_ID,
+                ),
+            ])
+            .rpc();
+    }
+
+    async withdrawNative(lamports: bigint, maxShares = BigInt("18446744073709551615")) {
+        return await this.program.methods
+            .withdrawNative(new BN(lamports.toString()), new BN(maxShares.toString()))
+            .accountsPartial(this.getNativeAccounts())
+            .preInstructions([this.createWsolAtaIx()])
+            .rpc();
+    }
+}
@@ -1,16 +1,33 @@
-import {getMultipleMintAccounts, getMultipleTokenAccounts} from "../utils";
-import {getAssociatedTokenAddressSync, TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID} from "@solana/spl-token";
+import {createSimpleMint, defaultInitLpVaultArgs, getMultipleMintAccounts, getMultipleTokenAccounts} from "../utils";
+import {
+    createAssociatedTokenAccountIdempotentInstruction,
+    createInitializeMintInstruction,
+    createMintToInstruction,
+    createTransferCheckedWithTransferHookInstruction,
+    getAssociatedTokenAddressSync,
+    getTokenMetadata,
+    getTransferFeeAmount,
+    MintLayout,
+    TOKEN_2022_PROGRAM_ID,
+    TOKEN_PROGRAM_ID
+} from "@solana/spl-token";
 import {assert} from "chai";
 import * as anchor from "@coral-xyz/anchor";
 import {VaultContext} from "./vaultContext";
+import {NativeVaultContext} from "./nativeVaultContext";
+
+// Virtual offsets applied to the share math by the program
+const VIRTUAL_SHARES = new anchor.BN(1_000);
+const VIRTUAL_ASSETS = new anchor.BN(1_000);
+export const MIN_FIRST_DEPOSIT = BigInt(1_000);
 
 export const getVaultAccountStates = async (ctx: VaultContext) => {
     const [lpVault, [ownerToken, vault], [ownerShares], [sharesMint]] = await Promise.all([
         ctx.program.account.lpVault.fetch(ctx.lpVault),
         getMultipleTokenAccounts(ctx.program.provider.connection, [
-            getAssociatedTokenAddressSync(ctx.currency, ctx.program.provider.publicKey, false, TOKEN_PROGRAM_ID),
-            getAssociatedTokenAddressSync(ctx.currency, ctx.lpVault, true, TOKEN_PROGRAM_ID),
-        ], TOKEN_PROGRAM_ID),
+            ctx.ownerCurrencyAta,
+            ctx.vault,
+        ], ctx.currencyTokenProgram),
         getMultipleTokenAccounts(ctx.program.provider.connection, [
             await ctx.getUserSharesAta(),
         ], TOKEN_2022_PROGRAM_ID),
@@ -85,9 +102,9 @@ export const validateWithdrawVaultStates = async (
         : amountBN.div(new anchor.BN(1000));
 
     const expectedSharesBurned = amountBN
-        .mul(new anchor.BN(before.sharesMint.supply.toString()))
+        .mul(new anchor.BN(before.sharesMint.supply.toString()).add(VIRTUAL_SHARES))
         .add(roundingProtection)
-        .div(before.lpVault.totalAssets);
+        .div(before.lpVault.totalAssets.add(VIRTUAL_ASSETS));
 
     const ownerADiff = after.ownerToken.amount - before.ownerToken.amount;
     assert.equal(ownerADiff, amount);
@@ -162,3 +179,774 @@ export const validateDonate = async (ctx: VaultContext, amount: bigint) => {
         assert.ok(false);
     }
 };
+
+const VAULT_DEPOSITS_PAUSED = 1;
+const VAULT_WITHDRAWALS_PAUSED = 2;
+
+export const validateQueuedWithdrawal = async (ctx: VaultContext, sharesAmount: bigint) => {
+    try {
+        const queueBefore = await ctx.program.account.withdrawalQueue.fetch(ctx.withdrawalQueue);
+        const id = BigInt(queueBefore.nextRequestId.toString());
+        const statesBefore = await getVaultAccountStates(ctx);
+
+        await ctx.requestWithdrawal(sharesAmount);
+
+        // The shares are escrowed rather than burned, so the vault's assets and supply are unchanged
+        const [request, queueRequested, [escrow], statesRequested] = await Promise.all([
+            ctx.program.account.withdrawalRequest.fetch(ctx.getWithdrawalRequest(id)),
+            ctx.program.account.withdrawalQueue.fetch(ctx.withdrawalQueue),
+            getMultipleTokenAccounts(
+                ctx.program.provider.connection,
+                [queueBefore.sharesEscrow],
+                TOKEN_2022_PROGRAM_ID
+            ),
+            getVaultAccountStates(ctx),
+        ]);
+        assert.equal(request.shares.toString(), sharesAmount.toString());
+        assert.equal(request.assets.toString(), "0");
+        assert.equal(escrow.amount, sharesAmount);
+        assert.equal(
+            queueRequested.pendingShares.sub(queueBefore.pendingShares).toString(),
+            sharesAmount.toString()
+        );
+        assert.equal(
+            statesBefore.ownerShares.amount - statesRequested.ownerShares.amount,
+            sharesAmount
+        );
+        assert.equal(statesRequested.sharesMint.supply, statesBefore.sharesMint.supply);
+        assert.equal(
+            statesRequested.lpVault.totalAssets.toString(),
+            statesBefore.lpVault.totalAssets.toString()
+        );
+
+        // The claim fails until the request is filled
+        await expectError(ctx.claimWithdrawal(id), 6040, "WithdrawalNotFilled");
+
+        // Requests can't be filled while withdrawals are paused
+        await ctx.setVaultStatus(VAULT_WITHDRAWALS_PAUSED);
+        await expectError(ctx.fillWithdrawalQueue(), 6049, "VaultWithdrawalsPaused");
+        await ctx.setVaultStatus(0);
+
+        await ctx.fillWithdrawalQueue();
+
+        // Filled at the share price at the time of filling
+        const expectedAssets = new anchor.BN(sharesAmount.toString())
+            .mul(statesRequested.lpVault.totalAssets.add(VIRTUAL_ASSETS))
+            .div(new anchor.BN(statesRequested.sharesMint.supply.toString()).add(VIRTUAL_SHARES));
+        const [filledRequest, queueFilled, statesFilled] = await Promise.all([
+            ctx.program.account.withdrawalRequest.fetch(ctx.getWithdrawalRequest(id)),
+            ctx.program.account.withdrawalQueue.fetch(ctx.withdrawalQueue),
+            getVaultAccountStates(ctx),
+        ]);
+        assert.equal(filledRequest.assets.toString(), expectedAssets.toString());
+        assert.equal(queueFilled.pendingShares.toString(), queueBefore.pendingShares.toString());
+        assert.equal(
+            queueFilled.unclaimedAssets.sub(queueBefore.unclaimedAssets).toString(),
+            expectedAssets.toString()
+        );
+        assert.equal(
+            statesRequested.sharesMint.supply - statesFilled.sharesMint.supply,
+            sharesAmount
+        );
+        assert.equal(
+            statesRequested.lpVault.totalAssets.sub(statesFilled.lpVault.totalAssets).toString(),
+            expectedAssets.toString()
+        );
+
+        await ctx.claimWithdrawal(id);
+
+        const statesAfter = await getVaultAccountStates(ctx);
+        assert.equal(
+            (statesAfter.ownerToken.amount - statesFilled.ownerToken.amount).toString(),
+            expectedAssets.toString()
+        );
+        assert.isNull(
+            await ctx.program.account.withdrawalRequest.fetchNullable(ctx.getWithdrawalRequest(id)),
+            "Withdrawal request should be closed"
+        );
+    } catch (err) {
+        console.error(err);
+        assert.ok(false);
+    }
+};
+
+export const validateQueueReservations = async (ctx: VaultContext, sharesAmount: bigint) => {
+    try {
+        const queueBefore = await ctx.program.account.withdrawalQueue.fetch(ctx.withdrawalQueue);
+        const id = BigInt(queueBefore.nextRequestId.toString());
+        await ctx.requestWithdrawal(sharesAmount);
+
+        // Deposits are reserved for the pending request
+        await ctx.deposit(BigInt(1_000));
+        const queueDeposited = await ctx.program.account.withdrawalQueue.fetch(ctx.withdrawalQueue);
+        assert.equal(
+            queueDeposited.reservedAssets.sub(queueBefore.reservedAssets).toString(),
+            "1000"
+        );
+
+        // A direct withdrawal reserves the rest of the pending value before it is paid
+        await ctx.withdraw(BigInt(1_000));
+        const queueWithdrawn = await ctx.program.account.withdrawalQueue.fetch(ctx.withdrawalQueue);
+        assert.equal(
+            queueWithdrawn.reservedAssets.toString(),
+            queueWithdrawn.pendingAssets.toString()
+        );
+
+        // Without the queue's token account the deposit is rejected
+        await expectError(
+            ctx.program.methods
+                .deposit(new anchor.BN(1_000))
+                .accountsPartial({...ctx.getVaultAccounts(), queueVault: null})
+                .rpc(),
+            6070,
+            "MissingWithdrawalQueueAccounts"
+        );
+
+        await ctx.fillWithdrawalQueue();
+        await ctx.claimWithdrawal(id);
+    } catch (err) {
+        console.error(err);
+        assert.ok(false);
+    }
+};
+
+export const depositWithInsufficientShares = async (ctx: VaultContext, amount: bigint) => {
+    try {
+        // Fewer shares than requested will be minted unless the share price drops by half
+        await ctx.depositWithMinShares(amount, amount * BigInt(2));
+        assert.fail("Should have failed with slippage exceeded");
+    } catch (err) {
+        if (/6041/.test(err.toString()) || /SlippageExceeded/.test(err.toString())) {
+            assert.ok(true);
+        } else {
+            console.error(err);
+            assert.ok(false);
+        }
+    }
+};
+
+export const validatePreviews = async (ctx: VaultContext, amount: bigint) => {
+    try {
+        const previewedShares = await ctx.previewDeposit(amount);
+        const beforeDeposit = await getVaultAccountStates(ctx);
+        await ctx.deposit(amount);
+        const afterDeposit = await getVaultAccountStates(ctx);
+        assert.equal(afterDeposit.ownerShares.amount - beforeDeposit.ownerShares.amount, previewedShares);
+
+        const previewedBurn = await ctx.previewWithdraw(amount);
+        await ctx.withdraw(amount);
+        const afterWithdraw = await getVaultAccountStates(ctx);
+        assert.equal(afterDeposit.ownerShares.amount - afterWithdraw.ownerShares.amount, previewedBurn);
+
+        // The owner can withdraw at most the unborrowed assets their shares are worth
+        const maxWithdraw = await ctx.maxWithdraw();
+        const ownerAssets = new anchor.BN(afterWithdraw.ownerShares.amount.toString())
+            .mul(afterWithdraw.lpVault.totalAssets.add(VIRTUAL_ASSETS))
+            .div(new anchor.BN(afterWithdraw.sharesMint.supply.toString()).add(VIRTUAL_SHARES));
+        const expected = BigInt(ownerAssets.toString()) < afterWithdraw.vault.amount
+            ? BigInt(ownerAssets.toString())
+            : afterWithdraw.vault.amount;
+        assert.equal(maxWithdraw, expected);
+    } catch (err) {
+        console.error(err);
+        assert.ok(false);
+    }
+};
+
+export const firstDepositBelowMinimum = async (ctx: VaultContext) => {
+    try {
+        await ctx.deposit(MIN_FIRST_DEPOSIT - BigInt(1));
+        assert.fail("Should have failed with first deposit too small");
+    } catch (err) {
+        if (/6042/.test(err.toString()) || /FirstDepositTooSmall/.test(err.toString())) {
+            assert.ok(true);
+        } else {
+            console.error(err);
+            assert.ok(false);
+        }
+    }
+};
+
+const U64_MAX = BigInt("18446744073709551615");
+
+const expectError = async (promise: Promise<unknown>, code: number, name: string) => {
+    try {
+        await promise;
+        assert.fail(`Should have failed with ${name}`);
+    } catch (err) {
+        if (new RegExp(code.toString()).test(err.toString()) || new RegExp(name).test(err.toString())) {
+            assert.ok(true);
+        } else {
+            console.error(err);
+            assert.ok(false);
+        }
+    }
+};
+
+export const depositOverVaultCap = async (ctx: VaultContext) => {
+    const {lpVault} = await getVaultAccountStates(ctx);
+    const totalAssets = BigInt(lpVault.totalAssets.toString());
+    await ctx.setDepositCaps(totalAssets + BigInt(100), BigInt(0));
+
+    await expectError(ctx.deposit(BigInt(101)), 6043, "DepositCapExceeded");
+    await ctx.deposit(BigInt(100));
+
+    await ctx.setDepositCaps(U64_MAX, BigInt(0));
+};
+
+export const depositOverWalletCap = async (ctx: VaultContext) => {
+    await ctx.initDepositor();
+    await ctx.setDepositCaps(U64_MAX, BigInt(500));
+
+    await expectError(ctx.deposit(BigInt(100)), 6045, "MissingDepositor");
+    await ctx.depositWithDepositor(BigInt(400));
+    await expectError(ctx.depositWithDepositor(BigInt(101)), 6044, "WalletDepositCapExceeded");
+
+    const depositor = await ctx.program.account.depositor.fetch(ctx.depositor);
+    assert.equal(depositor.deposited.toString(), "400");
+
+    await ctx.setDepositCaps(U64_MAX, BigInt(0));
+};
+
+export const withdrawDuringCooldown = async (ctx: VaultContext) => {
+    await expectError(ctx.setVaultCooldown(604_801, 0), 6060, "InvalidLpCooldown");
+    await expectError(ctx.setVaultCooldown(3_600, 1_001), 6054, "InvalidFee");
+    await ctx.setVaultCooldown(3_600, 0);
+
+    // Deposits must be recorded on the depositor while the cooldown is enabled
+    await expectError(ctx.deposit(BigInt(10_000)), 6045, "MissingDepositor");
+    await ctx.depositWithDepositor(BigInt(10_000));
+    await expectError(ctx.withdraw(BigInt(1_000)), 6045, "MissingDepositor");
+    await expectError(ctx.withdrawWithDepositor(BigInt(1_000)), 6061, "LpCooldownActive");
+
+    // With an exit fee the withdrawal goes through, and the fee stays in the vault
+    await ctx.setVaultCooldown(3_600, 100);
+    const before = await getVaultAccountStates(ctx);
+
+    // The views quote the assets received net of the exit fee
+    const expectedShares = await ctx.previewWithdraw(BigInt(990), ctx.depositor);
+    const redeemed = await ctx.previewRedeem(before.ownerShares.amount, ctx.depositor);
+    const maxWithdraw = await ctx.maxWithdraw(ctx.depositor);
+    assert.equal(maxWithdraw, redeemed < before.vault.amount ? redeemed : before.vault.amount);
+
+    await ctx.withdrawWithDepositor(BigInt(1_000));
+    const after = await getVaultAccountStates(ctx);
+    assert.equal(before.ownerShares.amount - after.ownerShares.amount, expectedShares);
+    assert.equal(after.ownerToken.amount - before.ownerToken.amount, BigInt(990));
+    assert.equal(before.vault.amount - after.vault.amount, BigInt(990));
+    assert.equal(before.lpVault.totalAssets.sub(after.lpVault.totalAssets).toString(), "990");
+
+    // Deposits for a new wallet create its depositor, starting its cooldown
+    const receiver = anchor.web3.Keypair.generate().publicKey;
+    await ctx.depositFor(receiver, BigInt(1_000));
+    const receiverDepositor = await ctx.program.account.depositor.fetch(ctx.getDepositor(receiver));
+    assert.equal(receiverDepositor.deposited.toString(), "1000");
+    assert.isAbove(receiverDepositor.lastDepositTimestamp.toNumber(), 0);
+
+    await ctx.setVaultCooldown(0, 0);
+};
+
+export const operationsWhileVaultPaused = async (ctx: VaultContext) => {
+    await ctx.setVaultStatus(VAULT_DEPOSITS_PAUSED);
+    await expectError(ctx.deposit(BigInt(1_000)), 6048, "VaultDepositsPaused");
+    await ctx.withdraw(BigInt(1_000));
+
+    await ctx.setVaultStatus(VAULT_WITHDRAWALS_PAUSED);
+    await expectError(ctx.withdraw(BigInt(1_000)), 6049, "VaultWithdrawalsPaused");
+    await ctx.deposit(BigInt(1_000));
+
+    await ctx.setVaultStatus(0);
+};
+
+export const validateSetVaultFees = async (ctx: VaultContext) => {
+    await expectError(ctx.setVaultFees(5_001, 0), 6054, "InvalidFee");
+    await expectError(ctx.setVaultFees(0, 1_001), 6054, "InvalidFee");
+
+    await ctx.setVaultFees(1_000, 200);
+    const lpVault = await ctx.program.account.lpVault.fetch(ctx.lpVault);
+    assert.equal(lpVault.performanceFeeBps, 1_000);
+    assert.equal(lpVault.managementFeeBps, 200);
+    assert.ok(lpVault.feeSharesAccount.equals(await ctx.getFeeSharesAta()));
+
+    // The fees must be crystallised before the share price is used
+    await expectError(ctx.deposit(BigInt(1_000)), 6069, "MissingFeeSharesAccount");
+
+    // Crystallising advances the fee clock and leaves no pending performance fee
+    await ctx.crystallizeVaultFees();
+    const after = await ctx.program.account.lpVault.fetch(ctx.lpVault);
+    assert.equal(after.pendingPerformanceFee.toString(), "0");
+    assert.ok(after.lastFeeTimestamp.gte(lpVault.lastFeeTimestamp));
+
+    await ctx.setVaultFees(0, 0);
+};
+
+export const validateVestedDonate = async (ctx: VaultContext, amount: bigint) => {
+    await expectError(ctx.setVaultVestingPeriod(2_592_001), 6059, "InvalidVestingPeriod");
+    await ctx.setVaultVestingPeriod(3_600);
+
+    const before = await ctx.program.account.lpVault.fetch(ctx.lpVault);
+    await ctx.donate(amount);
+    const after = await ctx.program.account.lpVault.fetch(ctx.lpVault);
+
+    // The donation is held back from share pricing until it vests
+    assert.equal(after.totalAssets.toString(), before.totalAssets.toString());
+    assert.equal(after.unvestedAssets.sub(before.unvestedAssets).toString(), amount.toString());
+    assert.equal(after.vestingEnd.sub(after.lastVestingTimestamp).toNumber(), 3_600);
+
+    await ctx.setVaultVestingPeriod(0);
+};
+
+export const validateUpdateSharesMetadata = async (ctx: VaultContext) => {
+    try {
+        const [name, symbol, uri] = ["Wasabi LP Updated", "wLPU", "https://wasabi.xyz/updated.json"];
+        await ctx.updateSharesMetadata(name, symbol, uri);
+
+        const metadata = await ctx.program.provider.connection.getAccountInfo(await ctx.getSharesMetadata());
+        const data = metadata.data.toString("utf8");
+        assert.include(data, name);
+        assert.include(data, symbol);
+        assert.include(data, uri);
+    } catch (err) {
+        console.error(err);
+        assert.ok(false);
+    }
+};
+
+export const validateTokenMetadataVault = async (ctx: VaultContext) => {
+    try {
+        const metadata = await getTokenMetadata(
+            ctx.program.provider.connection,
+            await ctx.getSharesMint(),
+            "confirmed",
+            TOKEN_2022_PROGRAM_ID,
+        );
+        assert.equal(metadata.name, defaultInitLpVaultArgs.name);
+        assert.equal(metadata.symbol, defaultInitLpVaultArgs.symbol);
+        assert.equal(metadata.uri, defaultInitLpVaultArgs.uri);
+        assert.isTrue(metadata.updateAuthority.equals(ctx.lpVault));
+    } catch (err) {
+        console.error(err);
+        assert.ok(false);
+    }
+};
+
+export const validateTransferFeeDeposit = async (ctx: VaultContext, amount: bigint) => {
+    const received = amount - ctx.transferFee(amount);
+    const expectedShares = await ctx.previewDeposit(received);
+    const before = await getVaultAccountStates(ctx);
+
+    await ctx.deposit(amount);
+
+    const after = await getVaultAccountStates(ctx);
+    assert.equal(after.vault.amount - before.vault.amount, received);
+    assert.equal(after.lpVault.totalAssets.sub(before.lpVault.totalAssets).toString(), received.toString());
+    assert.equal(after.ownerShares.amount - before.ownerShares.amount, expectedShares);
+    assert.equal(after.sharesMint.supply - before.sharesMint.supply, expectedShares);
+};
+
+export const validateTransferFeeDonate = async (ctx: VaultContext, amount: bigint) => {
+    const received = amount - ctx.transferFee(amount);
+    const before = await getVaultAccountStates(ctx);
+
+    await ctx.donate(amount);
+
+    const after = await getVaultAccountStates(ctx);
+    assert.equal(after.vault.amount - before.vault.amount, received);
+    assert.equal(after.lpVault.totalAssets.sub(before.lpVault.totalAssets).toString(), received.toString());
+    assert.equal(after.sharesMint.supply, before.sharesMint.supply);
+};
+
+export const validateTransferFeeClaimWithdrawal = async (ctx: VaultContext, sharesAmount: bigint) => {
+    const queueBefore = await ctx.program.account.withdrawalQueue.fetch(ctx.withdrawalQueue);
+    const id = BigInt(queueBefore.nextRequestId.toString());
+    await ctx.requestWithdrawal(sharesAmount);
+    const statesRequested = await getVaultAccountStates(ctx);
+
+    await ctx.fillWithdrawalQueue();
+
+    // The request is owed what the queue received from the vault, net of the transfer fee
+    const owed = BigInt(new anchor.BN(sharesAmount.toString())
+        .mul(statesRequested.lpVault.totalAssets.add(VIRTUAL_ASSETS))
+        .div(new anchor.BN(statesRequested.sharesMint.supply.toString()).add(VIRTUAL_SHARES))
+        .toString());
+    const filledAssets = owed - ctx.transferFee(owed);
+    const [request, queueFilled, statesFilled] = await Promise.all([
+        ctx.program.account.withdrawalRequest.fetch(ctx.getWithdrawalRequest(id)),
+        ctx.program.account.withdrawalQueue.fetch(ctx.withdrawalQueue),
+        getVaultAccountStates(ctx),
+    ]);
+    assert.equal(request.assets.toString(), filledAssets.toString());
+    assert.equal(
+        queueFilled.unclaimedAssets.sub(queueBefore.unclaimedAssets).toString(),
+        filledAssets.toString()
+    );
+    assert.equal(statesRequested.vault.amount - statesFilled.vault.amount, owed);
+
+    await ctx.claimWithdrawal(id);
+
+    // The claim pays the transfer fee once more, and nothing is left owed in the queue
+    const [queueClaimed, [queueVault], statesAfter] = await Promise.all([
+        ctx.program.account.withdrawalQueue.fetch(ctx.withdrawalQueue),
+        getMultipleTokenAccounts(ctx.program.provider.connection, [ctx.queueVault], ctx.currencyTokenProgram),
+        getVaultAccountStates(ctx),
+    ]);
+    assert.equal(
+        statesAfter.ownerToken.amount - statesFilled.ownerToken.amount,
+        filledAssets - ctx.transferFee(filledAssets)
+    );
+    assert.equal(queueClaimed.unclaimedAssets.toString(), queueBefore.unclaimedAssets.toString());
+    assert.equal(queueVault.amount.toString(), queueClaimed.unclaimedAssets.toString());
+};
+
+export const validateTransferFeeCloseLpVault = async (ctx: VaultContext) => {
+    const connection = ctx.program.provider.connection;
+    const before = await getVaultAccountStates(ctx);
+    await ctx.redeem(before.ownerShares.amount);
+    await ctx.decommissionLpVault();
+
+    // The vault holds the fees withheld from every transfer it received, which must be
+    // harvested to the mint before its token account can be closed
+    const [vaultWithFees] = await getMultipleTokenAccounts(connection, [ctx.vault], TOKEN_2022_PROGRAM_ID);
+    assert.isTrue(getTransferFeeAmount(vaultWithFees).withheldAmount > BigInt(0));
+
+    await ctx.closeLpVault();
+    assert.isNull(await connection.getAccountInfo(ctx.lpVault));
+    assert.isNull(await connection.getAccountInfo(ctx.vault));
+};
+
+export const validateMintPolicy = async (ctx: VaultContext) => {
+    // A mint with a freeze authority, which the default policy rejects
+    const mint = anchor.web3.Keypair.generate();
+    const payer = ctx.program.provider.publicKey;
+    const tx = new anchor.web3.Transaction().add(
+        anchor.web3.SystemProgram.createAccount({
+            fromPubkey:       payer,
+            newAccountPubkey: mint.publicKey,
+            space:            MintLayout.span,
+            lamports:         await ctx.program.provider.connection.getMinimumBalanceForRentExemption(MintLayout.span),
+            programId:        TOKEN_PROGRAM_ID,
+        }),
+        createInitializeMintInstruction(mint.publicKey, 6, payer, payer, TOKEN_PROGRAM_ID),
+    );
+    await ctx.program.provider.sendAndConfirm(tx, [mint]);
+
+    await expectError(ctx.initLpVaultFor(mint.publicKey, false), 6055, "MintNotAllowed");
+    await expectError(ctx.setMintPolicy(0b1000000), 6056, "InvalidMintPolicy");
+
+    await ctx.allowMint(mint.publicKey);
+    await ctx.initLpVaultFor(mint.publicKey, true);
+    await ctx.removeAllowedMint(mint.publicKey);
+
+    const allowedMint = await ctx.program.provider.connection.getAccountInfo(ctx.getAllowedMint(mint.publicKey));
+    assert.isNull(allowedMint);
+};
+
+export const validateVaultAllowlist = async (ctx: VaultContext) => {
+    const owner = ctx.program.provider.publicKey;
+    await ctx.setVaultAllowlist(true);
+
+    await expectError(ctx.deposit(BigInt(1_000)), 6062, "WalletNotAllowlisted");
+    await ctx.allowlistWallet(owner);
+    await ctx.depositWithAllowlist(BigInt(1_000));
+
+    await ctx.removeAllowlistedWallet(owner);
+    const allowlistedWallet = await ctx.program.provider.connection.getAccountInfo(ctx.getAllowlistedWallet(owner));
+    assert.isNull(allowlistedWallet);
+
+    await ctx.setVaultAllowlist(false);
+};
+
+export const validateShareTransferHook = async (ctx: VaultContext) => {
+    const connection = ctx.program.provider.connection;
+    const owner = ctx.program.provider.publicKey;
+    const amount = BigInt(1_000_000);
+
+    // A fresh asset, since each asset has a single vault
+    const {ixes, mint} = await createSimpleMint(owner, connection, 6, TOKEN_PROGRAM_ID);
+    const ownerAssetAccount = getAssociatedTokenAddressSync(mint.publicKey, owner, false, TOKEN_PROGRAM_ID);
+    await ctx.program.provider.sendAndConfirm(new anchor.web3.Transaction().add(
+        ...ixes,
+        createAssociatedTokenAccountIdempotentInstruction(owner, ownerAssetAccount, owner, mint.publicKey, TOKEN_PROGRAM_ID),
+        createMintToInstruction(mint.publicKey, ownerAssetAccount, owner, amount, [], TOKEN_PROGRAM_ID),
+    ), [mint]);
+
+    const lpVault = await ctx.initAllowlistedLpVault(mint.publicKey);
+    const sharesMint = anchor.web3.PublicKey.findProgramAddressSync(
+        [lpVault.toBuffer(), mint.publicKey.toBuffer()],
+        ctx.program.programId
+    )[0];
+    const lpVaultAccount = await ctx.program.account.lpVault.fetch(lpVault);
+    assert.isTrue(lpVaultAccount.allowlistEnabled);
+
+    const ownerSharesAccount = getAssociatedTokenAddressSync(sharesMint, owner, false, TOKEN_2022_PROGRAM_ID);
+    await ctx.allowlistWallet(owner, lpVault);
+    await ctx.program.methods
+        .deposit(new anchor.BN(amount.toString()))
+        .accountsPartial({
+            owner,
+            ownerAssetAccount,
+            lpVault,
+            assetMint:         mint.publicKey,
+            assetTokenProgram: TOKEN_PROGRAM_ID,
+            allowlistedWallet: ctx.getAllowlistedWallet(owner, lpVault),
+        })
+        .preInstructions([
+            createAssociatedTokenAccountIdempotentInstruction(owner, ownerSharesAccount, owner, sharesMint, TOKEN_2022_PROGRAM_ID),
+        ])
+        .rpc();
+
+    // Shares can only be transferred to allowlisted wallets
+    const receiver = anchor.web3.Keypair.generate().publicKey;
+    const receiverSharesAccount = getAssociatedTokenAddressSync(sharesMint, receiver, false, TOKEN_2022_PROGRAM_ID);
+    await ctx.program.provider.sendAndConfirm(new anchor.web3.Transaction().add(
+        createAssociatedTokenAccountIdempotentInstruction(owner, receiverSharesAccount, receiver, sharesMint, TOKEN_2022_PROGRAM_ID),
+    ));
+    const transfer = async () => ctx.program.provider.sendAndConfirm(new anchor.web3.Transaction().add(
+        await createTransferCheckedWithTransferHookInstruction(
+            connection,
+            ownerSharesAccount,
+            sharesMint,
+            receiverSharesAccount,
+            owner,
+            BigInt(1_000),
+            6,
+            [],
+            "confirmed",
+            TOKEN_2022_PROGRAM_ID,
+        ),
+    ));
+
+    await expectError(transfer(), 6062, "WalletNotAllowlisted");
+    await ctx.allowlistWallet(receiver, lpVault);
+    await transfer();
+
+    const [receiverShares] = await getMultipleTokenAccounts(connection, [receiverSharesAccount], TOKEN_2022_PROGRAM_ID);
+    assert.equal(receiverShares.amount, BigInt(1_000));
+
+    // Staking escrows the shares without a hooked transfer
+    await ctx.initRewardPool(lpVault);
+    const [sharesBefore] = await getMultipleTokenAccounts(connection, [ownerSharesAccount], TOKEN_2022_PROGRAM_ID);
+    await ctx.stakeShares(BigInt(1_000), lpVault);
+    const rewardPool = await ctx.program.account.rewardPool.fetch(ctx.getRewardPool(lpVault));
+    const [sharesStaked, stakeVault] = await getMultipleTokenAccounts(
+        connection,
+        [ownerSharesAccount, rewardPool.stakeVault],
+        TOKEN_2022_PROGRAM_ID
+    );
+    assert.equal(sharesBefore.amount - sharesStaked.amount, BigInt(1_000));
+    assert.equal(stakeVault.amount, BigInt(1_000));
+
+    await ctx.unstakeShares(BigInt(1_000), lpVault);
+    const [sharesUnstaked] = await getMultipleTokenAccounts(connection, [ownerSharesAccount], TOKEN_2022_PROGRAM_ID);
+    assert.equal(sharesUnstaked.amount, sharesBefore.amount);
+};
+
+// Upper bound on the network fees paid by the owner for a native SOL instruction
+const MAX_TX_FEE = BigInt(10_000);
+const HELD_WSOL = BigInt(1_000);
+
+const getNativeVaultStates = async (ctx: NativeVaultContext) => {
+    const connection = ctx.program.provider.connection;
+    const [[vault], [ownerShares], [sharesMint], ownerLamports, ownerWsolAta] = await Promise.all([
+        getMultipleTokenAccounts(connection, [ctx.vault], TOKEN_PROGRAM_ID),
+        getMultipleTokenAccounts(connection, [ctx.ownerSharesAta], TOKEN_2022_PROGRAM_ID),
+        getMultipleMintAccounts(connection, [ctx.sharesMint], TOKEN_2022_PROGRAM_ID),
+        connection.getBalance(ctx.program.provider.publicKey),
+        getMultipleTokenAccounts(connection, [ctx.ownerWsolAta], TOKEN_PROGRAM_ID),
+    ]);
+    return {
+        vaultBalance:  vault.amount,
+        ownerShares:   ownerShares.amount,
+        sharesSupply:  sharesMint.supply,
+        ownerLamports: BigInt(ownerLamports),
+        ownerWsol:     ownerWsolAta[0].amount,
+    };
+};
+
+export const validateNativeDeposit = async (ctx: NativeVaultContext, lamports: bigint) => {
+    // wSOL the owner already holds must not be unwrapped by the native instructions
+    await ctx.wrapSol(HELD_WSOL);
+    const before = await getNativeVaultStates(ctx);
+
+    await ctx.depositNative(lamports);
+
+    const after = await getNativeVaultStates(ctx);
+    assert.equal(after.vaultBalance - before.vaultBalance, lamports);
+    assert.isTrue(after.ownerShares > before.ownerShares);
+    assert.equal(after.ownerShares - before.ownerShares, after.sharesSupply - before.sharesSupply);
+
+    // Exactly the deposited lamports were wrapped from the owner
+    const spent = before.ownerLamports - after.ownerLamports;
+    assert.isTrue(spent >= lamports && spent <= lamports + MAX_TX_FEE);
+    assert.equal(after.ownerWsol, before.ownerWsol);
+};
+
+export const validateNativeWithdraw = async (ctx: NativeVaultContext, lamports: bigint) => {
+    await ctx.wrapSol(HELD_WSOL);
+    const before = await getNativeVaultStates(ctx);
+
+    await ctx.withdrawNative(lamports);
+
+    const after = await getNativeVaultStates(ctx);
+    assert.equal(before.vaultBalance - after.vaultBalance, lamports);
+    assert.isTrue(after.ownerShares < before.ownerShares);
+
+    // Exactly the withdrawn assets were unwrapped to the owner, and the temporary account's rent
+    // was refunded
+    const received = after.ownerLamports - before.ownerLamports;
+    assert.isTrue(received <= lamports && received >= lamports - MAX_TX_FEE);
+    assert.equal(after.ownerWsol, before.ownerWsol);
+    assert.isNull(await ctx.program.provider.connection.getAccountInfo(ctx.nativeUnwrapAccount));
+};
+
+export const validateDepositForAndWithdrawTo = async (ctx: VaultContext, amount: bigint) => {
+    const connection = ctx.program.provider.connection;
+    const receiver = anchor.web3.Keypair.generate().publicKey;
+    const receiverSharesAta = getAssociatedTokenAddressSync(
+        await ctx.getSharesMint(),
+        receiver,
+        false,
+        TOKEN_2022_PROGRAM_ID,
+    );
+    const receiverAssetAta = getAssociatedTokenAddressSync(ctx.currency, receiver, false, TOKEN_PROGRAM_ID);
+    await ctx.program.provider.sendAndConfirm(new anchor.web3.Transaction().add(
+        createAssociatedTokenAccountIdempotentInstruction(
+            ctx.program.provider.publicKey,
+            receiverAssetAta,
+            receiver,
+            ctx.currency,
+            TOKEN_PROGRAM_ID,
+        ),
+    ));
+
+    // The owner pays for the deposit and the receiver gets the shares
+    const expectedShares = await ctx.previewDeposit(amount);
+    const statesBefore = await getVaultAccountStates(ctx);
+    await ctx.depositFor(receiver, amount);
+    const statesDeposited = await getVaultAccountStates(ctx);
+    const [receiverShares] = await getMultipleTokenAccounts(connection, [receiverSharesAta], TOKEN_2022_PROGRAM_ID);
+
+    assert.equal(statesBefore.ownerToken.amount - statesDeposited.ownerToken.amount, amount);
+    assert.equal(statesDeposited.vault.amount - statesBefore.vault.amount, amount);
+    assert.equal(statesDeposited.ownerShares.amount, statesBefore.ownerShares.amount);
+    assert.equal(receiverShares.amount, expectedShares);
+
+    // The owner burns its shares and the receiver gets the assets
+    const withdrawAmount = amount / BigInt(2);
+    const expectedSharesBurned = await ctx.previewWithdraw(withdrawAmount);
+    await ctx.withdrawTo(receiverAssetAta, withdrawAmount);
+    const statesWithdrawn = await getVaultAccountStates(ctx);
+    const [receiverAsset] = await getMultipleTokenAccounts(connection, [receiverAssetAta], TOKEN_PROGRAM_ID);
+
+    assert.equal(receiverAsset.amount, withdrawAmount);
+    assert.equal(statesWithdrawn.ownerToken.amount, statesDeposited.ownerToken.amount);
+    assert.equal(statesDeposited.ownerShares.amount - statesWithdrawn.ownerShares.amount, expectedSharesBurned);
+    assert.equal(statesDeposited.vault.amount - statesWithdrawn.vault.amount, withdrawAmount);
+};
+
+// Share prices are quoted as the assets per `SHARE_PRICE_SCALE` shares
+const SHARE_PRICE_SCALE = new anchor.BN(1_000_000_000);
+
+export const validateSharePriceHistory = async (ctx: VaultContext, amount: bigint) => {
+    await ctx.initSharePriceHistory();
+
+    // Once the history exists every deposit records the share price
+    await ctx.deposit(amount);
+
+    const [history, states] = await Promise.all([
+        ctx.program.account.sharePriceHistory.fetch(ctx.sharePriceHistory),
+        getVaultAccountStates(ctx),
+    ]);
+    const expectedPrice = SHARE_PRICE_SCALE
+        .mul(states.lpVault.totalAssets.add(VIRTUAL_ASSETS))
+        .div(new anchor.BN(states.sharesMint.supply.toString()).add(VIRTUAL_SHARES));
+    const latest = history.checkpoints[history.head];
+    assert.isTrue(history.lpVault.equals(ctx.lpVault));
+    assert.isAtLeast(history.len, 1);
+    assert.equal(latest.sharePrice.toString(), expectedPrice.toString());
+
+    // The history doesn't reach back a day
+    await expectError(ctx.sharePriceTwap(86_400), 6057, "InsufficientPriceHistory");
+    await expectError(ctx.sharePriceTwap(0), 6058, "InvalidTwapWindow");
+
+    // Once the latest checkpoint is older than the window, the TWAP is its price
+    await new Promise((resolve) => setTimeout(resolve, 2_000));
+    assert.equal((await ctx.sharePriceTwap(1)).toString(), expectedPrice.toString());
+};
+
+export const validateLiquidityMining = async (ctx: VaultContext) => {
+    const connection = ctx.program.provider.connection;
+    const funded = BigInt(1_000_000);
+    await ctx.initRewardPool();
+    await ctx.topUpRewardPool(funded, BigInt(1_000));
+
+    const sharesAta = await ctx.getUserSharesAta();
+    const [sharesBefore] = await getMultipleTokenAccounts(connection, [sharesAta], TOKEN_2022_PROGRAM_ID);
+    const staked = sharesBefore.amount / BigInt(2);
+    await ctx.stakeShares(staked);
+
+    const stake = await ctx.program.account.rewardStake.fetch(ctx.rewardStake);
+    assert.equal(stake.staked.toString(), staked.toString());
+    await expectError(ctx.unstakeShares(staked + BigInt(1)), 6064, "InsufficientStake");
+
+    await new Promise((resolve) => setTimeout(resolve, 2_000));
+    const [rewardsBefore] = await getMultipleTokenAccounts(connection, [ctx.ownerCollateralAta], TOKEN_PROGRAM_ID);
+    await ctx.claimRewards();
+    const [rewardsAfter] = await getMultipleTokenAccounts(connection, [ctx.ownerCollateralAta], TOKEN_PROGRAM_ID);
+
+    // The sole staker earns every emitted reward, never more than was funded
+    const claimed = rewardsAfter.amount - rewardsBefore.amount;
+    assert.isTrue(claimed > BigInt(0));
+    assert.isTrue(claimed <= funded);
+
+    await ctx.unstakeShares(staked);
+    const [sharesAfter] = await getMultipleTokenAccounts(connection, [sharesAta], TOKEN_2022_PROGRAM_ID);
+    assert.equal(sharesAfter.amount.toString(), sharesBefore.amount.toString());
+};
+
+export const validateDecommissionAndMigrate = async (ctx: VaultContext, amount: bigint) => {
+    const connection = ctx.program.provider.connection;
+    await ctx.deposit(amount);
+
+    await ctx.decommissionLpVault();
+    await expectError(ctx.decommissionLpVault(), 6065, "VaultDecommissioned");
+    await expectError(ctx.deposit(amount), 6048, "VaultDepositsPaused");
+    await expectError(ctx.closeLpVault(), 6067, "VaultNotWoundDown");
+
+    // The successor carries over the predecessor's configuration
+    await ctx.setDepositCaps(BigInt(1), BigInt(0));
+    const successor = await ctx.initSuccessorLpVault(1);
+    const before = await ctx.program.account.lpVault.fetch(ctx.lpVault);
+    assert.isTrue(before.successor.equals(successor));
+    const successorConfig = await ctx.program.account.lpVault.fetch(successor);
+    assert.equal(successorConfig.depositCap.toString(), "1");
+
+    // Migrating deposits into the successor, so its caps apply
+    const [sharesBefore] = await getMultipleTokenAccounts(connection, [ctx.ownerSharesAta], TOKEN_2022_PROGRAM_ID);
+    await expectError(ctx.migrateLpShares(successor, sharesBefore.amount), 6043, "DepositCapExceeded");
+
+    await ctx.setDepositCaps(BigInt("18446744073709551615"), BigInt(0), successor);
+    await ctx.migrateLpShares(successor, sharesBefore.amount);
+
+    const [after, successorVault] = await Promise.all([
+        ctx.program.account.lpVault.fetch(ctx.lpVault),
+        ctx.program.account.lpVault.fetch(successor),
+    ]);
+    const migrated = before.totalAssets.sub(after.totalAssets);
+    assert.equal(successorVault.version, 1);
+    assert.equal(successorVault.totalAssets.toString(), migrated.toString());
+
+    // Both vaults were priced 1:1, so the balance carries over share for share
+    const successorSharesAta = getAssociatedTokenAddressSync(
+        successorVault.sharesMint,
+        ctx.program.provider.publicKey,
+        false,
+        TOKEN_2022_PROGRAM_ID,
+    );
+    const [successorShares] = await getMultipleTokenAccounts(connection, [successorSharesAta], TOKEN_2022_PROGRAM_ID);
+    assert.equal(successorShares.amount.toString(), sharesBefore.amount.toString());
+
+    await ctx.closeLpVault();
+    assert.isNull(await connection.getAccountInfo(ctx.lpVault));
+    assert.isNull(await connection.getAccountInfo(ctx.vault));
+};
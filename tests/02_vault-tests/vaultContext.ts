@@ -1,20 +1,25 @@
 import { TestContext } from "../testContext";
 import {
     createAssociatedTokenAccountIdempotentInstruction,
+    createMintToCheckedInstruction,
     getAssociatedTokenAddressSync,
     TOKEN_2022_PROGRAM_ID,
     TOKEN_PROGRAM_ID
 } from "@solana/spl-token";
 import { BN, web3 } from "@coral-xyz/anchor";
-import { superAdminProgram } from "../hooks/rootHook";
+import { superAdminProgram, superAdminPermission } from "../hooks/rootHook";
+import { MPL_TOKEN_METADATA_PROGRAM_ID } from "@metaplex-foundation/mpl-token-metadata";
 
 export class VaultContext extends TestContext {
-    constructor() {
-        super(); // initializes the lp vault
+    /** True once the vault's share price history is initialised */
+    hasSharePriceHistory = false;
+
+    constructor(currencyTokenProgram = TOKEN_PROGRAM_ID) {
+        super(undefined, currencyTokenProgram); // initializes the lp vault
     }
 
-    async generate() {
-        await this._generate();
+    async generate(useTokenMetadata = false) {
+        await this._generate(useTokenMetadata);
         return this;
     }
 
@@ -25,6 +30,51 @@ export class VaultContext extends TestContext {
             .rpc();
     }
 
+    async depositWithMinShares(amount: bigint, minShares: bigint) {
+        return await this.program.methods
+            .depositWithMinShares(new BN(amount.toString()), new BN(minShares.toString()))
+            .accountsPartial(this.getVaultAccounts())
+            .rpc();
+    }
+
+    async previewDeposit(amount: bigint): Promise<bigint> {
+        const shares = await this.program.methods
+            .previewDeposit(new BN(amount.toString()))
+            .accountsPartial({lpVault: this.lpVault, sharesMint: await this.getSharesMint()})
+            .view();
+        return BigInt(shares.toString());
+    }
+
+    async previewWithdraw(amount: bigint, depositor: web3.PublicKey | null = null): Promise<bigint> {
+        const shares = await this.program.methods
+            .previewWithdraw(new BN(amount.toString()))
+            .accountsPartial({lpVault: this.lpVault, sharesMint: await this.getSharesMint(), depositor})
+            .view();
+        return BigInt(shares.toString());
+    }
+
+    async previewRedeem(shares: bigint, depositor: web3.PublicKey | null = null): Promise<bigint> {
+        const assets = await this.program.methods
+            .previewRedeem(new BN(shares.toString()))
+            .accountsPartial({lpVault: this.lpVault, sharesMint: await this.getSharesMint(), depositor})
+            .view();
+        return BigInt(assets.toString());
+    }
+
+    async maxWithdraw(depositor: web3.PublicKey | null = null): Promise<bigint> {
+        const assets = await this.program.methods
+            .maxWithdraw()
+            .accountsPartial({
+                owner: this.program.provider.publicKey,
+                lpVault: this.lpVault,
+                vault: this.vault,
+                sharesMint: await this.getSharesMint(),
+                depositor,
+            })
+            .view();
+        return BigInt(assets.toString());
+    }
+
     async withdraw(amount: bigint) {
         return await this.program.methods
             .withdraw(new BN(amount.toString()))
@@ -32,6 +82,54 @@ export class VaultContext extends TestContext {
             .rpc();
     };
 
+    async redeem(shares: bigint) {
+        return await this.program.methods
+            .redeem(new BN(shares.toString()))
+            .accountsPartial(this.getVaultAccounts())
+            .rpc();
+    }
+
+    /** Deposits from the owner, minting the shares to `receiver` */
+    async depositFor(receiver: web3.PublicKey, amount: bigint, minShares = BigInt(0)) {
+        const sharesMint = await this.getSharesMint();
+        const receiverSharesAccount = getAssociatedTokenAddressSync(
+            sharesMint,
+            receiver,
+            false,
+            TOKEN_2022_PROGRAM_ID,
+        );
+        return await this.program.methods
+            .depositFor(new BN(amount.toString()), new BN(minShares.toString()))
+            .accountsPartial({
+                receiver,
+                receiverSharesAccount,
+                ...this.getVaultAccounts(),
+            })
+            .preInstructions([
+                createAssociatedTokenAccountIdempotentInstruction(
+                    this.program.provider.publicKey,
+                    receiverSharesAccount,
+                    receiver,
+                    sharesMint,
+                    TOKEN_2022_PROGRAM_ID,
+                ),
+            ])
+            .rpc();
+    }
+
+    /** Withdraws the owner's assets to `receiverAssetAccount` */
+    async withdrawTo(
+        receiverAssetAccount: web3.PublicKey,
+        amount: bigint,
+        maxShares = BigInt("18446744073709551615"),
+    ) {
+        const {ownerAssetAccount, ...accounts} = this.getVaultAccounts();
+        return await this.program.methods
+            .withdrawTo(new BN(amount.toString()), new BN(maxShares.toString()))
+            .accountsPartial({receiverAssetAccount, ...accounts})
+            .rpc();
+    }
+
     async donate(amount: bigint) {
         const permission = web3.PublicKey.findProgramAddressSync(
             [Buffer.from("admin"), this.program.provider.publicKey.toBuffer()],
@@ -43,6 +141,7 @@ export class VaultContext extends TestContext {
             canInitVaults: false, // 1
             canLiquidate: false, // 2
             canBorrowFromVaults: false, // 8
+            canManageVaults: false, // 32
             canInitPools: false,
             status: { active: {} }
         })
@@ -57,18 +156,578 @@ export class VaultContext extends TestContext {
                 owner: this.program.provider.publicKey,
                 permission,
                 currency: this.currency,
-                tokenProgram: TOKEN_PROGRAM_ID,
+                tokenProgram: this.currencyTokenProgram,
                 ...this.getVaultAccounts()
             })
             .rpc();
     };
 
+    get depositor() {
+        return this.getDepositor(this.program.provider.publicKey);
+    }
+
+    getDepositor(wallet: web3.PublicKey) {
+        return web3.PublicKey.findProgramAddressSync(
+            [Buffer.from("depositor"), this.lpVault.toBuffer(), wallet.toBuffer()],
+            this.program.programId
+        )[0];
+    }
+
+    async initDepositor() {
+        return await this.program.methods
+            .initDepositor()
+            .accountsPartial({
+                owner:   this.program.provider.publicKey,
+                lpVault: this.lpVault,
+            })
+            .rpc();
+    }
+
+    async depositWithDepositor(amount: bigint) {
+        return await this.program.methods
+            .deposit(new BN(amount.toString()))
+            .accountsPartial({...this.getVaultAccounts(), depositor: this.depositor})
+            .rpc();
+    }
+
+    async withdrawWithDepositor(amount: bigint) {
+        return await this.program.methods
+            .withdraw(new BN(amount.toString()))
+            .accountsPartial({...this.getVaultAccounts(), depositor: this.depositor})
+            .rpc();
+    }
+
+    async setVaultCooldown(lpCooldown: number, exitFeeBps: number) {
+        return await superAdminProgram.methods
+            .setVaultCooldown(new BN(lpCooldown), exitFeeBps)
+            .accountsPartial({
+                authority:  superAdminProgram.provider.publicKey,
+                permission: superAdminPermission,
+                lpVault:    this.lpVault,
+            })
+            .rpc();
+    }
+
+    async setDepositCaps(depositCap: bigint, walletDepositCap: bigint, lpVault = this.lpVault) {
+        return await superAdminProgram.methods
+            .setVaultDepositCaps(new BN(depositCap.toString()), new BN(walletDepositCap.toString()))
+            .accountsPartial({
+                authority:  superAdminProgram.provider.publicKey,
+                permission: superAdminPermission,
+                lpVault,
+            })
+            .rpc();
+    }
+
+    async setVaultStatus(statuses: number) {
+        return await superAdminProgram.methods
+            .setLpVaultStatus(statuses)
+            .accountsPartial({
+                authority:  superAdminProgram.provider.publicKey,
+                lpVault:    this.lpVault,
+                superAdmin: superAdminPermission,
+            })
+            .rpc();
+    }
+
+    async getFeeSharesAta() {
+        return getAssociatedTokenAddressSync(
+            await this.getSharesMint(),
+            superAdminProgram.provider.publicKey,
+            false,
+            TOKEN_2022_PROGRAM_ID,
+        );
+    }
+
+    async setVaultFees(performanceFeeBps: number, managementFeeBps: number) {
+        const sharesMint = await this.getSharesMint();
+        const feeSharesAccount = await this.getFeeSharesAta();
+        return await superAdminProgram.methods
+            .setVaultFees(performanceFeeBps, managementFeeBps)
+            .accountsPartial({
+                authority:          superAdminProgram.provider.publicKey,
+                permission:         superAdminPermission,
+                lpVault:            this.lpVault,
+                sharesMint,
+                feeSharesAccount,
+                sharesTokenProgram: TOKEN_2022_PROGRAM_ID,
+            })
+            .preInstructions([
+                createAssociatedTokenAccountIdempotentInstruction(
+                    superAdminProgram.provider.publicKey,
+                    feeSharesAccount,
+                    superAdminProgram.provider.publicKey,
+                    sharesMint,
+                    TOKEN_2022_PROGRAM_ID,
+                ),
+            ])
+            .rpc();
+    }
+
+    async setVaultVestingPeriod(vestingPeriod: number) {
+        return await superAdminProgram.methods
+            .setVaultVestingPeriod(new BN(vestingPeriod))
+            .accountsPartial({
+                authority:  superAdminProgram.provider.publicKey,
+                permission: superAdminPermission,
+                lpVault:    this.lpVault,
+            })
+            .rpc();
+    }
+
+    async crystallizeVaultFees() {
+        return await this.program.methods
+            .crystallizeVaultFees()
+            .accountsPartial({
+                lpVault:            this.lpVault,
+                sharesMint:         await this.getSharesMint(),
+                feeSharesAccount:   await this.getFeeSharesAta(),
+                sharesTokenProgram: TOKEN_2022_PROGRAM_ID,
+            })
+            .rpc();
+    }
+
+    async getSharesMetadata() {
+        const tokenMetadataProgram = new web3.PublicKey(MPL_TOKEN_METADATA_PROGRAM_ID);
+        return web3.PublicKey.findProgramAddressSync(
+            [Buffer.from("metadata"), tokenMetadataProgram.toBuffer(), (await this.getSharesMint()).toBuffer()],
+            tokenMetadataProgram
+        )[0];
+    }
+
+    async updateSharesMetadata(name: string, symbol: string, uri: string) {
+        return await superAdminProgram.methods
+            .updateSharesMetadata({name, symbol, uri})
+            .accountsPartial({
+                payer:                superAdminProgram.provider.publicKey,
+                authority:            superAdminProgram.provider.publicKey,
+                permission:           superAdminPermission,
+                lpVault:              this.lpVault,
+                sharesMint:           await this.getSharesMint(),
+                sharesMetadata:       await this.getSharesMetadata(),
+                tokenMetadataProgram: MPL_TOKEN_METADATA_PROGRAM_ID,
+                sysvarInstructions:   web3.SYSVAR_INSTRUCTIONS_PUBKEY,
+            })
+            .rpc();
+    }
+
+    getAllowedMint(mint: web3.PublicKey) {
+        return web3.PublicKey.findProgramAddressSync(
+            [Buffer.from("allowed_mint"), mint.toBuffer()],
+            this.program.programId
+        )[0];
+    }
+
+    async setMintPolicy(rejectedFeatures: number) {
+        return await superAdminProgram.methods
+            .setMintPolicy(rejectedFeatures)
+            .accountsPartial({
+                authority:  superAdminProgram.provider.publicKey,
+                superAdmin: superAdminPermission,
+            })
+            .rpc();
+    }
+
+    async allowMint(mint: web3.PublicKey) {
+        return await superAdminProgram.methods
+            .allowMint()
+            .accountsPartial({
+                authority:  superAdminProgram.provider.publicKey,
+                superAdmin: superAdminPermission,
+                mint,
+            })
+            .rpc();
+    }
+
+    async removeAllowedMint(mint: web3.PublicKey) {
+        return await superAdminProgram.methods
+            .removeAllowedMint()
+            .accountsPartial({
+                authority:   superAdminProgram.provider.publicKey,
+                superAdmin:  superAdminPermission,
+                allowedMint: this.getAllowedMint(mint),
+            })
+            .rpc();
+    }
+
+    /** Inits an LP Vault for `assetMint`, passing its allowlist entry if `allowed` */
+    async initLpVaultFor(assetMint: web3.PublicKey, allowed: boolean) {
+        const lpVault = web3.PublicKey.findProgramAddressSync(
+            [Buffer.from("lp_vault"), assetMint.toBuffer()],
+            this.program.programId
+        )[0];
+        const vault = getAssociatedTokenAddressSync(assetMint, lpVault, true, TOKEN_PROGRAM_ID);
+        return await superAdminProgram.methods
+            .initLpVaultWithTokenMetadata({name: "PLACEHOLDER", symbol: "PLC", uri: "https://placeholder.com"})
+            .accountsPartial({
+                payer:             superAdminProgram.provider.publicKey,
+                authority:         superAdminProgram.provider.publicKey,
+                permission:        superAdminPermission,
+                lpVault,
+                vault,
+                assetMint,
+                allowedAssetMint:  allowed ? this.getAllowedMint(assetMint) : null,
+                assetTokenProgram: TOKEN_PROGRAM_ID,
+            })
+            .preInstructions([
+                createAssociatedTokenAccountIdempotentInstruction(
+                    superAdminProgram.provider.publicKey,
+                    vault,
+                    lpVault,
+                    assetMint,
+                    TOKEN_PROGRAM_ID,
+                ),
+            ])
+            .rpc();
+    }
+
+    getAllowlistedWallet(wallet: web3.PublicKey, lpVault = this.lpVault) {
+        return web3.PublicKey.findProgramAddressSync(
+            [Buffer.from("allowlisted_wallet"), lpVault.toBuffer(), wallet.toBuffer()],
+            this.program.programId
+        )[0];
+    }
+
+    async setVaultAllowlist(allowlistEnabled: boolean) {
+        return await superAdminProgram.methods
+            .setVaultAllowlist(allowlistEnabled)
+            .accountsPartial({
+                authority:  superAdminProgram.provider.publicKey,
+                permission: superAdminPermission,
+                lpVault:    this.lpVault,
+            })
+            .rpc();
+    }
+
+    async allowlistWallet(wallet: web3.PublicKey, lpVault = this.lpVault) {
+        return await superAdminProgram.methods
+            .allowlistWallet()
+            .accountsPartial({
+                authority:  superAdminProgram.provider.publicKey,
+                permission: superAdminPermission,
+                lpVault,
+                wallet,
+            })
+            .rpc();
+    }
+
+    async removeAllowlistedWallet(wallet: web3.PublicKey, lpVault = this.lpVault) {
+        return await superAdminProgram.methods
+            .removeAllowlistedWallet()
+            .accountsPartial({
+                authority:         superAdminProgram.provider.publicKey,
+                permission:        superAdminPermission,
+                lpVault,
+                allowlistedWallet: this.getAllowlistedWallet(wallet, lpVault),
+            })
+            .rpc();
+    }
+
+    async depositWithAllowlist(amount: bigint) {
+        return await this.program.methods
+            .deposit(new BN(amount.toString()))
+            .accountsPartial({
+                ...this.getVaultAccounts(),
+                allowlistedWallet: this.getAllowlistedWallet(this.program.provider.publicKey),
+            })
+            .rpc();
+    }
+
+    /** Inits an allowlisted vault for `assetMint`, whose shares mint has the transfer hook */
+    async initAllowlistedLpVault(assetMint: web3.PublicKey) {
+        const lpVault = web3.PublicKey.findProgramAddressSync(
+            [Buffer.from("lp_vault"), assetMint.toBuffer()],
+            this.program.programId
+        )[0];
+        const vault = getAssociatedTokenAddressSync(assetMint, lpVault, true, TOKEN_PROGRAM_ID);
+        await superAdminProgram.methods
+            .initAllowlistedLpVault({name: "PLACEHOLDER", symbol: "PLC", uri: "https://placeholder.com"})
+            .accountsPartial({
+                payer:             superAdminProgram.provider.publicKey,
+                authority:         superAdminProgram.provider.publicKey,
+                permission:        superAdminPermission,
+                lpVault,
+                vault,
+                assetMint,
+                assetTokenProgram: TOKEN_PROGRAM_ID,
+            })
+            .preInstructions([
+                createAssociatedTokenAccountIdempotentInstruction(
+                    superAdminProgram.provider.publicKey,
+                    vault,
+                    lpVault,
+                    assetMint,
+                    TOKEN_PROGRAM_ID,
+                ),
+            ])
+            .rpc();
+        return lpVault;
+    }
+
+    async decommissionLpVault() {
+        return await superAdminProgram.methods
+            .decommissionLpVault()
+            .accountsPartial({
+                authority:  superAdminProgram.provider.publicKey,
+                lpVault:    this.lpVault,
+                superAdmin: superAdminPermission,
+            })
+            .rpc();
+    }
+
+    async closeLpVault() {
+        const authorityAssetAccount = getAssociatedTokenAddressSync(
+            this.currency,
+            superAdminProgram.provider.publicKey,
+            false,
+            this.currencyTokenProgram,
+        );
+        return await superAdminProgram.methods
+            .closeLpVault()
+            .accountsPartial({
+                authority:         superAdminProgram.provider.publicKey,
+                superAdmin:        superAdminPermission,
+                lpVault:           this.lpVault,
+                vault:             this.vault,
+                assetMint:         this.currency,
+                sharesMint:        this.sharesMint,
+                authorityAssetAccount,
+                withdrawalQueue:   this.withdrawalQueue,
+                assetTokenProgram: this.currencyTokenProgram,
+            })
+            .preInstructions([
+                createAssociatedTokenAccountIdempotentInstruction(
+                    superAdminProgram.provider.publicKey,
+                    authorityAssetAccount,
+                    superAdminProgram.provider.publicKey,
+                    this.currency,
+                    this.currencyTokenProgram,
+                ),
+            ])
+            .rpc();
+    }
+
+    getSuccessorLpVault(version: number) {
+        return web3.PublicKey.findProgramAddressSync(
+            [Buffer.from("lp_vault"), this.currency.toBuffer(), Buffer.from([version])],
+            this.program.programId
+        )[0];
+    }
+
+    async initSuccessorLpVault(version: number) {
+        const lpVault = this.getSuccessorLpVault(version);
+        const vault = getAssociatedTokenAddressSync(this.currency, lpVault, true, this.currencyTokenProgram);
+        await superAdminProgram.methods
+            .initSuccessorLpVault(version, {name: "PLACEHOLDER", symbol: "PLC", uri: "https://placeholder.com"})
+            .accountsPartial({
+                payer:              superAdminProgram.provider.publicKey,
+                authority:          superAdminProgram.provider.publicKey,
+                permission:         superAdminPermission,
+                predecessorLpVault:    this.lpVault,
+                predecessorSharesMint: this.sharesMint,
+                lpVault,
+                vault,
+                assetMint:             this.currency,
+                assetTokenProgram:     this.currencyTokenProgram,
+            })
+            .preInstructions([
+                createAssociatedTokenAccountIdempotentInstruction(
+                    superAdminProgram.provider.publicKey,
+                    vault,
+                    lpVault,
+                    this.currency,
+                    this.currencyTokenProgram,
+                ),
+            ])
+            .rpc();
+        return lpVault;
+    }
+
+    async migrateLpShares(successor: web3.PublicKey, sharesAmount: bigint, minShares = BigInt(0)) {
+        const successorVault = await this.program.account.lpVault.fetch(successor);
+        return await this.program.methods
+            .migrateLpShares(new BN(sharesAmount.toString()), new BN(minShares.toString()))
+            .accountsPartial({
+                owner:               this.program.provider.publicKey,
+                lpVault:             this.lpVault,
+                vault:               this.vault,
+                sharesMint:          this.sharesMint,
+                successor,
+                successorVault:      successorVault.vault,
+                successorSharesMint: successorVault.sharesMint,
+                withdrawalQueue:     this.queueVault ? this.withdrawalQueue : null,
+                queueVault:          this.queueVault,
+                successorWithdrawalQueue: null,
+                successorQueueVault: null,
+                successorDepositor:  null,
+                successorAllowlistedWallet: null,
+                assetMint:           this.currency,
+                assetTokenProgram:   this.currencyTokenProgram,
+                sharesTokenProgram:  TOKEN_2022_PROGRAM_ID,
+            })
+            .rpc();
+    }
+
+    get sharePriceHistory() {
+        return web3.PublicKey.findProgramAddressSync(
+            [Buffer.from("share_price_history"), this.lpVault.toBuffer()],
+            this.program.programId
+        )[0];
+    }
+
+    async initSharePriceHistory() {
+        const signature = await superAdminProgram.methods
+            .initSharePriceHistory()
+            .accountsPartial({
+                payer:       superAdminProgram.provider.publicKey,
+                authority:   superAdminProgram.provider.publicKey,
+                permission:  superAdminPermission,
+                lpVault:     this.lpVault,
+                sharesMint:  await this.getSharesMint(),
+            })
+            .rpc();
+        this.hasSharePriceHistory = true;
+        return signature;
+    }
+
+    async sharePriceTwap(window: number): Promise<bigint> {
+        const twap = await this.program.methods
+            .sharePriceTwap(window)
+            .accountsPartial({sharePriceHistory: this.sharePriceHistory})
+            .view();
+        return BigInt(twap.toString());
+    }
+
+    /** Reward pool paying out the collateral token to staked LP shares */
+    get rewardPool() {
+        return this.getRewardPool();
+    }
+
+    getRewardPool(lpVault = this.lpVault) {
+        return web3.PublicKey.findProgramAddressSync(
+            [Buffer.from("reward_pool"), lpVault.toBuffer(), this.collateral.toBuffer()],
+            this.program.programId
+        )[0];
+    }
+
+    get rewardStake() {
+        return web3.PublicKey.findProgramAddressSync(
+            [Buffer.from("reward_stake"), this.rewardPool.toBuffer(), this.program.provider.publicKey.toBuffer()],
+            this.program.programId
+        )[0];
+    }
+
+    async initRewardPool(lpVault = this.lpVault) {
+        const sharesMint = await this.program.account.lpVault.fetch(lpVault).then(v => v.sharesMint);
+        return await superAdminProgram.methods
+            .initRewardPool()
+            .accountsPartial({
+                payer:              superAdminProgram.provider.publicKey,
+                authority:          superAdminProgram.provider.publicKey,
+                permission:         superAdminPermission,
+                lpVault,
+                sharesMint,
+                rewardMint:         this.collateral,
+                rewardTokenProgram: TOKEN_PROGRAM_ID,
+                sharesTokenProgram: TOKEN_2022_PROGRAM_ID,
+            })
+            .rpc();
+    }
+
+    async topUpRewardPool(amount: bigint, emissionRate: bigint) {
+        const authorityRewardAccount = getAssociatedTokenAddressSync(
+            this.collateral,
+            superAdminProgram.provider.publicKey,
+            false,
+            TOKEN_PROGRAM_ID,
+        );
+        const fundTx = new web3.Transaction().add(
+            createAssociatedTokenAccountIdempotentInstruction(
+                this.program.provider.publicKey,
+                authorityRewardAccount,
+                superAdminProgram.provider.publicKey,
+                this.collateral,
+                TOKEN_PROGRAM_ID,
+            ),
+            createMintToCheckedInstruction(
+                this.collateral,
+                authorityRewardAccount,
+                this.program.provider.publicKey,
+                amount,
+                6,
+                [],
+                TOKEN_PROGRAM_ID,
+            ),
+        );
+        await this.program.provider.sendAndConfirm(fundTx);
+
+        const rewardPool = await this.program.account.rewardPool.fetch(this.rewardPool);
+        return await superAdminProgram.methods
+            .topUpRewardPool(new BN(amount.toString()), new BN(emissionRate.toString()))
+            .accountsPartial({
+                authority:          superAdminProgram.provider.publicKey,
+                permission:         superAdminPermission,
+                rewardPool:         this.rewardPool,
+                rewardVault:        rewardPool.rewardVault,
+                rewardMint:         this.collateral,
+                authorityRewardAccount,
+                rewardTokenProgram: TOKEN_PROGRAM_ID,
+            })
+            .rpc();
+    }
+
+    async stakeShares(amount: bigint, lpVault = this.lpVault) {
+        const rewardPool = await this.program.account.rewardPool.fetch(this.getRewardPool(lpVault));
+        return await this.program.methods
+            .stakeShares(new BN(amount.toString()))
+            .accountsPartial({
+                owner:              this.program.provider.publicKey,
+                rewardPool:         this.getRewardPool(lpVault),
+                lpVault,
+                stakeVault:         rewardPool.stakeVault,
+                sharesMint:         rewardPool.sharesMint,
+                sharesTokenProgram: TOKEN_2022_PROGRAM_ID,
+            })
+            .rpc();
+    }
+
+    async unstakeShares(amount: bigint, lpVault = this.lpVault) {
+        const rewardPool = await this.program.account.rewardPool.fetch(this.getRewardPool(lpVault));
+        return await this.program.methods
+            .unstakeShares(new BN(amount.toString()))
+            .accountsPartial({
+                owner:              this.program.provider.publicKey,
+                rewardPool:         this.getRewardPool(lpVault),
+                lpVault,
+                stakeVault:         rewardPool.stakeVault,
+                sharesMint:         rewardPool.sharesMint,
+                sharesTokenProgram: TOKEN_2022_PROGRAM_ID,
+            })
+            .rpc();
+    }
+
+    async claimRewards() {
+        const rewardPool = await this.program.account.rewardPool.fetch(this.rewardPool);
+        return await this.program.methods
+            .claimRewards()
+            .accountsPartial({
+                owner:              this.program.provider.publicKey,
+                ownerRewardAccount: this.ownerCollateralAta,
+                rewardPool:         this.rewardPool,
+                rewardVault:        rewardPool.rewardVault,
+                rewardMint:         this.collateral,
+                rewardStake:        this.rewardStake,
+                rewardTokenProgram: TOKEN_PROGRAM_ID,
+            })
+            .rpc();
+    }
+
     getVaultAccounts() {
         const ownerAssetAccount = getAssociatedTokenAddressSync(
             this.currency,
             this.program.provider.publicKey,
             false,
-            TOKEN_PROGRAM_ID
+            this.currencyTokenProgram
         );
 
         return {
@@ -76,7 +735,10 @@ export class VaultContext extends TestContext {
             ownerAssetAccount,
             lpVault: this.lpVault,
             assetMint: this.currency,
-            assetTokenProgram: TOKEN_PROGRAM_ID,
+            withdrawalQueue: this.queueVault ? this.withdrawalQueue : null,
+            queueVault: this.queueVault,
+            sharePriceHistory: this.hasSharePriceHistory ? this.sharePriceHistory : null,
+            assetTokenProgram: this.currencyTokenProgram,
         }
     }
 
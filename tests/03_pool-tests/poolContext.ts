@@ -20,8 +20,9 @@ export class PoolContext extends TestContext {
             Buffer.from('admin'),
             NON_INIT_AUTHORITY.publicKey.toBuffer(),
         ], WASABI_PROGRAM_ID)[0],
+        currencyTokenProgram = TOKEN_PROGRAM_ID,
     ) {
-        super();
+        super(undefined, currencyTokenProgram);
     }
 
     async generate(): Promise<this> {
@@ -44,6 +45,7 @@ export class PoolContext extends TestContext {
                 canLiquidate:        false, // 2
                 canInitPools:        true, // 8
                 canBorrowFromVaults: false,
+                canManageVaults: false,
                 status:              {active: {}}
             })
             .accounts({
@@ -59,6 +61,7 @@ export class PoolContext extends TestContext {
                 canLiquidate:        true, // 2
                 canInitPools:        false, // 8
                 canBorrowFromVaults: true,
+                canManageVaults: true,
                 status:              {active: {}}
             })
             .accounts({
@@ -76,7 +79,7 @@ export class PoolContext extends TestContext {
             collateral:             this.collateral,
             currency:               this.currency,
             collateralTokenProgram: TOKEN_PROGRAM_ID,
-            currencyTokenProgram:   TOKEN_PROGRAM_ID,
+            currencyTokenProgram:   this.currencyTokenProgram,
         };
     }
 
@@ -86,7 +89,7 @@ export class PoolContext extends TestContext {
                 this.currency,
                 this.longPool,
                 true,
-                TOKEN_PROGRAM_ID
+                this.currencyTokenProgram
             ),
             collateralVault: getAssociatedTokenAddressSync(
                 this.collateral,
@@ -99,7 +102,7 @@ export class PoolContext extends TestContext {
                 this.currency,
                 this.shortPool,
                 true,
-                TOKEN_PROGRAM_ID
+                this.currencyTokenProgram
             ),
             collateralVault: getAssociatedTokenAddressSync(
                 this.collateral,
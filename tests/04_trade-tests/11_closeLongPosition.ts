@@ -1,5 +1,10 @@
+import {TOKEN_2022_PROGRAM_ID} from "@solana/spl-token";
 import {TradeContext} from "./tradeContext";
-import {validateCloseLongPosition} from "./validateTrade";
+import {
+    validateClaimLongPosition,
+    validateCloseLongPosition,
+    validateCloseLongPositionFillsQueuedWithdrawal
+} from "./validateTrade";
 import {
     closeLongPositionWithIncorrectOwner,
     closeLongPositionWithoutCosigner,
@@ -51,4 +56,35 @@ describe("CloseLongPosition", () => {
             await validateCloseLongPosition(ctx);
         });
     });
+
+    describe("with a queued withdrawal", () => {
+        let queueCtx: TradeContext;
+
+        before(async () => {
+            queueCtx = await new TradeContext().generateLongTestWithDefaultPosition();
+            await queueCtx.initWithdrawalQueue();
+        });
+
+        it("should reserve the repayment for the queued withdrawal", async () => {
+            await validateCloseLongPositionFillsQueuedWithdrawal(queueCtx);
+        });
+    });
+
+    describe("with a transfer fee currency", () => {
+        const newTransferFeeContext = () => new TradeContext(
+            undefined,
+            undefined,
+            undefined,
+            undefined,
+            TOKEN_2022_PROGRAM_ID
+        ).generateLongTestWithDefaultPosition();
+
+        it("should credit the LP vault with the repayment net of the transfer fee", async () => {
+            await validateCloseLongPosition(await newTransferFeeContext());
+        });
+
+        it("should credit the LP vault with the claim net of the transfer fee", async () => {
+            await validateClaimLongPosition(await newTransferFeeContext());
+        });
+    });
 });
@@ -5,6 +5,7 @@ import {
     DEFAULT_AUTHORITY,
     feeWalletKeypair,
     liquidationWalletKeypair,
+    superAdminPermission,
     superAdminProgram,
     WASABI_PROGRAM_ID
 } from "../hooks/rootHook";
@@ -108,8 +109,9 @@ export class TradeContext extends PoolContext {
             anchor.utils.bytes.utf8.encode('admin'),
             NON_SWAP_AUTHORITY.publicKey.toBuffer(),
         ], WASABI_PROGRAM_ID)[0],
+        currencyTokenProgram = TOKEN_PROGRAM_ID,
     ) {
-        super();
+        super(undefined, undefined, undefined, undefined, currencyTokenProgram);
     }
 
     isLongTest = true;
@@ -133,7 +135,13 @@ export class TradeContext extends PoolContext {
     async generateLongTestWithDefaultPosition(): Promise<this> {
         this.isCloseTest = true;
         await this.generateLongTest();
-        await this.openLongPosition();
+
+        // Only what the pool receives net of any transfer fee can be swapped
+        const {downPayment, principal} = defaultOpenLongPositionArgs;
+        await this.openLongPosition({
+            ...defaultOpenLongPositionArgs,
+            swapIn: downPayment - this.transferFee(downPayment) + principal - this.transferFee(principal),
+        });
 
         return this;
     }
@@ -166,7 +174,7 @@ export class TradeContext extends PoolContext {
             this.currency,
             feeWalletKeypair.publicKey,
             false,
-            TOKEN_PROGRAM_ID
+            this.currencyTokenProgram
         ) : getAssociatedTokenAddressSync(
             this.collateral,
             feeWalletKeypair.publicKey,
@@ -178,7 +186,7 @@ export class TradeContext extends PoolContext {
             this.currency,
             liquidationWalletKeypair.publicKey,
             false,
-            TOKEN_PROGRAM_ID
+            this.currencyTokenProgram
         ) : getAssociatedTokenAddressSync(
             this.collateral,
             liquidationWalletKeypair.publicKey,
@@ -191,6 +199,7 @@ export class TradeContext extends PoolContext {
             this.feeWallet,
             feeWalletKeypair.publicKey,
             this.isLongTest ? this.currency : this.collateral,
+            this.isLongTest ? this.currencyTokenProgram : TOKEN_PROGRAM_ID,
         );
 
         const liqWalletIx = createAssociatedTokenAccountIdempotentInstruction(
@@ -198,6 +207,7 @@ export class TradeContext extends PoolContext {
             this.liquidationWallet,
             liquidationWalletKeypair.publicKey,
             this.isLongTest ? this.currency : this.collateral,
+            this.isLongTest ? this.currencyTokenProgram : TOKEN_PROGRAM_ID,
         );
 
         const transferIx = SystemProgram.transfer({
@@ -220,6 +230,7 @@ export class TradeContext extends PoolContext {
                 canLiquidate:        true, // 2
                 canInitPools:        false, // 8
                 canBorrowFromVaults: false,
+                canManageVaults: false,
                 status:              {active: {}}
             })
             .accounts({
@@ -234,6 +245,7 @@ export class TradeContext extends PoolContext {
                 canLiquidate:        false, // 2
                 canInitPools:        true, // 8
                 canBorrowFromVaults: true,
+                canManageVaults: true,
                 status:              {active: {}}
             })
             .accounts({
@@ -262,7 +274,7 @@ export class TradeContext extends PoolContext {
             )[0];
 
             this.longPoolCurrencyVault =
-                getAssociatedTokenAddressSync(this.currency, this.longPool, true, TOKEN_PROGRAM_ID);
+                getAssociatedTokenAddressSync(this.currency, this.longPool, true, this.currencyTokenProgram);
             this.longPoolCollateralVault =
                 getAssociatedTokenAddressSync(this.collateral, this.longPool, true, TOKEN_PROGRAM_ID);
 
@@ -282,7 +294,7 @@ export class TradeContext extends PoolContext {
             )[0];
 
             this.shortPoolCurrencyVault =
-                getAssociatedTokenAddressSync(this.currency, this.shortPool, true, TOKEN_PROGRAM_ID);
+                getAssociatedTokenAddressSync(this.currency, this.shortPool, true, this.currencyTokenProgram);
             this.shortPoolCollateralVault =
                 getAssociatedTokenAddressSync(this.collateral, this.shortPool, true, TOKEN_PROGRAM_ID);
 
@@ -332,7 +344,7 @@ export class TradeContext extends PoolContext {
                 owner:             this.program.provider.publicKey,
                 lpVault:           this.lpVault,
                 assetMint:         this.currency,
-                assetTokenProgram: TOKEN_PROGRAM_ID
+                assetTokenProgram: this.currencyTokenProgram
             })
             .preInstructions([createOwnerSharesAccount])
             .rpc();
@@ -340,6 +352,17 @@ export class TradeContext extends PoolContext {
         return this;
     }
 
+    async setPoolStatus(statuses: number) {
+        return await superAdminProgram.methods
+            .setPoolStatus(statuses)
+            .accountsPartial({
+                authority:  superAdminProgram.provider.publicKey,
+                pool:       this.isLongTest ? this.longPool : this.shortPool,
+                superAdmin: superAdminPermission,
+            })
+            .rpc();
+    }
+
     async send(instructions: TransactionInstruction[], signer: Keypair) {
         const connection = this.program.provider.connection;
         const message = new anchor.web3.TransactionMessage({
@@ -375,6 +398,8 @@ export class TradeContext extends PoolContext {
                 this.currency,
                 this.SWAP_AUTHORITY.publicKey,
                 actualSwapIn,
+                [],
+                this.currencyTokenProgram,
             ),
 
             createMintToInstruction(
@@ -410,6 +435,8 @@ export class TradeContext extends PoolContext {
                 poolAtaA,
                 this.program.provider.publicKey,
                 actualSwapOut,
+                [],
+                this.currencyTokenProgram,
             )
         ]);
     }
@@ -544,7 +571,7 @@ export class TradeContext extends PoolContext {
             authority:       this.SWAP_AUTHORITY.publicKey,
             permission:      this.swapPermission,
             feeWallet:       this.feeWallet,
-            tokenProgram:    TOKEN_PROGRAM_ID,
+            tokenProgram:    this.currencyTokenProgram,
         }).instruction();
     };
 
@@ -557,7 +584,7 @@ export class TradeContext extends PoolContext {
                 collateralVault: this.longPoolCollateralVault,
                 currencyVault:   this.longPoolCurrencyVault,
                 position:        this.longPosition,
-                tokenProgram:    TOKEN_PROGRAM_ID,
+                tokenProgram:    this.currencyTokenProgram,
             }).instruction();
     };
 
@@ -589,7 +616,7 @@ export class TradeContext extends PoolContext {
             authority:                  this.SWAP_AUTHORITY.publicKey,
             permission:                 this.swapPermission,
             feeWallet:                  this.feeWallet,
-            currencyTokenProgram:       TOKEN_PROGRAM_ID,
+            currencyTokenProgram:       this.currencyTokenProgram,
             collateralTokenProgram:     TOKEN_PROGRAM_ID,
         }).instruction();
     };
@@ -655,11 +682,29 @@ export class TradeContext extends PoolContext {
                 feeWallet:              this.feeWallet,
                 liquidationWallet:      this.liquidationWallet,
                 collateralTokenProgram: TOKEN_PROGRAM_ID,
-                currencyTokenProgram:   TOKEN_PROGRAM_ID,
+                currencyTokenProgram:   this.currencyTokenProgram,
+                withdrawalQueue:        this.queueVault ? this.withdrawalQueue : null,
+                queueVault:             this.queueVault,
             }
         }).instruction();
     };
 
+    async claimLongPosition() {
+        return await this.program.methods.claimPosition().accountsPartial({
+            trader:                  this.program.provider.publicKey,
+            traderCurrencyAccount:   this.ownerCurrencyAta,
+            traderCollateralAccount: this.ownerCollateralAta,
+            position:                this.longPosition,
+            pool:                    this.longPool,
+            collateral:              this.collateral,
+            currency:                this.currency,
+            lpVault:                 this.lpVault,
+            feeWallet:               this.feeWallet,
+            collateralTokenProgram:  TOKEN_PROGRAM_ID,
+            currencyTokenProgram:    this.currencyTokenProgram,
+        }).rpc();
+    };
+
     async closeShortPositionSetup({
         minOut,
         interest,
@@ -702,7 +747,9 @@ export class TradeContext extends PoolContext {
                 feeWallet:              this.feeWallet,
                 liquidationWallet:      this.liquidationWallet,
                 collateralTokenProgram: TOKEN_PROGRAM_ID,
-                currencyTokenProgram:   TOKEN_PROGRAM_ID,
+                currencyTokenProgram:   this.currencyTokenProgram,
+                withdrawalQueue:        this.queueVault ? this.withdrawalQueue : null,
+                queueVault:             this.queueVault,
             }
         }).instruction();
     };
@@ -1,6 +1,6 @@
 import * as anchor from '@coral-xyz/anchor';
 import {assert} from "chai";
-import {TOKEN_PROGRAM_ID} from "@solana/spl-token";
+import {TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID} from "@solana/spl-token";
 import {getMultipleTokenAccounts} from "../utils";
 import {
     TradeContext,
@@ -308,7 +308,10 @@ export const validateCloseLongPosition = async (ctx: TradeContext, {
 }: ClosePositionArgs = defaultCloseLongPositionArgs) => {
     try {
         // Get position before closing
-        const positionBefore = await ctx.program.account.position.fetch(ctx.longPosition);
+        const [positionBefore, lpVaultBefore] = await Promise.all([
+            ctx.program.account.position.fetch(ctx.longPosition),
+            ctx.program.account.lpVault.fetch(ctx.lpVault),
+        ]);
 
         // Get token account balances before closing
         const [vaultBefore, ownerTokenABefore, feeBalanceBefore] = await getMultipleTokenAccounts(
@@ -318,7 +321,7 @@ export const validateCloseLongPosition = async (ctx: TradeContext, {
                 ctx.ownerCurrencyAta,
                 ctx.feeWallet,
             ],
-            TOKEN_PROGRAM_ID
+            ctx.currencyTokenProgram
         );
 
         // Close the position
@@ -336,18 +339,27 @@ export const validateCloseLongPosition = async (ctx: TradeContext, {
                 ctx.ownerCurrencyAta,
                 ctx.feeWallet,
             ],
-            TOKEN_PROGRAM_ID
+            ctx.currencyTokenProgram
         );
 
-        // Verify LP vault received principal + interest
-        const expectedLpVaultDiff = positionBefore.principal.add(new anchor.BN(interest.toString()));
+        // Verify LP vault received principal + interest, net of any transfer fee
+        const repaid = BigInt(positionBefore.principal.toString()) + interest;
+        const transferFee = ctx.transferFee(repaid);
         const vaultDiff = vaultAfter.amount - vaultBefore.amount;
         assert.equal(
             vaultDiff.toString(),
-            expectedLpVaultDiff.toString(),
+            (repaid - transferFee).toString(),
             "LP vault should receive principal + interest"
         );
 
+        // The transfer fee is a loss for the LP vault
+        const lpVaultAfter = await ctx.program.account.lpVault.fetch(ctx.lpVault);
+        assert.equal(
+            lpVaultAfter.totalAssets.sub(lpVaultBefore.totalAssets).toString(),
+            (interest - transferFee).toString(),
+            "LP vault total assets should grow by the interest received"
+        );
+
         // Verify user received payout in currency
         const ownerADiff = ownerTokenAAfter.amount - ownerTokenABefore.amount;
         assert.isTrue(ownerADiff > BigInt(0), "User should receive payout in currency");
@@ -456,3 +468,130 @@ export const validateCloseShortPosition = async (ctx: TradeContext, {
     }
 }
 
+export const validateClaimLongPosition = async (ctx: TradeContext) => {
+    try {
+        const [positionBefore, lpVaultBefore] = await Promise.all([
+            ctx.program.account.position.fetch(ctx.longPosition),
+            ctx.program.account.lpVault.fetch(ctx.lpVault),
+        ]);
+        const [vaultBefore, ownerCurrencyBefore] = await getMultipleTokenAccounts(
+            ctx.program.provider.connection,
+            [ctx.vault, ctx.ownerCurrencyAta],
+            ctx.currencyTokenProgram
+        );
+        const [ownerCollateralBefore] = await getMultipleTokenAccounts(
+            ctx.program.provider.connection,
+            [ctx.ownerCollateralAta],
+            TOKEN_PROGRAM_ID
+        );
+
+        await ctx.claimLongPosition();
+
+        const positionAfter = await ctx.program.account.position.fetchNullable(ctx.longPosition);
+        assert.isNull(positionAfter, "Position should be closed");
+
+        const [vaultAfter, ownerCurrencyAfter] = await getMultipleTokenAccounts(
+            ctx.program.provider.connection,
+            [ctx.vault, ctx.ownerCurrencyAta],
+            ctx.currencyTokenProgram
+        );
+        const [ownerCollateralAfter] = await getMultipleTokenAccounts(
+            ctx.program.provider.connection,
+            [ctx.ownerCollateralAta],
+            TOKEN_PROGRAM_ID
+        );
+        const lpVaultAfter = await ctx.program.account.lpVault.fetch(ctx.lpVault);
+
+        // The trader pays the principal, interest and close fee, and receives the collateral
+        const owed = ownerCurrencyBefore.amount - ownerCurrencyAfter.amount
+            - BigInt(positionBefore.feesToBePaid.toString());
+        assert.equal(
+            (ownerCollateralAfter.amount - ownerCollateralBefore.amount).toString(),
+            positionBefore.collateralAmount.toString()
+        );
+
+        // The LP vault only receives the repayment net of any transfer fee
+        const vaultDiff = vaultAfter.amount - vaultBefore.amount;
+        assert.equal(vaultDiff.toString(), (owed - ctx.transferFee(owed)).toString());
+        assert.equal(
+            lpVaultAfter.totalAssets.sub(lpVaultBefore.totalAssets).toString(),
+            (vaultDiff - BigInt(positionBefore.principal.toString())).toString()
+        );
+    } catch (err) {
+        console.error("Error in validateClaimLongPosition:", err);
+        throw err;
+    }
+}
+
+export const validateCloseLongPositionFillsQueuedWithdrawal = async (ctx: TradeContext) => {
+    try {
+        // Queue every LP share while the position still holds part of the vault's principal
+        const queueBefore = await ctx.program.account.withdrawalQueue.fetch(ctx.withdrawalQueue);
+        const id = BigInt(queueBefore.nextRequestId.toString());
+        const [ownerShares] = await getMultipleTokenAccounts(
+            ctx.program.provider.connection,
+            [ctx.ownerSharesAta],
+            TOKEN_2022_PROGRAM_ID
+        );
+        await ctx.requestWithdrawal(ownerShares.amount);
+
+        // The unborrowed tokens are reserved, which leaves the lent principal short
+        const [queueRequested, lpVaultRequested] = await Promise.all([
+            ctx.program.account.withdrawalQueue.fetch(ctx.withdrawalQueue),
+            ctx.program.account.lpVault.fetch(ctx.lpVault),
+        ]);
+        assert.isTrue(queueRequested.reservedAssets.lt(queueRequested.pendingAssets));
+        assert.equal(
+            lpVaultRequested.queueReservedAssets.toString(),
+            queueRequested.reservedAssets.toString()
+        );
+        try {
+            await ctx.fillWithdrawalQueue();
+            assert.ok(false, "Fill should fail until the principal is repaid");
+        } catch (err) {
+            assert.match(err.toString(), /6014|InsufficientAvailablePrincipal/);
+        }
+
+        await ctx.closeLongPosition();
+
+        // The repayment completes the reserve
+        const [queueRepaid, lpVaultRepaid] = await Promise.all([
+            ctx.program.account.withdrawalQueue.fetch(ctx.withdrawalQueue),
+            ctx.program.account.lpVault.fetch(ctx.lpVault),
+        ]);
+        assert.equal(queueRepaid.reservedAssets.toString(), queueRepaid.pendingAssets.toString());
+        assert.equal(
+            lpVaultRepaid.queueReservedAssets.toString(),
+            queueRepaid.reservedAssets.toString()
+        );
+
+        await ctx.fillWithdrawalQueue();
+
+        const [request, lpVaultFilled, [ownerCurrencyFilled]] = await Promise.all([
+            ctx.program.account.withdrawalRequest.fetch(ctx.getWithdrawalRequest(id)),
+            ctx.program.account.lpVault.fetch(ctx.lpVault),
+            getMultipleTokenAccounts(
+                ctx.program.provider.connection,
+                [ctx.ownerCurrencyAta],
+                TOKEN_PROGRAM_ID
+            ),
+        ]);
+        assert.isTrue(request.assets.gte(queueRepaid.pendingAssets));
+        assert.equal(lpVaultFilled.queueReservedAssets.toString(), "0");
+
+        await ctx.claimWithdrawal(id);
+
+        const [ownerCurrencyClaimed] = await getMultipleTokenAccounts(
+            ctx.program.provider.connection,
+            [ctx.ownerCurrencyAta],
+            TOKEN_PROGRAM_ID
+        );
+        assert.equal(
+            (ownerCurrencyClaimed.amount - ownerCurrencyFilled.amount).toString(),
+            request.assets.toString()
+        );
+    } catch (err) {
+        console.error("Error in validateCloseLongPositionFillsQueuedWithdrawal:", err);
+        throw err;
+    }
+}
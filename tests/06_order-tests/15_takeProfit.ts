@@ -6,7 +6,10 @@ import {
     cancelTakeProfitOrderWithUser,
     cancelTakeProfitOrderWithAdmin,
     executeTakeProfitOrderWithInvalidAuthority,
-    executeTakeProfitOrderWithInvalidTakerAmount
+    executeTakeProfitOrderWithInvalidTakerAmount,
+    executeTakeProfitOrderBelowOrderPrice,
+    initTakeProfitOrderWithBadDebt,
+    initTakeProfitOrderAboveMaxExecutionFee,
 } from './validateOrder';
 
 describe("TakeProfit", () => {
@@ -18,6 +21,14 @@ describe("TakeProfit", () => {
             longCtx = await new OrderContext().generateLongOrderTest();
         });
 
+        it("should fail to init a TP order that would leave bad debt", async () => {
+            await initTakeProfitOrderWithBadDebt(longCtx);
+        });
+
+        it("should fail to escrow an execution fee above the maximum", async () => {
+            await initTakeProfitOrderAboveMaxExecutionFee(longCtx);
+        });
+
         it("should init the TP order", async () => {
             await validateInitTakeProfitOrder(longCtx);
         });
@@ -42,6 +53,10 @@ describe("TakeProfit", () => {
             await executeTakeProfitOrderWithInvalidTakerAmount(longCtx);
         });
 
+        it("should fail when the swap is below the TP order price", async () => {
+            await executeTakeProfitOrderBelowOrderPrice(longCtx);
+        });
+
         it("should execute TP order", async () => {
             await validateExecuteTakeProfitOrder(longCtx);
         });
@@ -52,6 +67,10 @@ describe("TakeProfit", () => {
             shortCtx = await new OrderContext().generateShortOrderTest();
         });
 
+        it("should fail to init a TP order that would leave bad debt", async () => {
+            await initTakeProfitOrderWithBadDebt(shortCtx);
+        });
+
         it("should init the TP order", async () => {
             await validateInitTakeProfitOrder(shortCtx);
         });
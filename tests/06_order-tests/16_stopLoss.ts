@@ -6,7 +6,8 @@ import {
     cancelStopLossOrderWithUser,
     cancelStopLossOrderWithAdmin,
     executeStopLossOrderWithInvalidAuthority,
-    executeStopLossOrderWithInvalidTakerAmount
+    executeStopLossOrderWithInvalidTakerAmount,
+    initStopLossOrderWithZeroAmount,
 } from './validateOrder';
 
 describe("StopLoss", () => {
@@ -18,6 +19,10 @@ describe("StopLoss", () => {
             longCtx = await new OrderContext().generateLongOrderTest();
         });
 
+        it("should fail to init a SL order with a zero taker amount", async () => {
+            await initStopLossOrderWithZeroAmount(longCtx);
+        });
+
         it("should init the SL order", async () => {
             await initStopLossOrder(longCtx);
         });
@@ -52,6 +57,10 @@ describe("StopLoss", () => {
             shortCtx = await new OrderContext().generateShortOrderTest();
         });
 
+        it("should fail to init a SL order with a zero taker amount", async () => {
+            await initStopLossOrderWithZeroAmount(shortCtx);
+        });
+
         it("should init the SL order", async () => {
             await initStopLossOrder(shortCtx);
         });
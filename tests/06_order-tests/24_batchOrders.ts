@@ -0,0 +1,31 @@
+import {OrderContext} from "./orderContext";
+import {batchUpdateExitOrdersWithInvalidAccounts, validateBatchUpdateExitOrders} from './validateOrder';
+
+describe("BatchOrders", () => {
+    let longCtx: OrderContext;
+    let shortCtx: OrderContext;
+
+    describe("Long position", () => {
+        before(async () => {
+            longCtx = await new OrderContext().generateLongOrderTest();
+        });
+
+        it("should reject an empty batch or unused remaining accounts", async () => {
+            await batchUpdateExitOrdersWithInvalidAccounts(longCtx);
+        });
+
+        it("should create and cancel TP and SL orders in one instruction", async () => {
+            await validateBatchUpdateExitOrders(longCtx);
+        });
+    });
+
+    describe("Short position", () => {
+        before(async () => {
+            shortCtx = await new OrderContext().generateShortOrderTest();
+        });
+
+        it("should create and cancel TP and SL orders in one instruction", async () => {
+            await validateBatchUpdateExitOrders(shortCtx);
+        });
+    });
+});
@@ -0,0 +1,61 @@
+import {OrderContext} from "./orderContext";
+import {
+    validateInitLimitOpenOrder,
+    validateCancelLimitOpenOrder,
+    validateExecuteLimitOpenOrder,
+    executeLimitOpenOrderBelowLimitPrice,
+    limitOpenOrdersWhilePoolPaused,
+} from './validateOrder';
+
+describe("LimitOpenOrders", () => {
+    let longCtx: OrderContext;
+    let shortCtx: OrderContext;
+
+    describe("Long pool", () => {
+        before(async () => {
+            longCtx = await new OrderContext().generateLongTest();
+        });
+
+        it("should init the limit open order", async () => {
+            await validateInitLimitOpenOrder(longCtx);
+        });
+
+        it("should cancel a limit open order and refund the trader", async () => {
+            await validateCancelLimitOpenOrder(longCtx);
+        });
+
+        it("should only take limit open orders while the pool is not reduce-only", async () => {
+            await limitOpenOrdersWhilePoolPaused(longCtx);
+        });
+
+        it("should fail when the swap is below the limit price", async () => {
+            await executeLimitOpenOrderBelowLimitPrice(longCtx);
+        });
+
+        it("should execute the limit open order", async () => {
+            await validateExecuteLimitOpenOrder(longCtx);
+        });
+    });
+
+    describe("Short pool", () => {
+        before(async () => {
+            shortCtx = await new OrderContext().generateShortTest();
+        });
+
+        it("should init the limit open order", async () => {
+            await validateInitLimitOpenOrder(shortCtx);
+        });
+
+        it("should cancel a limit open order and refund the trader", async () => {
+            await validateCancelLimitOpenOrder(shortCtx);
+        });
+
+        it("should fail when the swap is below the limit price", async () => {
+            await executeLimitOpenOrderBelowLimitPrice(shortCtx);
+        });
+
+        it("should execute the limit open order", async () => {
+            await validateExecuteLimitOpenOrder(shortCtx);
+        });
+    });
+});
@@ -2,11 +2,12 @@ import * as anchor from '@coral-xyz/anchor';
 import {PublicKey, TransactionInstruction} from '@solana/web3.js';
 import {TradeContext} from '../04_trade-tests/tradeContext';
 import {WASABI_PROGRAM_ID} from "../hooks/rootHook";
-import {TOKEN_PROGRAM_ID} from "@solana/spl-token";
+import {getAssociatedTokenAddressSync, TOKEN_PROGRAM_ID} from "@solana/spl-token";
 
 export interface OrderInitArgs {
     makerAmount?: bigint;
     takerAmount?: bigint;
+    executionFee?: bigint;
 }
 
 export interface OrderArgs {
@@ -18,12 +19,14 @@ export interface OrderArgs {
 
 export const defaultInitTakeProfitOrderArgs = <OrderInitArgs>{
     makerAmount: BigInt(100),
-    takerAmount: BigInt(200),
+    takerAmount: BigInt(1_100),
+    executionFee: BigInt(11),
 }
 
 export const defaultInitStopLossOrderArgs = <OrderInitArgs>{
     makerAmount: BigInt(100),
     takerAmount: BigInt(2000),
+    executionFee: BigInt(11),
 }
 
 export const defaultLongTakeProfitOrderArgs = <OrderArgs>{
@@ -54,6 +57,38 @@ export const defaultShortStopLossOrderArgs = <OrderArgs>{
     swapOut:      BigInt(1001),
 };
 
+export interface LimitOpenOrderArgs {
+    downPayment: bigint;
+    fee: bigint;
+    leverage: bigint;
+    makerAmount: bigint;
+    takerAmount: bigint;
+    principal?: bigint; // currency borrowed for short pools
+    swapIn?: bigint;
+    swapOut?: bigint;
+}
+
+export const defaultLongLimitOpenOrderArgs = <LimitOpenOrderArgs>{
+    downPayment: BigInt(1_000),
+    fee:         BigInt(10),
+    leverage:    BigInt(200),
+    makerAmount: BigInt(2_000),
+    takerAmount: BigInt(1_900),
+    swapIn:      BigInt(2_000),
+    swapOut:     BigInt(1_900),
+};
+
+export const defaultShortLimitOpenOrderArgs = <LimitOpenOrderArgs>{
+    downPayment: BigInt(100),
+    fee:         BigInt(10),
+    leverage:    BigInt(200),
+    makerAmount: BigInt(1_000),
+    takerAmount: BigInt(100),
+    principal:   BigInt(1_000),
+    swapIn:      BigInt(1_000),
+    swapOut:     BigInt(100),
+};
+
 export class OrderContext extends TradeContext {
     takeProfitListener: number;
     stopLossListener: number;
@@ -131,24 +166,64 @@ export class OrderContext extends TradeContext {
     async initTakeProfitOrder({
         makerAmount,
         takerAmount,
+        executionFee = BigInt(11),
     }: OrderInitArgs) {
         const position = this.isLongTest ? this.longPosition : this.shortPosition;
         const takeProfitOrder = this.isLongTest ? this.longTakeProfitOrder : this.shortTakeProfitOrder;
 
         return await this.program.methods
-            .initOrUpdateTakeProfitOrder(
+            .initOrUpdateTakeProfitOrderWithFee(
                 new anchor.BN(makerAmount.toString()),
-                new anchor.BN(takerAmount.toString())
+                new anchor.BN(takerAmount.toString()),
+                new anchor.BN(executionFee.toString()),
             )
             .accountsPartial({
                 //@ts-ignore
                 trader: this.program.provider.publicKey,
                 position,
+                pool: this.isLongTest ? this.longPool : this.shortPool,
+                currency: this.currency,
+                collateral: this.collateral,
                 takeProfitOrder,
             })
             .rpc();
     }
 
+    /// Updates the TP order's amounts with the instruction that keeps its escrowed execution fee
+    async updateTakeProfitOrderAmounts(makerAmount: bigint, takerAmount: bigint) {
+        return await this.program.methods
+            .initOrUpdateTakeProfitOrder(
+                new anchor.BN(makerAmount.toString()),
+                new anchor.BN(takerAmount.toString()),
+            )
+            .accountsPartial({
+                trader:          this.program.provider.publicKey,
+                position:        this.isLongTest ? this.longPosition : this.shortPosition,
+                pool:            this.isLongTest ? this.longPool : this.shortPool,
+                currency:        this.currency,
+                collateral:      this.collateral,
+                takeProfitOrder: this.isLongTest ? this.longTakeProfitOrder : this.shortTakeProfitOrder,
+            })
+            .rpc();
+    }
+
+    async initTakeProfitOrderAtPrice(price: bigint, decimals: number, executionFee = BigInt(11)) {
+        return await this.program.methods
+            .initOrUpdateTakeProfitOrderAtPrice(
+                {price: new anchor.BN(price.toString()), decimals},
+                new anchor.BN(executionFee.toString()),
+            )
+            .accountsPartial({
+                trader:          this.program.provider.publicKey,
+                position:        this.isLongTest ? this.longPosition : this.shortPosition,
+                pool:            this.isLongTest ? this.longPool : this.shortPool,
+                currency:        this.currency,
+                collateral:      this.collateral,
+                takeProfitOrder: this.isLongTest ? this.longTakeProfitOrder : this.shortTakeProfitOrder,
+            })
+            .rpc();
+    }
+
     async executeTakeProfitOrder({
         interest,
         executionFee,
@@ -205,6 +280,7 @@ export class OrderContext extends TradeContext {
                     permission:   this.swapPermission,
                     tokenProgram: TOKEN_PROGRAM_ID,
                 },
+                takeProfitOrder: this.isLongTest ? this.longTakeProfitOrder : this.shortTakeProfitOrder,
             })
             .instruction();
     }
@@ -256,22 +332,66 @@ export class OrderContext extends TradeContext {
         }
     }
 
+    // Batch order methods
+    async batchUpdateExitOrders(
+        ops: { takeProfit: boolean, cancel: boolean, args?: OrderInitArgs }[],
+        extraAccounts: anchor.web3.PublicKey[] = [],
+    ) {
+        const [position, pool, takeProfitOrder, stopLossOrder] = this.isLongTest ?
+            [this.longPosition, this.longPool, this.longTakeProfitOrder, this.longStopLossOrder] :
+            [this.shortPosition, this.shortPool, this.shortTakeProfitOrder, this.shortStopLossOrder];
+
+        const remainingAccounts = ops.flatMap(op => {
+            const order = op.takeProfit ? takeProfitOrder : stopLossOrder;
+            return op.cancel ?
+                [position, order] :
+                [position, pool, this.currency, this.collateral, order];
+        }).concat(extraAccounts).map(pubkey => ({
+            pubkey,
+            isSigner:   false,
+            isWritable: [takeProfitOrder, stopLossOrder].some(order => order.equals(pubkey)),
+        }));
+
+        return await this.program.methods
+            .batchUpdateExitOrders(ops.map(op => ({
+                orderType: op.takeProfit ? {takeProfit: {}} : {stopLoss: {}},
+                action:    op.cancel ? {cancel: {}} : {
+                    set: {
+                        makerAmount:  new anchor.BN(op.args.makerAmount.toString()),
+                        takerAmount:  new anchor.BN(op.args.takerAmount.toString()),
+                        executionFee: new anchor.BN(op.args.executionFee.toString()),
+                    }
+                },
+            })))
+            .accounts({
+                //@ts-ignore
+                trader: this.program.provider.publicKey,
+            })
+            .remainingAccounts(remainingAccounts)
+            .rpc();
+    }
+
     // Stop Loss Order methods
     async initStopLossOrder({
         makerAmount,
         takerAmount,
+        executionFee = BigInt(11),
     }: OrderInitArgs = defaultInitStopLossOrderArgs) {
         const position = this.isLongTest ? this.longPosition : this.shortPosition;
 
         return await this.program.methods
-            .initOrUpdateStopLossOrder(
+            .initOrUpdateStopLossOrderWithFee(
                 new anchor.BN(makerAmount.toString()),
                 new anchor.BN(takerAmount.toString()),
+                new anchor.BN(executionFee.toString()),
             )
             .accounts({
                 //@ts-ignore
-                trader:   this.program.provider.publicKey,
-                position: position,
+                trader:     this.program.provider.publicKey,
+                position:   position,
+                pool:       this.isLongTest ? this.longPool : this.shortPool,
+                currency:   this.currency,
+                collateral: this.collateral,
             })
             .rpc();
     }
@@ -334,6 +454,7 @@ export class OrderContext extends TradeContext {
                     permission:   this.swapPermission,
                     tokenProgram: TOKEN_PROGRAM_ID,
                 },
+                stopLossOrder: this.isLongTest ? this.longStopLossOrder : this.shortStopLossOrder,
             })
             .instruction();
     }
@@ -383,4 +504,176 @@ export class OrderContext extends TradeContext {
             return await baseMethodCall.rpc();
         }
     }
+
+    // Limit open order methods
+    limitOpenOrder(nonce = this.nonce) {
+        return PublicKey.findProgramAddressSync([
+            anchor.utils.bytes.utf8.encode('limit_open_order'),
+            this.program.provider.publicKey.toBuffer(),
+            (this.isLongTest ? this.longPool : this.shortPool).toBuffer(),
+            new anchor.BN(nonce).toArrayLike(Buffer, "le", 2),
+        ], WASABI_PROGRAM_ID)[0];
+    }
+
+    limitOpenOrderEscrow(nonce = this.nonce) {
+        return getAssociatedTokenAddressSync(
+            this.isLongTest ? this.currency : this.collateral,
+            this.limitOpenOrder(nonce),
+            true,
+            TOKEN_PROGRAM_ID,
+        );
+    }
+
+    async initLimitOpenOrder({
+        downPayment,
+        fee,
+        leverage,
+        makerAmount,
+        takerAmount,
+    }: LimitOpenOrderArgs = this.isLongTest ? defaultLongLimitOpenOrderArgs : defaultShortLimitOpenOrderArgs,
+    nonce = this.nonce) {
+        const expiration = Math.floor(Date.now() / 1_000 + 60 * 60);
+
+        return await this.program.methods
+            .initLimitOpenOrder({
+                nonce,
+                downPayment: new anchor.BN(downPayment.toString()),
+                fee:         new anchor.BN(fee.toString()),
+                leverage:    new anchor.BN(leverage.toString()),
+                makerAmount: new anchor.BN(makerAmount.toString()),
+                takerAmount: new anchor.BN(takerAmount.toString()),
+                expiration:  new anchor.BN(expiration),
+            })
+            .accountsPartial({
+                trader:               this.program.provider.publicKey,
+                traderPaymentAccount: this.isLongTest ? this.ownerCurrencyAta : this.ownerCollateralAta,
+                pool:                 this.isLongTest ? this.longPool : this.shortPool,
+                lpVault:              this.lpVault,
+                paymentMint:          this.isLongTest ? this.currency : this.collateral,
+                limitOpenOrder:       this.limitOpenOrder(nonce),
+                escrow:               this.limitOpenOrderEscrow(nonce),
+                tokenProgram:         TOKEN_PROGRAM_ID,
+            })
+            .rpc();
+    }
+
+    async executeLimitOpenOrder({
+        fee,
+        principal,
+        swapIn,
+        swapOut,
+    }: LimitOpenOrderArgs = this.isLongTest ? defaultLongLimitOpenOrderArgs : defaultShortLimitOpenOrderArgs,
+    authority = this.SWAP_AUTHORITY) {
+        const instructions = await Promise.all([
+            this.executeLimitOpenOrderSetup({principal: principal || BigInt(0), fee}, authority),
+            this.isLongTest ?
+                this.createABSwapIx({
+                    swapIn,
+                    swapOut,
+                    poolAtaA: this.longPoolCurrencyVault,
+                    poolAtaB: this.longPoolCollateralVault
+                }) :
+                this.createABSwapIx({
+                    swapIn,
+                    swapOut,
+                    poolAtaA: this.shortPoolCurrencyVault,
+                    poolAtaB: this.shortPoolCollateralVault
+                }),
+            this.executeLimitOpenOrderCleanup(authority),
+        ]).then(ixes => ixes.flatMap((ix: TransactionInstruction) => ix));
+
+        return await this.send(instructions, authority);
+    }
+
+    async executeLimitOpenOrderSetup({
+        principal,
+        fee,
+    }: {
+        principal: bigint,
+        fee: bigint,
+    }, authority = this.SWAP_AUTHORITY) {
+        const [pool, position, currencyVault, collateralVault] = this.isLongTest ?
+            [this.longPool, this.longPosition, this.longPoolCurrencyVault, this.longPoolCollateralVault] :
+            [this.shortPool, this.shortPosition, this.shortPoolCurrencyVault, this.shortPoolCollateralVault];
+
+        return await this.program.methods
+            .executeLimitOpenOrderSetup(
+                new anchor.BN(principal.toString()),
+                new anchor.BN(fee.toString()),
+            )
+            .accountsPartial({
+                authority:              authority.publicKey,
+                permission:             authority === this.SWAP_AUTHORITY ? this.swapPermission : this.nonSwapPermission,
+                trader:                 this.program.provider.publicKey,
+                limitOpenOrder:         this.limitOpenOrder(),
+                escrow:                 this.limitOpenOrderEscrow(),
+                lpVault:                this.lpVault,
+                vault:                  this.vault,
+                pool,
+                collateralVault,
+                currencyVault,
+                currency:               this.currency,
+                collateral:             this.collateral,
+                position,
+                feeWallet:              this.feeWallet,
+                currencyTokenProgram:   TOKEN_PROGRAM_ID,
+                collateralTokenProgram: TOKEN_PROGRAM_ID,
+            })
+            .instruction();
+    }
+
+    async executeLimitOpenOrderCleanup(authority = this.SWAP_AUTHORITY) {
+        const [pool, position, currencyVault, collateralVault, traderPaymentAccount, escrowMint] = this.isLongTest ?
+            [
+                this.longPool,
+                this.longPosition,
+                this.longPoolCurrencyVault,
+                this.longPoolCollateralVault,
+                this.ownerCurrencyAta,
+                this.currency,
+            ] :
+            [
+                this.shortPool,
+                this.shortPosition,
+                this.shortPoolCurrencyVault,
+                this.shortPoolCollateralVault,
+                this.ownerCollateralAta,
+                this.collateral,
+            ];
+
+        return await this.program.methods
+            .executeLimitOpenOrderCleanup()
+            .accountsPartial({
+                authority:            authority.publicKey,
+                trader:               this.program.provider.publicKey,
+                traderPaymentAccount,
+                limitOpenOrder:       this.limitOpenOrder(),
+                escrow:               this.limitOpenOrderEscrow(),
+                escrowMint,
+                position,
+                pool,
+                collateralVault,
+                currencyVault,
+                currency:             this.currency,
+                lpVault:              this.lpVault,
+                vault:                this.vault,
+                currencyTokenProgram: TOKEN_PROGRAM_ID,
+                escrowTokenProgram:   TOKEN_PROGRAM_ID,
+            })
+            .instruction();
+    }
+
+    async cancelLimitOpenOrder(nonce = this.nonce) {
+        return await this.program.methods
+            .cancelLimitOpenOrder()
+            .accountsPartial({
+                trader:               this.program.provider.publicKey,
+                traderPaymentAccount: this.isLongTest ? this.ownerCurrencyAta : this.ownerCollateralAta,
+                limitOpenOrder:       this.limitOpenOrder(nonce),
+                escrow:               this.limitOpenOrderEscrow(nonce),
+                paymentMint:          this.isLongTest ? this.currency : this.collateral,
+                tokenProgram:         TOKEN_PROGRAM_ID,
+            })
+            .rpc();
+    }
 }
@@ -1,5 +1,5 @@
 import {assert} from "chai";
-import {TOKEN_PROGRAM_ID} from "@solana/spl-token";
+import {createMintToInstruction, TOKEN_PROGRAM_ID} from "@solana/spl-token";
 import {getMultipleTokenAccounts} from "../utils";
 import {
     OrderContext,
@@ -7,7 +7,10 @@ import {
     OrderInitArgs,
     defaultInitStopLossOrderArgs,
     defaultInitTakeProfitOrderArgs,
-    defaultShortTakeProfitOrderArgs
+    defaultShortTakeProfitOrderArgs,
+    defaultLongLimitOpenOrderArgs,
+    defaultShortLimitOpenOrderArgs,
+    LimitOpenOrderArgs,
 } from "./orderContext";
 import {
     defaultLongTakeProfitOrderArgs,
@@ -17,6 +20,7 @@ import {
 import * as anchor from '@coral-xyz/anchor';
 import {TransactionInstruction} from "@solana/web3.js";
 import {owner} from "../../scripts/raydiumConfig";
+import {superAdminProgram} from "../hooks/rootHook";
 
 export const validateExecuteTakeProfitOrder = async (
     ctx: OrderContext, {
@@ -251,6 +255,80 @@ export const initStopLossOrder = async (ctx: OrderContext, {
     return order;
 }
 
+export const initTakeProfitOrderWithBadDebt = async (ctx: OrderContext) => {
+    try {
+        // The position can't repay its principal at this price
+        await ctx.initTakeProfitOrder({
+            makerAmount: BigInt(100),
+            takerAmount: ctx.isLongTest ? BigInt(200) : BigInt(100),
+        });
+        assert.fail("Should have failed with bad debt");
+    } catch (err) {
+        if (/6011/.test(err.toString()) || /BadDebt/.test(err.toString())) {
+            assert.ok(true);
+        } else {
+            console.error(err);
+            assert.ok(false);
+        }
+    }
+}
+
+const setMaxExitOrderExecutionFee = async (maxExecutionFee: bigint) => {
+    await superAdminProgram.methods
+        .setMaxExitOrderExecutionFee(new anchor.BN(maxExecutionFee.toString()))
+        .accounts({
+            authority: superAdminProgram.provider.publicKey,
+        })
+        .rpc();
+}
+
+export const initTakeProfitOrderAboveMaxExecutionFee = async (ctx: OrderContext) => {
+    const takeProfitOrder = ctx.isLongTest ? ctx.longTakeProfitOrder : ctx.shortTakeProfitOrder;
+    const {makerAmount, takerAmount} = defaultInitTakeProfitOrderArgs;
+
+    await setMaxExitOrderExecutionFee(BigInt(10));
+    try {
+        try {
+            await ctx.initTakeProfitOrder({makerAmount, takerAmount, executionFee: BigInt(11)});
+            assert.fail("Should have failed with the execution fee above the maximum");
+        } catch (err) {
+            if (/6054/.test(err.toString()) || /InvalidFee/.test(err.toString())) {
+                assert.ok(true);
+            } else {
+                console.error(err);
+                assert.ok(false);
+            }
+        }
+
+        await ctx.initTakeProfitOrder({makerAmount, takerAmount, executionFee: BigInt(10)});
+
+        // Updating the amounts without a fee keeps the escrowed fee
+        await ctx.updateTakeProfitOrderAmounts(makerAmount, takerAmount + BigInt(1));
+        const order = await ctx.program.account.takeProfitOrder.fetch(takeProfitOrder);
+        assert.equal(order.takerAmount.toString(), (takerAmount + BigInt(1)).toString());
+        assert.equal(order.executionFee.toString(), "10", "Execution fee should be unchanged");
+    } finally {
+        await setMaxExitOrderExecutionFee(BigInt("18446744073709551615"));
+    }
+}
+
+export const initStopLossOrderWithZeroAmount = async (ctx: OrderContext) => {
+    try {
+        await ctx.initStopLossOrder({
+            makerAmount: BigInt(100),
+            takerAmount: BigInt(0),
+        });
+        assert.fail("Should have failed with zero amount");
+    } catch (err) {
+        if (/6031/.test(err.toString()) || /ZeroAmount/.test(err.toString())) {
+            assert.ok(true);
+        } else {
+            console.error(err);
+            assert.ok(false);
+        }
+    }
+}
+
 export const cancelTakeProfitOrderWithInvalidPermission = async (ctx: OrderContext) => {
     const position = ctx.isLongTest ? ctx.longPosition : ctx.shortPosition;
     const takeProfitOrder = ctx.isLongTest ? ctx.longTakeProfitOrder : ctx.shortTakeProfitOrder;
@@ -418,6 +496,7 @@ export const executeTakeProfitOrderWithInvalidAuthority = async (ctx: OrderConte
                         permission:   ctx.nonSwapPermission,
                         tokenProgram: TOKEN_PROGRAM_ID,
                     },
+                    takeProfitOrder: ctx.isLongTest ? ctx.longTakeProfitOrder : ctx.shortTakeProfitOrder,
                 })
                 .instruction(),
             ctx.isLongTest ?
@@ -487,6 +566,7 @@ export const executeStopLossOrderWithInvalidAuthority = async (ctx: OrderContext
                         permission:   ctx.nonSwapPermission,
                         tokenProgram: TOKEN_PROGRAM_ID,
                     },
+                    stopLossOrder: ctx.isLongTest ? ctx.longStopLossOrder : ctx.shortStopLossOrder,
                 })
                 .instruction(),
             ctx.isLongTest ?
@@ -580,6 +660,33 @@ export const executeTakeProfitOrderWithInvalidTakerAmount = async (ctx: OrderCon
     assert.ok(order, "Take profit order should still exist");
 }
 
+export const executeTakeProfitOrderBelowOrderPrice = async (ctx: OrderContext) => {
+    const takeProfitOrder = ctx.isLongTest ? ctx.longTakeProfitOrder : ctx.shortTakeProfitOrder;
+    const args = ctx.isLongTest ? defaultLongTakeProfitOrderArgs : defaultShortTakeProfitOrderArgs;
+
+    // The swap sells the collateral at 2_100 / 1_900 (~1.105), below the 1.2 the order asks for
+    await ctx.initTakeProfitOrderAtPrice(BigInt(1_200_000_000), 9);
+
+    const order = await ctx.program.account.takeProfitOrder.fetch(takeProfitOrder);
+    assert.equal(order.price.price.toString(), "1200000000", "Price should match");
+    assert.equal(order.price.decimals, 9, "Price decimals should match");
+
+    try {
+        await ctx.executeTakeProfitOrder(args);
+        assert.fail("Should have failed with price target not reached");
+    } catch (err) {
+        if (/6017/.test(err.toString()) || /PriceTargetNotReached/.test(err.toString())) {
+            assert.ok(true);
+        } else {
+            console.error(err);
+            assert.ok(false);
+        }
+    }
+
+    const orderAfter = await ctx.program.account.takeProfitOrder.fetch(takeProfitOrder);
+    assert.ok(orderAfter, "Take profit order should still exist");
+}
+
 export const executeStopLossOrderWithInvalidTakerAmount = async (ctx: OrderContext, {
     interest,
     executionFee,
@@ -591,7 +698,7 @@ export const executeStopLossOrderWithInvalidTakerAmount = async (ctx: OrderConte
     // Initialize the stop loss order with high taker amount requirement
     await initStopLossOrder(ctx, {
         makerAmount: BigInt(100),
-        takerAmount: ctx.isLongTest ? BigInt(1000) : BigInt(500),
+        takerAmount: ctx.isLongTest ? BigInt(1050) : BigInt(600),
     });
 
     // Try to execute with insufficient swap amount
@@ -617,3 +724,255 @@ export const executeStopLossOrderWithInvalidTakerAmount = async (ctx: OrderConte
     const order = await ctx.program.account.stopLossOrder.fetch(stopLossOrder);
     assert.ok(order, "Stop loss order should still exist");
 }
+
+export const validateBatchUpdateExitOrders = async (ctx: OrderContext) => {
+    const [takeProfitOrder, stopLossOrder] = ctx.isLongTest ?
+        [ctx.longTakeProfitOrder, ctx.longStopLossOrder] :
+        [ctx.shortTakeProfitOrder, ctx.shortStopLossOrder];
+
+    // Create both orders in a single instruction
+    await ctx.batchUpdateExitOrders([
+        {takeProfit: true, cancel: false, args: defaultInitTakeProfitOrderArgs},
+        {takeProfit: false, cancel: false, args: defaultInitStopLossOrderArgs},
+    ]);
+
+    const tpOrder = await ctx.program.account.takeProfitOrder.fetch(takeProfitOrder);
+    assert.equal(tpOrder.takerAmount.toString(), defaultInitTakeProfitOrderArgs.takerAmount.toString());
+    const slOrder = await ctx.program.account.stopLossOrder.fetch(stopLossOrder);
+    assert.equal(slOrder.takerAmount.toString(), defaultInitStopLossOrderArgs.takerAmount.toString());
+
+    // Cancel both orders in a single instruction
+    await ctx.batchUpdateExitOrders([
+        {takeProfit: true, cancel: true},
+        {takeProfit: false, cancel: true},
+    ]);
+
+    assert.isNull(
+        await ctx.program.account.takeProfitOrder.fetchNullable(takeProfitOrder),
+        "Take profit order should be closed"
+    );
+    assert.isNull(
+        await ctx.program.account.stopLossOrder.fetchNullable(stopLossOrder),
+        "Stop loss order should be closed"
+    );
+}
+
+export const batchUpdateExitOrdersWithInvalidAccounts = async (ctx: OrderContext) => {
+    const takeProfitOrder = ctx.isLongTest ? ctx.longTakeProfitOrder : ctx.shortTakeProfitOrder;
+
+    try {
+        await ctx.batchUpdateExitOrders([]);
+        assert.fail("Should have failed with an empty batch");
+    } catch (err) {
+        if (/6076/.test(err.toString()) || /EmptyBatch/.test(err.toString())) {
+            assert.ok(true);
+        } else {
+            console.error(err);
+            assert.ok(false);
+        }
+    }
+
+    try {
+        await ctx.batchUpdateExitOrders(
+            [{takeProfit: true, cancel: false, args: defaultInitTakeProfitOrderArgs}],
+            [ctx.isLongTest ? ctx.longPosition : ctx.shortPosition],
+        );
+        assert.fail("Should have failed with unused remaining accounts");
+    } catch (err) {
+        if (/6077/.test(err.toString()) || /UnusedRemainingAccounts/.test(err.toString())) {
+            assert.ok(true);
+        } else {
+            console.error(err);
+            assert.ok(false);
+        }
+    }
+
+    assert.isNull(
+        await ctx.program.account.takeProfitOrder.fetchNullable(takeProfitOrder),
+        "Take profit order should not be created"
+    );
+}
+
+export const validateInitLimitOpenOrder = async (
+    ctx: OrderContext,
+    args: LimitOpenOrderArgs = ctx.isLongTest ? defaultLongLimitOpenOrderArgs : defaultShortLimitOpenOrderArgs,
+    nonce = ctx.nonce,
+) => {
+    const traderPaymentAccount = ctx.isLongTest ? ctx.ownerCurrencyAta : ctx.ownerCollateralAta;
+    const [traderBefore] = await getMultipleTokenAccounts(
+        ctx.program.provider.connection,
+        [traderPaymentAccount],
+        TOKEN_PROGRAM_ID
+    );
+
+    await ctx.initLimitOpenOrder(args, nonce);
+
+    const order = await ctx.program.account.limitOpenOrder.fetch(ctx.limitOpenOrder(nonce));
+    assert.equal(order.trader.toString(), ctx.program.provider.publicKey.toString(), "Trader should match");
+    assert.equal(order.downPayment.toString(), args.downPayment.toString(), "Down payment should match");
+    assert.equal(order.fee.toString(), args.fee.toString(), "Fee should match");
+    assert.equal(order.leverage.toString(), args.leverage.toString(), "Leverage should match");
+    assert.equal(order.makerAmount.toString(), args.makerAmount.toString(), "Maker amount should match");
+    assert.equal(order.takerAmount.toString(), args.takerAmount.toString(), "Taker amount should match");
+
+    const [traderAfter, escrow] = await getMultipleTokenAccounts(
+        ctx.program.provider.connection,
+        [traderPaymentAccount, ctx.limitOpenOrderEscrow(nonce)],
+        TOKEN_PROGRAM_ID
+    );
+    const escrowed = args.downPayment + args.fee;
+    assert.equal((traderBefore.amount - traderAfter.amount).toString(), escrowed.toString(), "Trader should pay the down payment and fee");
+    assert.equal(escrow.amount.toString(), escrowed.toString(), "Escrow should hold the down payment and fee");
+}
+
+export const validateCancelLimitOpenOrder = async (ctx: OrderContext) => {
+    // Use a separate order so the one at `ctx.nonce` can still be executed
+    const nonce = ctx.nonce + 1;
+    const args = ctx.isLongTest ? defaultLongLimitOpenOrderArgs : defaultShortLimitOpenOrderArgs;
+    const traderPaymentAccount = ctx.isLongTest ? ctx.ownerCurrencyAta : ctx.ownerCollateralAta;
+
+    await validateInitLimitOpenOrder(ctx, args, nonce);
+
+    const [traderBefore] = await getMultipleTokenAccounts(
+        ctx.program.provider.connection,
+        [traderPaymentAccount],
+        TOKEN_PROGRAM_ID
+    );
+
+    await ctx.cancelLimitOpenOrder(nonce);
+
+    const order = await ctx.program.account.limitOpenOrder.fetchNullable(ctx.limitOpenOrder(nonce));
+    assert.isNull(order, "Limit open order should be closed");
+    const escrow = await ctx.program.provider.connection.getAccountInfo(ctx.limitOpenOrderEscrow(nonce));
+    assert.isNull(escrow, "Escrow should be closed");
+
+    const [traderAfter] = await getMultipleTokenAccounts(
+        ctx.program.provider.connection,
+        [traderPaymentAccount],
+        TOKEN_PROGRAM_ID
+    );
+    assert.equal(
+        (traderAfter.amount - traderBefore.amount).toString(),
+        (args.downPayment + args.fee).toString(),
+        "Trader should be refunded the down payment and fee"
+    );
+}
+
+export const executeLimitOpenOrderBelowLimitPrice = async (ctx: OrderContext) => {
+    const args = ctx.isLongTest ? defaultLongLimitOpenOrderArgs : defaultShortLimitOpenOrderArgs;
+
+    try {
+        // One less collateral than the limit price requires
+        await ctx.executeLimitOpenOrder({...args, swapOut: args.swapOut - BigInt(1)});
+        assert.fail("Should have failed with price target not reached");
+    } catch (err) {
+        if (/6017/.test(err.toString()) || /PriceTargetNotReached/.test(err.toString())) {
+            assert.ok(true);
+        } else {
+            console.error(err);
+            assert.ok(false);
+        }
+    }
+
+    const order = await ctx.program.account.limitOpenOrder.fetch(ctx.limitOpenOrder());
+    assert.ok(order, "Limit open order should still exist");
+}
+
+export const validateExecuteLimitOpenOrder = async (ctx: OrderContext) => {
+    const args = ctx.isLongTest ? defaultLongLimitOpenOrderArgs : defaultShortLimitOpenOrderArgs;
+    const [position, traderPaymentAccount, paymentMint] = ctx.isLongTest
+        ? [ctx.longPosition, ctx.ownerCurrencyAta, ctx.currency]
+        : [ctx.shortPosition, ctx.ownerCollateralAta, ctx.collateral];
+    const escrow = ctx.limitOpenOrderEscrow();
+
+    // Tokens sent to the escrow after the order was placed are refunded on execution
+    const stray = BigInt(5);
+    await ctx.program.provider.sendAndConfirm(new anchor.web3.Transaction().add(
+        createMintToInstruction(paymentMint, escrow, ctx.program.provider.publicKey, stray, [], TOKEN_PROGRAM_ID),
+    ));
+
+    const [traderBefore, feeWalletBefore, vaultBefore] = await getMultipleTokenAccounts(
+        ctx.program.provider.connection,
+        [traderPaymentAccount, ctx.feeWallet, ctx.vault],
+        TOKEN_PROGRAM_ID
+    );
+
+    // The executor can't charge more than the fee escrowed with the order
+    try {
+        await ctx.executeLimitOpenOrder({...args, fee: args.fee + BigInt(1)});
+        assert.fail("Should have failed with insufficient order fee");
+    } catch (err) {
+        if (!/6071/.test(err.toString()) && !/InsufficientOrderFee/.test(err.toString())) {
+            console.error(err);
+            assert.ok(false);
+        }
+    }
+
+    // The part of the escrowed fee the executor doesn't charge is refunded
+    const fee = args.fee - BigInt(3);
+    await ctx.executeLimitOpenOrder({...args, fee});
+
+    const order = await ctx.program.account.limitOpenOrder.fetchNullable(ctx.limitOpenOrder());
+    assert.isNull(order, "Limit open order should be closed");
+    const escrowAfter = await ctx.program.provider.connection.getAccountInfo(escrow);
+    assert.isNull(escrowAfter, "Escrow should be closed");
+
+    const positionAfter = await ctx.program.account.position.fetch(position);
+    assert.equal(positionAfter.trader.toString(), ctx.program.provider.publicKey.toString(), "Trader should own the position");
+    assert.equal(positionAfter.downPayment.toString(), args.downPayment.toString(), "Down payment should match");
+    assert.equal(positionAfter.feesToBePaid.toString(), fee.toString(), "Fee should match");
+    if (ctx.isLongTest) {
+        assert.equal(positionAfter.collateralAmount.toString(), args.swapOut.toString(), "Collateral should match the swap");
+    } else {
+        assert.equal(
+            positionAfter.collateralAmount.toString(),
+            (args.swapOut + args.downPayment).toString(),
+            "Collateral should include the down payment"
+        );
+    }
+
+    const [traderAfter, feeWalletAfter, vaultAfter] = await getMultipleTokenAccounts(
+        ctx.program.provider.connection,
+        [traderPaymentAccount, ctx.feeWallet, ctx.vault],
+        TOKEN_PROGRAM_ID
+    );
+    assert.equal(
+        (traderAfter.amount - traderBefore.amount).toString(),
+        (stray + args.fee - fee).toString(),
+        "Trader should get the leftover escrow"
+    );
+    assert.equal((feeWalletAfter.amount - feeWalletBefore.amount).toString(), fee.toString(), "Fee wallet should receive the fee");
+    assert.equal(
+        (vaultBefore.amount - vaultAfter.amount).toString(),
+        positionAfter.principal.toString(),
+        "LP vault should lend the principal"
+    );
+}
+
+const POOL_OPENS_PAUSED = 1;
+const POOL_REDUCE_ONLY = 2;
+
+export const limitOpenOrdersWhilePoolPaused = async (ctx: OrderContext) => {
+    const args = ctx.isLongTest ? defaultLongLimitOpenOrderArgs : defaultShortLimitOpenOrderArgs;
+
+    // Orders can be placed while opens are paused, they are executed once opens resume
+    await ctx.setPoolStatus(POOL_OPENS_PAUSED);
+    await validateInitLimitOpenOrder(ctx, args, ctx.nonce + 2);
+
+    // A reduce-only pool takes no new orders, but resting ones can still be cancelled
+    await ctx.setPoolStatus(POOL_REDUCE_ONLY);
+    try {
+        await ctx.initLimitOpenOrder(args, ctx.nonce + 3);
+        assert.fail("Should have failed with pool reduce only");
+    } catch (err) {
+        if (/6052/.test(err.toString()) || /PoolReduceOnly/.test(err.toString())) {
+            assert.ok(true);
+        } else {
+            console.error(err);
+            assert.ok(false);
+        }
+    }
+    await ctx.cancelLimitOpenOrder(ctx.nonce + 2);
+
+    await ctx.setPoolStatus(0);
+}
@@ -1,4 +1,4 @@
-import {validateDeposit} from "./validateStrategy";
+import {depositOverMaxBorrow, validateDeposit} from "./validateStrategy";
 import {StrategyContext} from "./strategyContext";
 
 describe("StrategyDeposit", () => {
@@ -16,4 +16,9 @@ describe("StrategyDeposit", () => {
             await validateDeposit(ctx, {amountIn: 1_000, amountOut: 1_000});
         });
     });
+    describe("when the deposit would exceed the vault's max borrow", () => {
+        it("should fail", async () => {
+            await depositOverMaxBorrow(ctx, 1_001);
+        });
+    });
 });
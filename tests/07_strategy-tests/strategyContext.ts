@@ -12,7 +12,7 @@ import {
 } from '@solana/spl-token';
 import {
     WASABI_PROGRAM_ID,
-    superAdminProgram, DEFAULT_AUTHORITY,
+    superAdminProgram, superAdminPermission, DEFAULT_AUTHORITY,
 } from '../hooks/rootHook';
 import {TestContext} from '../testContext';
 
@@ -143,6 +143,7 @@ export class StrategyContext extends TestContext {
             canLiquidate:        false,
             canInitPools:        false,
             canBorrowFromVaults: true,
+            canManageVaults: true,
             status:              {active: {}}
         }).accounts({
             payer:        superAdminProgram.provider.publicKey,
@@ -161,6 +162,7 @@ export class StrategyContext extends TestContext {
             canLiquidate:        true,
             canInitPools:        true,
             canBorrowFromVaults: false,
+            canManageVaults: false,
             status:              {active: {}}
         }).accounts({
             payer:        superAdminProgram.provider.publicKey,
@@ -182,6 +184,8 @@ export class StrategyContext extends TestContext {
             this.strategyDepositEvent = event;
         });
 
+        await this.setMaxBorrow(3_000 * LAMPORTS_PER_SOL);
+
         // (optional) init strategy
         await this.validateSetup();
 
@@ -257,6 +261,17 @@ export class StrategyContext extends TestContext {
         };
     }
 
+    async setMaxBorrow(maxBorrow: number) {
+        await superAdminProgram.methods.updateLpVaultMaxBorrow(new anchor.BN(maxBorrow))
+            .accountsPartial({
+                payer:      superAdminProgram.provider.publicKey,
+                authority:  superAdminProgram.provider.publicKey,
+                permission: superAdminPermission,
+                lpVault:    this.lpVault,
+            })
+            .rpc();
+    }
+
     async strategyDeposit(
         this: StrategyContext,
         {
@@ -202,6 +202,20 @@ export const validateDeposit = async (
     }
 };
 
+export const depositOverMaxBorrow = async (ctx: StrategyContext, amountIn: number) => {
+    try {
+        await ctx.strategyDeposit({amountIn, amountOut: amountIn});
+        assert.fail("Should have failed with max borrow exceeded");
+    } catch (err) {
+        if (/6018/.test(err.toString()) || /MaxBorrowExceeded/.test(err.toString())) {
+            assert.ok(true);
+        } else {
+            console.error(err);
+            assert.ok(false);
+        }
+    }
+};
+
 export const validateDepositStates = async (
     beforePromise: ReturnType<typeof getAccountStates>,
     afterPromise: ReturnType<typeof getAccountStates>,
@@ -34,6 +34,7 @@ export const initWasabi = async () => {
         canLiquidate:        true,
         canInitPools:        true,
         canBorrowFromVaults: true,
+        canManageVaults: true,
         status:              {active: {}}
     }).accounts({
         payer:        superAdminProgram.provider.publicKey,
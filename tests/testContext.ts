@@ -1,5 +1,5 @@
 import {SYSVAR_INSTRUCTIONS_PUBKEY} from '@solana/web3.js';
-import {workspace, Program, web3} from '@coral-xyz/anchor';
+import {BN, workspace, Program, web3} from '@coral-xyz/anchor';
 import {WasabiSolana} from '../target/types/wasabi_solana';
 import {
     createAssociatedTokenAccountIdempotentInstruction,
@@ -7,14 +7,28 @@ import {
     getAssociatedTokenAddressSync, TOKEN_2022_PROGRAM_ID
 } from '@solana/spl-token';
 import {TOKEN_PROGRAM_ID} from '@coral-xyz/anchor/dist/cjs/utils/token';
-import {createSimpleMint, initDefaultPermission, defaultInitLpVaultArgs} from './utils';
+import {
+    createSimpleMint,
+    createTransferFeeMint,
+    initDefaultPermission,
+    defaultInitLpVaultArgs,
+} from './utils';
 import {WASABI_PROGRAM_ID} from './hooks/rootHook';
 import {MPL_TOKEN_METADATA_PROGRAM_ID} from '@metaplex-foundation/mpl-token-metadata';
 import {superAdminProgram, superAdminPermission} from './hooks/rootHook';
 
+/** Transfer fee of the currency when it is a Token-2022 mint */
+export const TRANSFER_FEE_BPS = 100; // 1%
+export const MAX_TRANSFER_FEE = BigInt(1_000_000_000);
+
 export class TestContext {
+    /** Token account of the vault's withdrawal queue, set once the queue is initialised */
+    queueVault: web3.PublicKey | null = null;
+
     constructor(
         readonly program = workspace.WasabiSolana as Program<WasabiSolana>,
+        /** A Token-2022 currency is created with the transfer fee extension */
+        readonly currencyTokenProgram = TOKEN_PROGRAM_ID,
         readonly currencyKeypair = web3.Keypair.generate(),
         readonly collateralKeypair = web3.Keypair.generate(),
         readonly currency = currencyKeypair.publicKey,
@@ -31,13 +45,13 @@ export class TestContext {
             currency,
             lpVault,
             true,
-            TOKEN_PROGRAM_ID,
+            currencyTokenProgram,
         ),
         readonly ownerCurrencyAta = getAssociatedTokenAddressSync(
             currency,
             program.provider.publicKey,
             false,
-            TOKEN_PROGRAM_ID,
+            currencyTokenProgram,
         ),
         readonly ownerCollateralAta = getAssociatedTokenAddressSync(
             collateral,
@@ -60,19 +74,28 @@ export class TestContext {
     ) {
     }
 
-    protected async _generate() {
+    protected async _generate(useTokenMetadata = false) {
         const mintTx = new web3.Transaction();
         let [
             {ixes: uIxes, mint: uMint},
             {ixes: qIxes, mint: qMint}
         ] = await Promise.all([
-            createSimpleMint(
-                this.program.provider.publicKey,
-                this.program.provider.connection,
-                6,
-                TOKEN_PROGRAM_ID,
-                this.currencyKeypair
-            ),
+            this.currencyTokenProgram.equals(TOKEN_2022_PROGRAM_ID) ?
+                createTransferFeeMint(
+                    this.program.provider.publicKey,
+                    this.program.provider.connection,
+                    6,
+                    TRANSFER_FEE_BPS,
+                    MAX_TRANSFER_FEE,
+                    this.currencyKeypair
+                ) :
+                createSimpleMint(
+                    this.program.provider.publicKey,
+                    this.program.provider.connection,
+                    6,
+                    TOKEN_PROGRAM_ID,
+                    this.currencyKeypair
+                ),
             createSimpleMint(
                 this.program.provider.publicKey,
                 this.program.provider.connection,
@@ -92,7 +115,7 @@ export class TestContext {
             this.ownerCurrencyAta,
             this.program.provider.publicKey,
             this.currency,
-            TOKEN_PROGRAM_ID,
+            this.currencyTokenProgram,
         );
 
         const ownerCollateralAtaIx = createAssociatedTokenAccountIdempotentInstruction(
@@ -110,7 +133,7 @@ export class TestContext {
             1_000_000_000 * Math.pow(10, 6),
             6,
             [],
-            TOKEN_PROGRAM_ID,
+            this.currencyTokenProgram,
         )
 
         const mintCollateralToOwner = createMintToCheckedInstruction(
@@ -139,7 +162,7 @@ export class TestContext {
             this.vault,
             this.lpVault,
             this.currency,
-            TOKEN_PROGRAM_ID
+            this.currencyTokenProgram
         );
 
         const ownerSharesAta = createAssociatedTokenAccountIdempotentInstruction(
@@ -150,6 +173,23 @@ export class TestContext {
             TOKEN_2022_PROGRAM_ID
         )
 
+        if (useTokenMetadata) {
+            return await superAdminProgram.methods
+                .initLpVaultWithTokenMetadata(defaultInitLpVaultArgs)
+                .accountsPartial({
+                    payer:             superAdminProgram.provider.publicKey,
+                    authority:         superAdminProgram.provider.publicKey,
+                    vault:             this.vault,
+                    lpVault:           this.lpVault,
+                    permission:        superAdminPermission,
+                    assetMint:         this.currency,
+                    assetTokenProgram: this.currencyTokenProgram,
+                })
+                .preInstructions([permissionIx, vaultIx])
+                .postInstructions([ownerSharesAta])
+                .rpc();
+        }
+
         return await superAdminProgram.methods
             .initLpVault(defaultInitLpVaultArgs)
             .accountsPartial({
@@ -159,7 +199,7 @@ export class TestContext {
                 lpVault:              this.lpVault,
                 permission:           superAdminPermission,
                 assetMint:            this.currency,
-                assetTokenProgram:    TOKEN_PROGRAM_ID,
+                assetTokenProgram:    this.currencyTokenProgram,
                 tokenMetadataProgram: MPL_TOKEN_METADATA_PROGRAM_ID,
                 sysvarInstructions:   SYSVAR_INSTRUCTIONS_PUBKEY,
             })
@@ -167,4 +207,102 @@ export class TestContext {
             .postInstructions([ownerSharesAta])
             .rpc();
     }
+
+    /** The fee withheld when transferring `amount` of the currency */
+    transferFee(amount: bigint): bigint {
+        if (!this.currencyTokenProgram.equals(TOKEN_2022_PROGRAM_ID)) {
+            return BigInt(0);
+        }
+        const fee = (amount * BigInt(TRANSFER_FEE_BPS) + BigInt(9_999)) / BigInt(10_000);
+        return fee > MAX_TRANSFER_FEE ? MAX_TRANSFER_FEE : fee;
+    }
+
+    get withdrawalQueue() {
+        return web3.PublicKey.findProgramAddressSync(
+            [Buffer.from("withdrawal_queue"), this.lpVault.toBuffer()],
+            this.program.programId
+        )[0];
+    }
+
+    getWithdrawalRequest(id: bigint) {
+        const idBuffer = Buffer.alloc(8);
+        idBuffer.writeBigUInt64LE(id);
+        return web3.PublicKey.findProgramAddressSync(
+            [Buffer.from("withdrawal_request"), this.lpVault.toBuffer(), idBuffer],
+            this.program.programId
+        )[0];
+    }
+
+    async initWithdrawalQueue() {
+        const signature = await superAdminProgram.methods
+            .initWithdrawalQueue()
+            .accountsPartial({
+                payer:             superAdminProgram.provider.publicKey,
+                authority:         superAdminProgram.provider.publicKey,
+                permission:        superAdminPermission,
+                lpVault:            this.lpVault,
+                assetMint:          this.currency,
+                sharesMint:         this.sharesMint,
+                assetTokenProgram:  this.currencyTokenProgram,
+                sharesTokenProgram: TOKEN_2022_PROGRAM_ID,
+            })
+            .rpc();
+        const queue = await this.program.account.withdrawalQueue.fetch(this.withdrawalQueue);
+        this.queueVault = queue.queueVault;
+        return signature;
+    }
+
+    async requestWithdrawal(sharesAmount: bigint) {
+        const queue = await this.program.account.withdrawalQueue.fetch(this.withdrawalQueue);
+        return await this.program.methods
+            .requestWithdrawal(new BN(sharesAmount.toString()))
+            .accountsPartial({
+                owner:              this.program.provider.publicKey,
+                lpVault:            this.lpVault,
+                assetMint:          this.currency,
+                withdrawalQueue:    this.withdrawalQueue,
+                queueVault:         queue.queueVault,
+                sharesEscrow:       queue.sharesEscrow,
+                withdrawalRequest:  this.getWithdrawalRequest(BigInt(queue.nextRequestId.toString())),
+                assetTokenProgram:  this.currencyTokenProgram,
+                sharesTokenProgram: TOKEN_2022_PROGRAM_ID,
+            })
+            .rpc();
+    }
+
+    async fillWithdrawalQueue() {
+        const queue = await this.program.account.withdrawalQueue.fetch(this.withdrawalQueue);
+        return await this.program.methods
+            .fillWithdrawalQueue()
+            .accountsPartial({
+                lpVault:            this.lpVault,
+                vault:              this.vault,
+                assetMint:          this.currency,
+                sharesMint:         this.sharesMint,
+                withdrawalQueue:    this.withdrawalQueue,
+                queueVault:         queue.queueVault,
+                sharesEscrow:       queue.sharesEscrow,
+                withdrawalRequest:  this.getWithdrawalRequest(BigInt(queue.nextFillId.toString())),
+                feeSharesAccount:   null,
+                assetTokenProgram:  this.currencyTokenProgram,
+                sharesTokenProgram: TOKEN_2022_PROGRAM_ID,
+            })
+            .rpc();
+    }
+
+    async claimWithdrawal(id: bigint) {
+        const queue = await this.program.account.withdrawalQueue.fetch(this.withdrawalQueue);
+        return await this.program.methods
+            .claimWithdrawal()
+            .accountsPartial({
+                owner:             this.program.provider.publicKey,
+                lpVault:           this.lpVault,
+                assetMint:         this.currency,
+                withdrawalQueue:   this.withdrawalQueue,
+                queueVault:        queue.queueVault,
+                withdrawalRequest: this.getWithdrawalRequest(id),
+                assetTokenProgram: this.currencyTokenProgram,
+            })
+            .rpc();
+    }
 }
@@ -1,7 +1,11 @@
 import {web3} from "@coral-xyz/anchor";
 import {
     createInitializeMintInstruction,
+    createInitializeTransferFeeConfigInstruction,
+    ExtensionType,
+    getMintLen,
     MintLayout,
+    TOKEN_2022_PROGRAM_ID,
     unpackAccount,
     unpackMint,
 } from "@solana/spl-token";
@@ -56,6 +60,56 @@ export const createSimpleMint = async (
     return {ixes, mint};
 };
 
+/**
+ * Ixes to create a Token-2022 mint with the transfer fee extension, the payer gains the Mint
+ * Tokens and transfer fee authorities
+ * @param payer - pays account init fees, must sign
+ * @param connection
+ * @param decimals
+ * @param feeBasisPoints - fee withheld on every transfer
+ * @param maxFee - cap on the fee withheld per transfer
+ * @param mintKeypair - (optional) generates random keypair if not provided, must sign
+ * @returns ixes, and keypair of new mint
+ */
+export const createTransferFeeMint = async (
+    payer: web3.PublicKey,
+    connection: web3.Connection,
+    decimals: number,
+    feeBasisPoints: number,
+    maxFee: bigint,
+    mintKeypair?: web3.Keypair,
+) => {
+    let mint = mintKeypair ? mintKeypair : web3.Keypair.generate();
+    const space = getMintLen([ExtensionType.TransferFeeConfig]);
+    const lamports = await connection.getMinimumBalanceForRentExemption(space);
+    const ixes: web3.TransactionInstruction[] = [
+        web3.SystemProgram.createAccount({
+            fromPubkey:       payer,
+            newAccountPubkey: mint.publicKey,
+            space,
+            lamports,
+            programId:        TOKEN_2022_PROGRAM_ID,
+        }),
+        createInitializeTransferFeeConfigInstruction(
+            mint.publicKey,
+            payer,
+            payer,
+            feeBasisPoints,
+            maxFee,
+            TOKEN_2022_PROGRAM_ID,
+        ),
+        createInitializeMintInstruction(
+            mint.publicKey,
+            decimals,
+            payer,
+            undefined,
+            TOKEN_2022_PROGRAM_ID,
+        ),
+    ];
+
+    return {ixes, mint};
+};
+
 export const getMultipleTokenAccounts = async (
     connection: web3.Connection,
     keys: web3.PublicKey[],
@@ -91,6 +145,7 @@ export const initDefaultPermission = async (newAuthority: web3.PublicKey): Promi
         canLiquidate:        true, // 2
         canInitPools:        true, // 8
         canBorrowFromVaults: true,
+        canManageVaults: true,
         status:              {active: {}}
     }).accountsPartial({
         payer: superAdminProgram.provider.publicKey,